@@ -0,0 +1,6 @@
+/// Identifies an action of the application, such as "Save" or "Copy".
+///
+/// Menu items and keyboard shortcuts report the action they invoke through it, so that the
+/// application can handle both in one place. The values are chosen by the application.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ActionId(pub u32);
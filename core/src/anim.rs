@@ -0,0 +1,9 @@
+mod animated;
+mod animation;
+mod easing;
+mod interpolate;
+
+pub use animated::Animated;
+pub use animation::{Animation, Map, Parallel, Sequence, Tween};
+pub use easing::Easing;
+pub use interpolate::Interpolate;
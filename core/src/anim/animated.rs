@@ -0,0 +1,154 @@
+use crate::anim::{Animation, Easing, Interpolate, Tween};
+use crate::time::Timestamp;
+use std::fmt::{Debug, Formatter};
+use std::time::Duration;
+
+struct Running<T> {
+    start: Timestamp,
+    animation: Box<dyn Animation<Output = T>>,
+}
+
+/// A value that can be animated.
+///
+/// The value is evaluated on demand for a given point in time, so it does not need to be updated
+/// on every frame. Call [`Animated::settle()`] every now and then (e.g. on every loop start) to
+/// release finished animations.
+pub struct Animated<T> {
+    value: T,
+    running: Option<Running<T>>,
+}
+
+impl<T> Animated<T>
+where
+    T: Clone + 'static,
+{
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            running: None,
+        }
+    }
+
+    /// The value at `now`.
+    pub fn value(&self, now: Timestamp) -> T {
+        match &self.running {
+            Some(running) => running.animation.sample(now.duration_since(running.start)),
+            None => self.value.clone(),
+        }
+    }
+
+    /// Sets the value immediately, stopping any running animation.
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+        self.running = None;
+    }
+
+    /// Starts `animation` at `now`, replacing any running animation.
+    pub fn start<A>(&mut self, animation: A, now: Timestamp)
+    where
+        A: Animation<Output = T> + 'static,
+    {
+        self.value = animation.sample(animation.duration());
+        self.running = Some(Running {
+            start: now,
+            animation: Box::new(animation),
+        });
+    }
+
+    /// Whether an animation is still in progress at `now`.
+    pub fn is_running(&self, now: Timestamp) -> bool {
+        self.end_time().is_some_and(|end| now < end)
+    }
+
+    /// The point in time at which the running animation ends, if any.
+    pub fn end_time(&self) -> Option<Timestamp> {
+        self.running
+            .as_ref()
+            .map(|running| running.start + running.animation.duration())
+    }
+
+    /// The value once the running animation has finished.
+    pub fn target(&self) -> &T {
+        &self.value
+    }
+
+    /// Drops the running animation if it has finished at `now`.
+    pub fn settle(&mut self, now: Timestamp) {
+        if !self.is_running(now) {
+            self.running = None;
+        }
+    }
+}
+
+impl<T> Animated<T>
+where
+    T: Interpolate + Clone + 'static,
+{
+    /// Transitions from the value at `now` to `target`.
+    pub fn animate_to(&mut self, target: T, now: Timestamp, duration: Duration, easing: Easing) {
+        let from = self.value(now);
+        self.start(Tween::new(from, target, duration, easing), now);
+    }
+}
+
+impl<T> Default for Animated<T>
+where
+    T: Default + Clone + 'static,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> Debug for Animated<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Animated")
+            .field("target", &self.value)
+            .field("running", &self.running.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geom::Point;
+
+    #[test]
+    fn test_animated() {
+        let t0 = Timestamp::from_millis(1000);
+        let mut pos = Animated::new(Point::new(-100, 0));
+        assert!(!pos.is_running(t0));
+        assert_eq!(Point::new(-100, 0), pos.value(t0));
+
+        pos.animate_to(
+            Point::zero(),
+            t0,
+            Duration::from_millis(100),
+            Easing::Linear,
+        );
+        assert!(pos.is_running(t0));
+        assert_eq!(Some(Timestamp::from_millis(1100)), pos.end_time());
+        assert_eq!(&Point::zero(), pos.target());
+        assert_eq!(Point::new(-50, 0), pos.value(Timestamp::from_millis(1050)));
+
+        // Retargeting starts from the current value.
+        let t1 = Timestamp::from_millis(1050);
+        pos.animate_to(
+            Point::new(-100, 0),
+            t1,
+            Duration::from_millis(50),
+            Easing::Linear,
+        );
+        assert_eq!(Point::new(-75, 0), pos.value(Timestamp::from_millis(1075)));
+
+        let t2 = Timestamp::from_millis(1100);
+        assert!(!pos.is_running(t2));
+        pos.settle(t2);
+        assert_eq!(None, pos.end_time());
+        assert_eq!(Point::new(-100, 0), pos.value(t2));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::anim::{Easing, Interpolate};
+use std::time::Duration;
+
+/// A value that changes over a fixed duration.
+pub trait Animation {
+    type Output;
+
+    fn duration(&self) -> Duration;
+
+    /// The value after `elapsed` time. Values past the duration yield the final value.
+    fn sample(&self, elapsed: Duration) -> Self::Output;
+
+    /// Runs `next` after this animation has finished.
+    fn then<B>(self, next: B) -> Sequence<Self, B>
+    where
+        Self: Sized,
+        B: Animation<Output = Self::Output>,
+    {
+        Sequence::new(self, next)
+    }
+
+    /// Runs `other` at the same time as this animation, yielding both values as a tuple.
+    fn with<B>(self, other: B) -> Parallel<Self, B>
+    where
+        Self: Sized,
+        B: Animation,
+    {
+        Parallel::new(self, other)
+    }
+
+    /// Transforms the values of this animation, for instance to combine the tuple of a
+    /// [`Parallel`] into a single value.
+    fn map<F, U>(self, f: F) -> Map<Self, F>
+    where
+        Self: Sized,
+        F: Fn(Self::Output) -> U,
+    {
+        Map::new(self, f)
+    }
+}
+
+impl<T> Animation for Box<T>
+where
+    T: Animation + ?Sized,
+{
+    type Output = T::Output;
+
+    fn duration(&self) -> Duration {
+        (**self).duration()
+    }
+
+    fn sample(&self, elapsed: Duration) -> Self::Output {
+        (**self).sample(elapsed)
+    }
+}
+
+/// Transitions from one value to another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl<T> Tween<T> {
+    pub fn new(from: T, to: T, duration: Duration, easing: Easing) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            easing,
+        }
+    }
+}
+
+impl<T> Tween<T>
+where
+    T: Clone,
+{
+    /// Keeps `value` for the provided duration, which is mostly useful as a delay in a
+    /// [`Sequence`].
+    pub fn hold(value: T, duration: Duration) -> Self {
+        Self::new(value.clone(), value, duration, Easing::Linear)
+    }
+}
+
+impl<T> Animation for Tween<T>
+where
+    T: Interpolate + Clone,
+{
+    type Output = T;
+
+    fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    fn sample(&self, elapsed: Duration) -> Self::Output {
+        if elapsed >= self.duration {
+            return self.to.clone();
+        }
+
+        let t = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        self.from.interpolate(&self.to, self.easing.apply(t))
+    }
+}
+
+/// Two animations that run one after the other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sequence<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Sequence<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<A, B> Animation for Sequence<A, B>
+where
+    A: Animation,
+    B: Animation<Output = A::Output>,
+{
+    type Output = A::Output;
+
+    fn duration(&self) -> Duration {
+        self.first.duration() + self.second.duration()
+    }
+
+    fn sample(&self, elapsed: Duration) -> Self::Output {
+        let first = self.first.duration();
+        if elapsed < first {
+            self.first.sample(elapsed)
+        } else {
+            self.second.sample(elapsed - first)
+        }
+    }
+}
+
+/// Two animations that run at the same time. The shorter one keeps its final value until the
+/// longer one has finished.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parallel<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Parallel<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<A, B> Animation for Parallel<A, B>
+where
+    A: Animation,
+    B: Animation,
+{
+    type Output = (A::Output, B::Output);
+
+    fn duration(&self) -> Duration {
+        self.a.duration().max(self.b.duration())
+    }
+
+    fn sample(&self, elapsed: Duration) -> Self::Output {
+        (self.a.sample(elapsed), self.b.sample(elapsed))
+    }
+}
+
+/// An animation whose values are transformed by a function.
+#[derive(Debug, Clone)]
+pub struct Map<A, F> {
+    inner: A,
+    f: F,
+}
+
+impl<A, F> Map<A, F> {
+    pub fn new(inner: A, f: F) -> Self {
+        Self { inner, f }
+    }
+}
+
+impl<A, F, U> Animation for Map<A, F>
+where
+    A: Animation,
+    F: Fn(A::Output) -> U,
+{
+    type Output = U;
+
+    fn duration(&self) -> Duration {
+        self.inner.duration()
+    }
+
+    fn sample(&self, elapsed: Duration) -> Self::Output {
+        (self.f)(self.inner.sample(elapsed))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geom::{Point, Rect, Size};
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_tween() {
+        let tween = Tween::new(0, 100, ms(200), Easing::Linear);
+        assert_eq!(0, tween.sample(ms(0)));
+        assert_eq!(25, tween.sample(ms(50)));
+        assert_eq!(100, tween.sample(ms(200)));
+        assert_eq!(100, tween.sample(ms(500)));
+
+        let instant = Tween::new(0, 100, Duration::ZERO, Easing::Linear);
+        assert_eq!(100, instant.sample(ms(0)));
+    }
+
+    #[test]
+    fn test_sequence() {
+        let anim = Tween::new(0, 100, ms(100), Easing::Linear)
+            .then(Tween::hold(100, ms(50)))
+            .then(Tween::new(100, 0, ms(100), Easing::Linear));
+        assert_eq!(ms(250), anim.duration());
+        assert_eq!(50, anim.sample(ms(50)));
+        assert_eq!(100, anim.sample(ms(120)));
+        assert_eq!(50, anim.sample(ms(200)));
+        assert_eq!(0, anim.sample(ms(300)));
+    }
+
+    #[test]
+    fn test_parallel() {
+        let anim = Tween::new(
+            Point::new(0, 0),
+            Point::new(100, 0),
+            ms(100),
+            Easing::Linear,
+        )
+        .with(Tween::new(
+            Size::zero(),
+            Size::new(40, 40),
+            ms(200),
+            Easing::Linear,
+        ))
+        .map(|(origin, size)| Rect::new(origin, size));
+        assert_eq!(ms(200), anim.duration());
+        assert_eq!(Rect::from_xywh(50, 0, 10, 10), anim.sample(ms(50)));
+        assert_eq!(Rect::from_xywh(100, 0, 30, 30), anim.sample(ms(150)));
+    }
+}
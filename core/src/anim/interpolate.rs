@@ -0,0 +1,102 @@
+use crate::color::Color;
+use crate::geom::{Point, Rect, Size, Vector};
+
+/// Values that can be blended linearly.
+pub trait Interpolate {
+    /// Blends `self` (at `t == 0.0`) with `other` (at `t == 1.0`). Values of `t` outside of that
+    /// range extrapolate.
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * f64::from(t)
+    }
+}
+
+impl Interpolate for i32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        let value = f64::from(*self).interpolate(&f64::from(*other), t);
+        value.round() as i32
+    }
+}
+
+impl Interpolate for u8 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        let value = f32::from(*self).interpolate(&f32::from(*other), t);
+        value.round().clamp(0.0, 255.0) as u8
+    }
+}
+
+impl Interpolate for Point {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self::new(
+            self.x.interpolate(&other.x, t),
+            self.y.interpolate(&other.y, t),
+        )
+    }
+}
+
+impl Interpolate for Vector {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self::new(
+            self.x.interpolate(&other.x, t),
+            self.y.interpolate(&other.y, t),
+        )
+    }
+}
+
+impl Interpolate for Size {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self::new(
+            self.width.interpolate(&other.width, t),
+            self.height.interpolate(&other.height, t),
+        )
+    }
+}
+
+impl Interpolate for Rect {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self::new(
+            self.origin.interpolate(&other.origin, t),
+            self.size.interpolate(&other.size, t),
+        )
+    }
+}
+
+impl Interpolate for Color {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self::rgba(
+            self.r.interpolate(&other.r, t),
+            self.g.interpolate(&other.g, t),
+            self.b.interpolate(&other.b, t),
+            self.a.interpolate(&other.a, t),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        assert_eq!(15, 10.interpolate(&20, 0.5));
+        assert_eq!(-5, 10.interpolate(&-20, 0.5));
+        assert_eq!(
+            Rect::from_xywh(5, 10, 150, 75),
+            Rect::from_xywh(0, 0, 100, 50).interpolate(&Rect::from_xywh(10, 20, 200, 100), 0.5)
+        );
+        assert_eq!(
+            Color::rgba(0x80, 0x00, 0x40, 0xff),
+            Color::rgb(0x00, 0x00, 0x80).interpolate(&Color::rgb(0xff, 0x00, 0x00), 0.5)
+        );
+        assert_eq!(255, 200u8.interpolate(&255, 1.5));
+    }
+}
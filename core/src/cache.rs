@@ -0,0 +1,132 @@
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// A map of limited capacity that evicts the least recently used entry to make room for new ones.
+///
+/// Looking up an entry counts as using it. Lookups, insertions and evictions take logarithmic
+/// time at most.
+#[derive(Debug, Clone)]
+pub struct LruCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, (V, u64)>,
+    /// The keys ordered by when they were last used.
+    usage: BTreeMap<u64, K>,
+    tick: u64,
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Creates an empty cache that holds at most `capacity` entries, but at least one.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            usage: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (value, used) = self.entries.get_mut(key)?;
+        self.tick += 1;
+        let key = self
+            .usage
+            .remove(used)
+            .expect("every entry has its usage recorded");
+        *used = self.tick;
+        self.usage.insert(self.tick, key);
+        Some(value)
+    }
+
+    /// Inserts an entry, replacing the entry with the same key. Returns the entry that was evicted
+    /// to make room for it, if any.
+    pub fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.get(&key) {
+            self.usage.remove(used);
+        }
+        self.usage.insert(self.tick, key.clone());
+        if self.entries.insert(key, (value, self.tick)).is_some() {
+            return None;
+        }
+
+        if self.entries.len() > self.capacity {
+            let (_, oldest) = self.usage.pop_first()?;
+            let (value, _) = self.entries.remove(&oldest)?;
+            return Some((oldest, value));
+        }
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (value, used) = self.entries.remove(key)?;
+        self.usage.remove(&used);
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.usage.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        assert_eq!(None, cache.insert("a".to_string(), 1));
+        assert_eq!(None, cache.insert("b".to_string(), 2));
+        assert_eq!(Some(&1), cache.get("a"));
+
+        assert_eq!(Some(("b".to_string(), 2)), cache.insert("c".to_string(), 3));
+        assert_eq!(None, cache.get("b"));
+        assert_eq!(Some(&1), cache.get("a"));
+        assert_eq!(Some(&3), cache.get("c"));
+        assert_eq!(2, cache.len());
+    }
+
+    #[test]
+    fn test_replace_and_remove() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        assert_eq!(None, cache.insert(1, "uno"));
+        assert_eq!(2, cache.len());
+        // Replacing counts as using the entry.
+        assert_eq!(Some((2, "two")), cache.insert(3, "three"));
+
+        assert_eq!(Some("uno"), cache.remove(&1));
+        assert_eq!(None, cache.remove(&1));
+        assert_eq!(None, cache.insert(4, "four"));
+        assert_eq!(
+            vec![3, 4],
+            cache.usage.values().copied().collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,11 +1,18 @@
-use crate::geom::{Point, Rect, Size, Vector};
+use crate::geom::{Direction, Point, Rect, Size, Vector};
+use crate::keyboard::{Key, Modifiers};
 use crate::mouse::MouseButton;
-use crate::VuiResult;
+use crate::overlay::OverlayId;
+use crate::time::Timestamp;
+use crate::timer::TimerId;
+use crate::{VuiResult, WindowEvent};
+pub use animated_position::{AnimatedPosition, AnimatedPositionState};
 pub use frame::Frame;
 pub use mouse_sensor::{MouseSensor, MouseSensorState};
 use paste::paste;
 pub use position::Position;
+use std::time::Duration;
 
+mod animated_position;
 mod frame;
 mod mouse_sensor;
 mod position;
@@ -14,6 +21,11 @@ mod position;
 pub struct FramedPosition {
     absolute_position: Point,
     frame_rect: Rect,
+    /// The topmost overlay at the position, if any.
+    hit_layer: Option<OverlayId>,
+    /// The overlay the frame belongs to, or `None` for the base layer.
+    layer: Option<OverlayId>,
+    direction: Direction,
 }
 
 impl FramedPosition {
@@ -21,64 +33,200 @@ impl FramedPosition {
         Self {
             absolute_position: pos,
             frame_rect,
+            hit_layer: None,
+            layer: None,
+            direction: Direction::LeftToRight,
         }
     }
 
     pub fn clip(self, vector: Vector) -> Self {
         Self {
-            absolute_position: self.absolute_position,
             frame_rect: self.frame_rect.clip(vector),
+            ..self
         }
     }
 
     pub fn resize_clipped(self, size: Size) -> Self {
         Self {
-            absolute_position: self.absolute_position,
             frame_rect: self.frame_rect.resize_clipped(size),
+            ..self
         }
     }
 
+    /// Like [`Self::clip()`], but mirrored horizontally in right-to-left direction.
+    pub fn place(self, vector: Vector) -> Self {
+        Self {
+            frame_rect: self.direction.clip(self.frame_rect, vector),
+            ..self
+        }
+    }
+
+    /// Like [`Self::resize_clipped()`], but keeping the right edge of the frame in place in
+    /// right-to-left direction.
+    pub fn fit(self, size: Size) -> Self {
+        Self {
+            frame_rect: self.direction.resize_clipped(self.frame_rect, size),
+            ..self
+        }
+    }
+
+    pub fn with_direction(self, direction: Direction) -> Self {
+        Self { direction, ..self }
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Records the topmost overlay at the position. See [`crate::overlay::Overlays::route()`].
+    pub fn with_hit_layer(self, hit_layer: Option<OverlayId>) -> Self {
+        Self { hit_layer, ..self }
+    }
+
+    /// Moves the frame onto the overlay `id`, covering `rect`.
+    pub fn enter_overlay(self, id: OverlayId, rect: Rect) -> Self {
+        Self {
+            frame_rect: rect,
+            layer: Some(id),
+            ..self
+        }
+    }
+
+    /// Whether the position lies within the frame and is not covered by an overlay above the
+    /// layer of the frame.
     pub fn is_hit(&self) -> bool {
-        self.frame_rect.contains(self.absolute_position)
+        !self.is_occluded() && self.frame_rect.contains(self.absolute_position)
+    }
+
+    /// Whether the position is covered by an overlay other than the one the frame belongs to.
+    pub fn is_occluded(&self) -> bool {
+        self.hit_layer != self.layer
+    }
+
+    pub fn absolute_position(&self) -> Point {
+        self.absolute_position
+    }
+
+    /// The position relative to the origin of the frame. Note that this may lie outside of the
+    /// frame (or even be negative), for instance while the pointer is captured.
+    pub fn relative_position(&self) -> Point {
+        self.absolute_position - self.frame_rect.origin
+    }
+
+    pub fn frame_rect(&self) -> Rect {
+        self.frame_rect
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ComponentEvent {
-    LoopStart,
-    MouseMotion(FramedPosition),
-    MouseButtonDown(MouseButton, FramedPosition),
-    MouseButtonUp(MouseButton, FramedPosition),
+    LoopStart(Timestamp, Duration),
+    MouseMotion(FramedPosition, Modifiers, Timestamp),
+    MouseButtonDown(MouseButton, FramedPosition, Modifiers, Timestamp),
+    MouseButtonUp(MouseButton, FramedPosition, Modifiers, Timestamp),
+    MouseWheel(Vector, FramedPosition, Modifiers, Timestamp),
+    KeyDown(Key, Modifiers, Timestamp),
+    KeyUp(Key, Modifiers, Timestamp),
+    Window(WindowEvent, Timestamp),
+    Timer(TimerId, Timestamp),
 }
 
 impl ComponentEvent {
-    pub fn clip(self, vector: Vector) -> Self {
+    pub fn timestamp(&self) -> Timestamp {
         match self {
-            ComponentEvent::MouseMotion(pos) => ComponentEvent::MouseMotion(pos.clip(vector)),
-            ComponentEvent::MouseButtonDown(btn, pos) => {
-                ComponentEvent::MouseButtonDown(btn, pos.clip(vector))
-            }
-            ComponentEvent::MouseButtonUp(btn, pos) => {
-                ComponentEvent::MouseButtonUp(btn, pos.clip(vector))
-            }
-            other => other,
+            ComponentEvent::LoopStart(ts, _)
+            | ComponentEvent::MouseMotion(_, _, ts)
+            | ComponentEvent::MouseButtonDown(_, _, _, ts)
+            | ComponentEvent::MouseButtonUp(_, _, _, ts)
+            | ComponentEvent::MouseWheel(_, _, _, ts)
+            | ComponentEvent::KeyDown(_, _, ts)
+            | ComponentEvent::KeyUp(_, _, ts)
+            | ComponentEvent::Window(_, ts)
+            | ComponentEvent::Timer(_, ts) => *ts,
+        }
+    }
+
+    /// The modifier keys that were held down when the event occurred. This is empty for
+    /// events that don't originate from the user's input devices.
+    pub fn modifiers(&self) -> Modifiers {
+        match self {
+            ComponentEvent::LoopStart(..)
+            | ComponentEvent::Window(..)
+            | ComponentEvent::Timer(..) => Modifiers::NONE,
+            ComponentEvent::MouseMotion(_, mods, _)
+            | ComponentEvent::MouseButtonDown(_, _, mods, _)
+            | ComponentEvent::MouseButtonUp(_, _, mods, _)
+            | ComponentEvent::MouseWheel(_, _, mods, _)
+            | ComponentEvent::KeyDown(_, mods, _)
+            | ComponentEvent::KeyUp(_, mods, _) => *mods,
         }
     }
 
+    /// Whether the event originates from the keyboard.
+    pub fn is_keyboard(&self) -> bool {
+        matches!(
+            self,
+            ComponentEvent::KeyDown(..) | ComponentEvent::KeyUp(..)
+        )
+    }
+
+    pub fn clip(self, vector: Vector) -> Self {
+        self.map_position(|pos| pos.clip(vector))
+    }
+
     pub fn resize(self, size: Size) -> Self {
+        self.map_position(|pos| pos.resize_clipped(size))
+    }
+
+    /// See [`FramedPosition::place()`].
+    pub fn place(self, vector: Vector) -> Self {
+        self.map_position(|pos| pos.place(vector))
+    }
+
+    /// See [`FramedPosition::fit()`].
+    pub fn fit(self, size: Size) -> Self {
+        self.map_position(|pos| pos.fit(size))
+    }
+
+    /// Sets the direction in which [`Position`] and [`Frame`] place components.
+    pub fn with_direction(self, direction: Direction) -> Self {
+        self.map_position(|pos| pos.with_direction(direction))
+    }
+
+    /// Moves pointer events onto the overlay `id`, covering `rect`. Components that render the
+    /// content of an overlay use this to pass events on to that content.
+    pub fn enter_overlay(self, id: OverlayId, rect: Rect) -> Self {
+        self.map_position(|pos| pos.enter_overlay(id, rect))
+    }
+
+    pub fn map_position(self, f: impl FnOnce(FramedPosition) -> FramedPosition) -> Self {
         match self {
-            ComponentEvent::MouseMotion(pos) => {
-                ComponentEvent::MouseMotion(pos.resize_clipped(size))
+            ComponentEvent::MouseMotion(pos, mods, ts) => {
+                ComponentEvent::MouseMotion(f(pos), mods, ts)
+            }
+            ComponentEvent::MouseButtonDown(btn, pos, mods, ts) => {
+                ComponentEvent::MouseButtonDown(btn, f(pos), mods, ts)
             }
-            ComponentEvent::MouseButtonDown(btn, pos) => {
-                ComponentEvent::MouseButtonDown(btn, pos.resize_clipped(size))
+            ComponentEvent::MouseButtonUp(btn, pos, mods, ts) => {
+                ComponentEvent::MouseButtonUp(btn, f(pos), mods, ts)
             }
-            ComponentEvent::MouseButtonUp(btn, pos) => {
-                ComponentEvent::MouseButtonUp(btn, pos.resize_clipped(size))
+            ComponentEvent::MouseWheel(amount, pos, mods, ts) => {
+                ComponentEvent::MouseWheel(amount, f(pos), mods, ts)
             }
             other => other,
         }
     }
+
+    /// The pointer position carried by the event, if any.
+    pub fn position(&self) -> Option<&FramedPosition> {
+        match self {
+            ComponentEvent::MouseMotion(pos, ..)
+            | ComponentEvent::MouseButtonDown(_, pos, ..)
+            | ComponentEvent::MouseButtonUp(_, pos, ..)
+            | ComponentEvent::MouseWheel(_, pos, ..) => Some(pos),
+            _ => None,
+        }
+    }
 }
 
 pub trait HandleEvent {
@@ -131,26 +279,65 @@ where
     }
 }
 
+/// The area a component is rendered in.
+///
+/// In right-to-left direction, [`Position`] measures offsets from the right edge, and [`Frame`]
+/// keeps the right edge in place, which mirrors the arrangement of components.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Layout {
     clip_rect: Rect,
+    direction: Direction,
 }
 
 impl Layout {
     pub fn new(clip_rect: Rect) -> Self {
-        Self { clip_rect }
+        Self {
+            clip_rect,
+            direction: Direction::LeftToRight,
+        }
+    }
+
+    pub fn with_direction(self, direction: Direction) -> Self {
+        Self { direction, ..self }
     }
 
     pub fn clip_rect(&self) -> Rect {
         self.clip_rect
     }
 
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
     pub fn clip(&self, vector: Vector) -> Self {
-        Self::new(self.clip_rect.clip(vector))
+        Self {
+            clip_rect: self.clip_rect.clip(vector),
+            direction: self.direction,
+        }
     }
 
     pub fn resize_clipped(&self, size: Size) -> Self {
-        Self::new(self.clip_rect.resize_clipped(size))
+        Self {
+            clip_rect: self.clip_rect.resize_clipped(size),
+            direction: self.direction,
+        }
+    }
+
+    /// Like [`Self::clip()`], but mirrored horizontally in right-to-left direction.
+    pub fn place(&self, vector: Vector) -> Self {
+        Self {
+            clip_rect: self.direction.clip(self.clip_rect, vector),
+            direction: self.direction,
+        }
+    }
+
+    /// Like [`Self::resize_clipped()`], but keeping the right edge in place in right-to-left
+    /// direction.
+    pub fn fit(&self, size: Size) -> Self {
+        Self {
+            clip_rect: self.direction.resize_clipped(self.clip_rect, size),
+            direction: self.direction,
+        }
     }
 }
 
@@ -237,7 +424,9 @@ mod test {
     use crate::component::{
         AsChain, ComponentEvent, Frame, FramedPosition, HandleEvent, Layout, Position, UpdateLayout,
     };
-    use crate::geom::{Point, Rect};
+    use crate::geom::{Direction, Point, Rect};
+    use crate::keyboard::Modifiers;
+    use crate::time::Timestamp;
 
     #[test]
     fn test_as_chain_handle_event() {
@@ -246,15 +435,17 @@ mod test {
             Frame::new((100, 200).into()),
         );
         let state = ((), ());
-        let event = ComponentEvent::MouseMotion(FramedPosition::new(
-            Point::new(123, 456),
-            Rect::from_size((80, 300).into()),
-        ));
+        let event = ComponentEvent::MouseMotion(
+            FramedPosition::new(Point::new(123, 456), Rect::from_size((80, 300).into())),
+            Modifiers::NONE,
+            Timestamp::default(),
+        );
         let event = comps.as_chain().handle_event(state, event).unwrap();
-        let expected_event = ComponentEvent::MouseMotion(FramedPosition::new(
-            Point::new(123, 456),
-            Rect::from_xywh(12, 34, 68, 200),
-        ));
+        let expected_event = ComponentEvent::MouseMotion(
+            FramedPosition::new(Point::new(123, 456), Rect::from_xywh(12, 34, 68, 200)),
+            Modifiers::NONE,
+            Timestamp::default(),
+        );
         assert_eq!(expected_event, event);
     }
 
@@ -271,4 +462,56 @@ mod test {
 
         assert_eq!(expected_layout, layout);
     }
+
+    #[test]
+    fn test_as_chain_right_to_left() {
+        let comps = (
+            Position::new((12, 34).into()),
+            Frame::new((100, 200).into()),
+        );
+        let layout =
+            Layout::new(Rect::from_xywh(600, 700, 200, 180)).with_direction(Direction::RightToLeft);
+        let layout = comps.as_chain().update_layout(((), ()), layout).unwrap();
+        assert_eq!(Rect::from_xywh(688, 734, 100, 146), layout.clip_rect());
+        assert_eq!(Direction::RightToLeft, layout.direction());
+
+        let event = ComponentEvent::MouseMotion(
+            FramedPosition::new(Point::new(700, 800), Rect::from_xywh(600, 700, 200, 180)),
+            Modifiers::NONE,
+            Timestamp::default(),
+        )
+        .with_direction(Direction::RightToLeft);
+        let event = comps.as_chain().handle_event(((), ()), event).unwrap();
+        let pos = event.position().unwrap();
+        assert_eq!(layout.clip_rect(), pos.frame_rect());
+        assert_eq!(Point::new(12, 66), pos.relative_position());
+    }
+
+    #[test]
+    fn test_nested_position_right_to_left() {
+        let comps = (
+            Frame::new((100, 200).into()),
+            Position::new((10, 5).into()),
+            Frame::new((30, 20).into()),
+        );
+        let layout =
+            Layout::new(Rect::from_xywh(600, 700, 200, 180)).with_direction(Direction::RightToLeft);
+        let layout = comps
+            .as_chain()
+            .update_layout(((), (), ()), layout)
+            .unwrap();
+        assert_eq!(Rect::from_xywh(760, 705, 30, 20), layout.clip_rect());
+
+        let event = ComponentEvent::MouseMotion(
+            FramedPosition::new(Point::new(770, 710), Rect::from_xywh(600, 700, 200, 180)),
+            Modifiers::NONE,
+            Timestamp::default(),
+        )
+        .with_direction(Direction::RightToLeft);
+        let event = comps.as_chain().handle_event(((), (), ()), event).unwrap();
+        let pos = event.position().unwrap();
+        assert_eq!(layout.clip_rect(), pos.frame_rect());
+        assert!(pos.is_hit());
+        assert_eq!(Point::new(10, 5), pos.relative_position());
+    }
 }
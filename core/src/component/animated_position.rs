@@ -0,0 +1,145 @@
+use crate::anim::{Animated, Easing};
+use crate::component::{ComponentEvent, HandleEvent, Layout, UpdateLayout};
+use crate::geom::Point;
+use crate::time::Timestamp;
+use crate::timer::Timers;
+use crate::VuiResult;
+use std::time::Duration;
+
+#[derive(Debug, Default)]
+pub struct AnimatedPositionState {
+    position: Animated<Point>,
+    now: Timestamp,
+}
+
+impl AnimatedPositionState {
+    pub fn new(pos: Point) -> Self {
+        Self {
+            position: Animated::new(pos),
+            now: Timestamp::default(),
+        }
+    }
+
+    /// The position at the start of the current loop iteration.
+    pub fn position(&self) -> Point {
+        self.position.value(self.now)
+    }
+
+    /// Moves to `pos` immediately.
+    pub fn set(&mut self, pos: Point) {
+        self.position.set(pos);
+    }
+
+    /// Moves from the current position to `target`, starting at the current loop iteration.
+    ///
+    /// The event loop is kept awake from the next loop iteration on. Use
+    /// [`AnimatedPosition::animate_to()`] to keep it awake right away.
+    pub fn animate_to(&mut self, target: Point, duration: Duration, easing: Easing) {
+        self.position.animate_to(target, self.now, duration, easing);
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.position.is_running(self.now)
+    }
+}
+
+/// A [`Position`](crate::component::Position) that can be animated.
+///
+/// While an animation is running, the component keeps the event loop awake through the provided
+/// [`Timers`], so that it is laid out on every frame.
+pub struct AnimatedPosition {
+    timers: Timers,
+}
+
+impl AnimatedPosition {
+    pub fn new(timers: Timers) -> Self {
+        Self { timers }
+    }
+
+    /// Like [`AnimatedPositionState::animate_to()`], but also keeps the event loop awake until
+    /// the animation ends.
+    pub fn animate_to(
+        &self,
+        state: &mut AnimatedPositionState,
+        target: Point,
+        duration: Duration,
+        easing: Easing,
+    ) {
+        state.animate_to(target, duration, easing);
+        self.keep_awake(state);
+    }
+
+    fn keep_awake(&self, state: &AnimatedPositionState) {
+        if let Some(end) = state.position.end_time() {
+            self.timers.keep_awake_until(end);
+        }
+    }
+}
+
+impl HandleEvent for AnimatedPosition {
+    type State<'a> = &'a mut AnimatedPositionState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        if let ComponentEvent::LoopStart(now, _) = &event {
+            state.now = *now;
+            state.position.settle(*now);
+            self.keep_awake(state);
+        }
+
+        Ok(event.place(state.position().as_vector()))
+    }
+}
+
+impl UpdateLayout for AnimatedPosition {
+    type State<'a> = &'a AnimatedPositionState;
+
+    fn update_layout(&self, state: Self::State<'_>, layout: Layout) -> VuiResult<Layout> {
+        Ok(layout.place(state.position().as_vector()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geom::Rect;
+
+    #[test]
+    fn test_animated_position() {
+        let timers = Timers::new();
+        let comp = AnimatedPosition::new(timers.clone());
+        let mut state = AnimatedPositionState::new(Point::new(0, 100));
+        let layout = Layout::new(Rect::from_xywh(0, 0, 200, 200));
+
+        let t0 = Timestamp::from_millis(1000);
+        comp.handle_event(&mut state, ComponentEvent::LoopStart(t0, Duration::ZERO))
+            .unwrap();
+        comp.animate_to(
+            &mut state,
+            Point::zero(),
+            Duration::from_millis(100),
+            Easing::Linear,
+        );
+        assert!(state.is_animating());
+        assert!(timers.is_awake(t0));
+
+        let updated = comp.update_layout(&state, layout.clone()).unwrap();
+        assert_eq!(Rect::from_xywh(0, 100, 200, 100), updated.clip_rect());
+
+        let t1 = Timestamp::from_millis(1050);
+        comp.handle_event(&mut state, ComponentEvent::LoopStart(t1, Duration::ZERO))
+            .unwrap();
+        let updated = comp.update_layout(&state, layout.clone()).unwrap();
+        assert_eq!(Rect::from_xywh(0, 50, 200, 150), updated.clip_rect());
+
+        let t2 = Timestamp::from_millis(1100);
+        comp.handle_event(&mut state, ComponentEvent::LoopStart(t2, Duration::ZERO))
+            .unwrap();
+        assert!(!state.is_animating());
+        assert!(!timers.is_awake(t2));
+        assert_eq!(layout, comp.update_layout(&state, layout.clone()).unwrap());
+    }
+}
@@ -2,6 +2,8 @@ use crate::component::{ComponentEvent, HandleEvent, Layout, SizeAttr, UpdateLayo
 use crate::geom::Size;
 use crate::VuiResult;
 
+/// Gives a component its size. In right-to-left layouts, the frame keeps the right edge of the
+/// area in place.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Frame {
     size: Size,
@@ -27,7 +29,7 @@ impl HandleEvent for Frame {
         _state: Self::State<'_>,
         event: ComponentEvent,
     ) -> VuiResult<ComponentEvent> {
-        Ok(event.resize(self.size))
+        Ok(event.fit(self.size))
     }
 }
 
@@ -35,6 +37,6 @@ impl UpdateLayout for Frame {
     type State<'a> = ();
 
     fn update_layout(&self, _state: Self::State<'_>, layout: Layout) -> VuiResult<Layout> {
-        Ok(layout.resize_clipped(self.size))
+        Ok(layout.fit(self.size))
     }
 }
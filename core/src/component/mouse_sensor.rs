@@ -1,17 +1,47 @@
+use crate::bitops::{ClearBits, IsSet, SetBits};
 use crate::component::{ComponentEvent, HandleEvent, UpdateLayout};
-use crate::mouse::{ClickStates, HoverState};
-use crate::VuiResult;
+use crate::geom::{Point, Vector};
+use crate::keyboard::Modifiers;
+use crate::mouse::{ClickConfig, ClickStates, DragStates, HoverState, MouseButton};
+use crate::time::Timestamp;
+use crate::{VuiResult, WindowEvent};
+use std::time::Duration;
 
 #[derive(Debug, Default, Clone)]
 pub struct MouseSensorState {
     hover_state: HoverState,
     click_states: ClickStates,
+    drag_states: DragStates,
+    captured: u8,
+    pointer_position: Option<Point>,
+    modifiers: Modifiers,
+    hover_start: Option<Timestamp>,
+    now: Timestamp,
+    wheel: Vector,
 }
 
 impl MouseSensorState {
+    /// Only start drags once the pointer moved more than `threshold` pixels away from where the
+    /// button was pressed.
+    pub fn with_drag_threshold(self, threshold: i32) -> Self {
+        Self {
+            drag_states: DragStates::new(threshold),
+            ..self
+        }
+    }
+
+    pub fn with_click_config(self, config: ClickConfig) -> Self {
+        Self {
+            click_states: ClickStates::with_config(config),
+            ..self
+        }
+    }
+
     pub fn clear_event_states(&mut self) {
         self.hover_state.clear_event_state();
         self.click_states.clear_event_state();
+        self.drag_states.clear_event_state();
+        self.wheel = Vector::zero();
     }
 
     pub fn hover_state(&self) -> &HoverState {
@@ -21,8 +51,67 @@ impl MouseSensorState {
     pub fn click_states(&self) -> &ClickStates {
         &self.click_states
     }
+
+    /// Drag positions are relative to the frame origin.
+    pub fn drag_states(&self) -> &DragStates {
+        &self.drag_states
+    }
+
+    /// Whether `btn` holds the pointer capture.
+    ///
+    /// The pointer is captured when a button is pressed inside the frame and released when that
+    /// same button goes up again, regardless of where that happens. This covers the full duration
+    /// of any drag with that button. While captured, the sensor keeps tracking the pointer
+    /// position outside of the frame.
+    pub fn is_captured_by(&self, btn: MouseButton) -> bool {
+        self.captured.is_set(btn.mask())
+    }
+
+    pub fn is_captured(&self) -> bool {
+        self.captured != 0
+    }
+
+    /// The modifier keys that were held down during the last button press or release inside the
+    /// frame. When a click completes, these are the modifiers at the time of the release.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// How long the pointer has been hovering over the frame, as of the last event. This is zero
+    /// if the pointer is not hovering.
+    pub fn hover_duration(&self) -> Duration {
+        self.hover_start
+            .map_or(Duration::ZERO, |start| self.now.duration_since(start))
+    }
+
+    /// Whether the pointer has been hovering over the frame for at least `delay`.
+    pub fn has_dwelled(&self, delay: Duration) -> bool {
+        self.hover_start.is_some() && self.hover_duration() >= delay
+    }
+
+    /// How far the mouse wheel was scrolled over the frame since the start of the current loop
+    /// iteration, in notches.
+    pub fn wheel_delta(&self) -> Vector {
+        self.wheel
+    }
+
+    /// The last known pointer position, relative to the frame origin.
+    ///
+    /// This is `None` if the pointer is outside of the frame and not captured.
+    pub fn pointer_position(&self) -> Option<Point> {
+        self.pointer_position
+    }
+
+    fn update_hover_start(&mut self) {
+        if !self.hover_state.is_hovering() {
+            self.hover_start = None;
+        } else if self.hover_start.is_none() {
+            self.hover_start = Some(self.now);
+        }
+    }
 }
 
+#[derive(Default, Clone)]
 pub struct MouseSensor {}
 
 impl MouseSensor {
@@ -39,28 +128,70 @@ impl HandleEvent for MouseSensor {
         state: Self::State<'_>,
         event: ComponentEvent,
     ) -> VuiResult<ComponentEvent> {
+        state.now = state.now.max(event.timestamp());
+
         match &event {
-            ComponentEvent::LoopStart => {
+            ComponentEvent::LoopStart(..) => {
                 state.clear_event_states();
             }
-            ComponentEvent::MouseMotion(pos) => {
+            ComponentEvent::MouseMotion(pos, ..) => {
                 state.hover_state.update(pos.is_hit());
+                state.update_hover_start();
+                state.pointer_position =
+                    (pos.is_hit() || state.is_captured()).then(|| pos.relative_position());
+                state.drag_states.motion(pos.relative_position());
             }
-            ComponentEvent::MouseButtonDown(btn, pos) => {
+            ComponentEvent::MouseButtonDown(btn, pos, mods, ts) => {
                 if pos.is_hit() {
-                    state.click_states.click(*btn);
+                    state.modifiers = *mods;
+                    state
+                        .click_states
+                        .click_at(*btn, pos.relative_position(), *ts);
+                    state.drag_states.press(*btn, pos.relative_position());
+                    state.captured.set_bits(btn.mask());
+                    state.pointer_position = Some(pos.relative_position());
                 } else {
                     state.click_states.clear(*btn);
+                    state.drag_states.cancel(*btn);
                 }
             }
-            ComponentEvent::MouseButtonUp(btn, pos) => {
+            ComponentEvent::MouseButtonUp(btn, pos, mods, _) => {
                 if pos.is_hit() {
+                    state.modifiers = *mods;
                     state.click_states.unclick(*btn);
                 } else {
                     state.click_states.clear(*btn);
                 }
+
+                state.drag_states.release(*btn, pos.relative_position());
+
+                if state.is_captured_by(*btn) {
+                    state.captured.clear_bits(btn.mask());
+                    if !pos.is_hit() && !state.is_captured() {
+                        state.pointer_position = None;
+                    }
+                }
             }
+            ComponentEvent::MouseWheel(amount, pos, ..) => {
+                if pos.is_hit() {
+                    state.wheel = state.wheel + *amount;
+                }
+            }
+            ComponentEvent::Window(WindowEvent::PointerLeft, _) => {
+                state.hover_state.update(false);
+                state.update_hover_start();
+                if !state.is_captured() {
+                    state.pointer_position = None;
+                }
+            }
+            ComponentEvent::KeyDown(..)
+            | ComponentEvent::KeyUp(..)
+            | ComponentEvent::Window(..)
+            | ComponentEvent::Timer(..) => {}
         }
+        // Long presses are usually detected on LoopStart, so this has to come after clearing the
+        // event states.
+        state.click_states.update_time(state.now);
 
         Ok(event)
     }
@@ -69,3 +200,253 @@ impl HandleEvent for MouseSensor {
 impl UpdateLayout for MouseSensor {
     type State<'a> = ();
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::component::FramedPosition;
+    use crate::geom::Rect;
+    use crate::time::Timestamp;
+
+    fn motion(x: i32, y: i32) -> ComponentEvent {
+        ComponentEvent::MouseMotion(
+            FramedPosition::new((x, y).into(), Rect::from_xywh(10, 10, 20, 20)),
+            Modifiers::NONE,
+            Timestamp::default(),
+        )
+    }
+
+    fn down(x: i32, y: i32) -> ComponentEvent {
+        ComponentEvent::MouseButtonDown(
+            MouseButton::Left,
+            FramedPosition::new((x, y).into(), Rect::from_xywh(10, 10, 20, 20)),
+            Modifiers::NONE,
+            Timestamp::default(),
+        )
+    }
+
+    fn up_with(x: i32, y: i32, mods: Modifiers) -> ComponentEvent {
+        ComponentEvent::MouseButtonUp(
+            MouseButton::Left,
+            FramedPosition::new((x, y).into(), Rect::from_xywh(10, 10, 20, 20)),
+            mods,
+            Timestamp::default(),
+        )
+    }
+
+    fn up(x: i32, y: i32) -> ComponentEvent {
+        up_with(x, y, Modifiers::NONE)
+    }
+
+    #[test]
+    fn test_pointer_capture() {
+        let sensor = MouseSensor::new();
+        let mut state = MouseSensorState::default();
+
+        sensor.handle_event(&mut state, motion(5, 5)).unwrap();
+        assert_eq!(None, state.pointer_position());
+        assert!(!state.is_captured());
+
+        sensor.handle_event(&mut state, motion(15, 16)).unwrap();
+        assert_eq!(Some(Point::new(5, 6)), state.pointer_position());
+
+        sensor.handle_event(&mut state, down(15, 16)).unwrap();
+        assert!(state.is_captured_by(MouseButton::Left));
+        assert!(!state.is_captured_by(MouseButton::Right));
+
+        // Leaving the frame while captured keeps reporting the position.
+        sensor.handle_event(&mut state, motion(50, 3)).unwrap();
+        assert!(!state.hover_state().is_hovering());
+        assert_eq!(Some(Point::new(40, -7)), state.pointer_position());
+        assert!(state.click_states().is_down(MouseButton::Left));
+
+        // Releasing outside of the frame ends the capture without completing the click.
+        sensor.handle_event(&mut state, up(50, 3)).unwrap();
+        assert!(!state.is_captured());
+        assert_eq!(None, state.pointer_position());
+        assert!(!state.click_states().has_click_completed(MouseButton::Left));
+
+        sensor.handle_event(&mut state, motion(60, 3)).unwrap();
+        assert_eq!(None, state.pointer_position());
+    }
+
+    #[test]
+    fn test_drag() {
+        let sensor = MouseSensor::new();
+        let mut state = MouseSensorState::default().with_drag_threshold(2);
+
+        sensor.handle_event(&mut state, down(15, 15)).unwrap();
+        sensor.handle_event(&mut state, motion(16, 15)).unwrap();
+        assert!(!state.drag_states().is_dragging(MouseButton::Left));
+
+        sensor.handle_event(&mut state, motion(40, 15)).unwrap();
+        assert!(state.drag_states().has_drag_started(MouseButton::Left));
+        assert_eq!(
+            Point::new(5, 5),
+            state.drag_states().origin(MouseButton::Left)
+        );
+        assert_eq!(
+            Vector::new(25, 0),
+            state.drag_states().offset(MouseButton::Left)
+        );
+        assert!(state.is_captured_by(MouseButton::Left));
+
+        sensor
+            .handle_event(
+                &mut state,
+                ComponentEvent::LoopStart(Timestamp::default(), Duration::ZERO),
+            )
+            .unwrap();
+        sensor.handle_event(&mut state, up(45, 0)).unwrap();
+        assert!(state.drag_states().has_drag_ended(MouseButton::Left));
+        assert_eq!(
+            Vector::new(30, -15),
+            state.drag_states().offset(MouseButton::Left)
+        );
+        assert!(!state.is_captured());
+    }
+
+    #[test]
+    fn test_pointer_capture_outside_press() {
+        let sensor = MouseSensor::new();
+        let mut state = MouseSensorState::default();
+
+        sensor.handle_event(&mut state, down(5, 5)).unwrap();
+        assert!(!state.is_captured());
+
+        sensor.handle_event(&mut state, motion(15, 15)).unwrap();
+        assert_eq!(Some(Point::new(5, 5)), state.pointer_position());
+
+        sensor.handle_event(&mut state, up(15, 15)).unwrap();
+        assert!(!state.click_states().has_click_completed(MouseButton::Left));
+    }
+
+    #[test]
+    fn test_click_modifiers() {
+        let sensor = MouseSensor::new();
+        let mut state = MouseSensorState::default();
+
+        sensor.handle_event(&mut state, down(15, 15)).unwrap();
+        assert_eq!(Modifiers::NONE, state.modifiers());
+
+        sensor
+            .handle_event(&mut state, up_with(15, 15, Modifiers::CTRL))
+            .unwrap();
+        assert!(state.click_states().has_click_completed(MouseButton::Left));
+        assert!(state.modifiers().ctrl());
+
+        // Releases outside of the frame are ignored.
+        sensor.handle_event(&mut state, down(15, 15)).unwrap();
+        sensor
+            .handle_event(&mut state, up_with(5, 5, Modifiers::SHIFT))
+            .unwrap();
+        assert_eq!(Modifiers::NONE, state.modifiers());
+    }
+
+    #[test]
+    fn test_pointer_left_window() {
+        let sensor = MouseSensor::new();
+        let mut state = MouseSensorState::default();
+
+        sensor.handle_event(&mut state, motion(15, 15)).unwrap();
+        assert!(state.hover_state().is_hovering());
+
+        sensor
+            .handle_event(
+                &mut state,
+                ComponentEvent::Window(WindowEvent::PointerLeft, Timestamp::default()),
+            )
+            .unwrap();
+        assert!(!state.hover_state().is_hovering());
+        assert!(state.hover_state().has_left());
+        assert_eq!(None, state.pointer_position());
+    }
+
+    #[test]
+    fn test_hover_dwell() {
+        let sensor = MouseSensor::new();
+        let mut state = MouseSensorState::default();
+        let at = |millis| Timestamp::from_millis(millis);
+        let loop_start = |millis| ComponentEvent::LoopStart(at(millis), Duration::ZERO);
+        let motion_at = |x, y, millis| {
+            ComponentEvent::MouseMotion(
+                FramedPosition::new(Point::new(x, y), Rect::from_xywh(10, 10, 20, 20)),
+                Modifiers::NONE,
+                at(millis),
+            )
+        };
+
+        sensor
+            .handle_event(&mut state, motion_at(5, 5, 1000))
+            .unwrap();
+        sensor.handle_event(&mut state, loop_start(2000)).unwrap();
+        assert_eq!(Duration::ZERO, state.hover_duration());
+
+        sensor
+            .handle_event(&mut state, motion_at(15, 15, 2100))
+            .unwrap();
+        sensor
+            .handle_event(&mut state, motion_at(16, 15, 2200))
+            .unwrap();
+        assert_eq!(Duration::from_millis(100), state.hover_duration());
+
+        sensor.handle_event(&mut state, loop_start(2600)).unwrap();
+        assert!(state.has_dwelled(Duration::from_millis(500)));
+        assert!(!state.has_dwelled(Duration::from_millis(501)));
+
+        sensor
+            .handle_event(&mut state, motion_at(5, 5, 2700))
+            .unwrap();
+        assert!(!state.has_dwelled(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_wheel() {
+        let sensor = MouseSensor::new();
+        let mut state = MouseSensorState::default();
+        let wheel = |x, y, dy| {
+            ComponentEvent::MouseWheel(
+                Vector::new(0, dy),
+                FramedPosition::new(Point::new(x, y), Rect::from_xywh(10, 10, 20, 20)),
+                Modifiers::NONE,
+                Timestamp::default(),
+            )
+        };
+
+        sensor.handle_event(&mut state, wheel(15, 15, 1)).unwrap();
+        sensor.handle_event(&mut state, wheel(15, 15, 2)).unwrap();
+        sensor.handle_event(&mut state, wheel(5, 5, 4)).unwrap();
+        assert_eq!(Vector::new(0, 3), state.wheel_delta());
+
+        sensor
+            .handle_event(
+                &mut state,
+                ComponentEvent::LoopStart(Timestamp::default(), Duration::ZERO),
+            )
+            .unwrap();
+        assert_eq!(Vector::zero(), state.wheel_delta());
+    }
+
+    #[test]
+    fn test_long_press() {
+        let sensor = MouseSensor::new();
+        let mut state = MouseSensorState::default();
+        let loop_start =
+            |millis| ComponentEvent::LoopStart(Timestamp::from_millis(millis), Duration::ZERO);
+
+        sensor.handle_event(&mut state, down(15, 15)).unwrap();
+        sensor.handle_event(&mut state, loop_start(700)).unwrap();
+        assert!(!state.click_states().is_long_pressed(MouseButton::Left));
+
+        sensor.handle_event(&mut state, loop_start(800)).unwrap();
+        assert!(state
+            .click_states()
+            .has_long_press_started(MouseButton::Left));
+
+        sensor.handle_event(&mut state, loop_start(900)).unwrap();
+        assert!(!state
+            .click_states()
+            .has_long_press_started(MouseButton::Left));
+        assert!(state.click_states().is_long_pressed(MouseButton::Left));
+    }
+}
@@ -27,7 +27,7 @@ impl HandleEvent for Position {
         _state: Self::State<'_>,
         event: ComponentEvent,
     ) -> VuiResult<ComponentEvent> {
-        Ok(event.clip(self.value.as_vector()))
+        Ok(event.place(self.value.as_vector()))
     }
 }
 
@@ -35,6 +35,6 @@ impl UpdateLayout for Position {
     type State<'a> = ();
 
     fn update_layout(&self, _state: Self::State<'_>, layout: Layout) -> VuiResult<Layout> {
-        Ok(layout.clip(self.value.as_vector()))
+        Ok(layout.place(self.value.as_vector()))
     }
 }
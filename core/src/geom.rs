@@ -1,8 +1,12 @@
+mod direction;
+mod orientation;
 mod point;
 mod rect;
 mod size;
 mod vector;
 
+pub use direction::Direction;
+pub use orientation::Orientation;
 pub use point::Point;
 pub use rect::Rect;
 pub use size::Size;
@@ -0,0 +1,64 @@
+use crate::geom::{Rect, Size, Vector};
+
+/// The direction in which text runs, and in which components are placed horizontally.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub enum Direction {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Direction {
+    pub fn is_rtl(&self) -> bool {
+        *self == Direction::RightToLeft
+    }
+
+    /// Like [`Rect::clip()`], but measuring the horizontal offset from the right edge of `rect`
+    /// in right-to-left direction.
+    pub fn clip(&self, rect: Rect, vector: Vector) -> Rect {
+        match self {
+            Direction::LeftToRight => rect.clip(vector),
+            Direction::RightToLeft => rect.clip(Vector::new(-vector.x, vector.y)),
+        }
+    }
+
+    /// Like [`Rect::resize_clipped()`], but keeping the right edge of `rect` in place in
+    /// right-to-left direction.
+    pub fn resize_clipped(&self, rect: Rect, size: Size) -> Rect {
+        let resized = rect.resize_clipped(size);
+        match self {
+            Direction::LeftToRight => resized,
+            Direction::RightToLeft => {
+                resized.translate(Vector::new(rect.size.width - resized.size.width, 0))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mirrored_clip_and_resize() {
+        let rect = Rect::from_xywh(100, 50, 200, 100);
+        let rtl = Direction::RightToLeft;
+        assert_eq!(
+            Rect::from_xywh(100, 60, 190, 90),
+            rtl.clip(rect, Vector::new(10, 10))
+        );
+        assert_eq!(
+            Rect::from_xywh(110, 50, 190, 100),
+            rtl.clip(rect, Vector::new(-10, 0))
+        );
+        assert_eq!(
+            Rect::from_xywh(220, 50, 80, 40),
+            rtl.resize_clipped(rect, Size::new(80, 40))
+        );
+        assert_eq!(rect, rtl.resize_clipped(rect, Size::new(300, 300)));
+        assert_eq!(
+            Rect::from_xywh(100, 50, 80, 40),
+            Direction::LeftToRight.resize_clipped(rect, Size::new(80, 40))
+        );
+    }
+}
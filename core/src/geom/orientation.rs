@@ -0,0 +1,47 @@
+use crate::geom::{Point, Size, Vector};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+impl Orientation {
+    /// The coordinate of `point` along this orientation.
+    pub fn along(&self, point: Point) -> i32 {
+        match self {
+            Orientation::Horizontal => point.x,
+            Orientation::Vertical => point.y,
+        }
+    }
+
+    /// The extent of `size` along this orientation.
+    pub fn length(&self, size: Size) -> i32 {
+        match self {
+            Orientation::Horizontal => size.width,
+            Orientation::Vertical => size.height,
+        }
+    }
+
+    /// The extent of `size` perpendicular to this orientation.
+    pub fn breadth(&self, size: Size) -> i32 {
+        match self {
+            Orientation::Horizontal => size.height,
+            Orientation::Vertical => size.width,
+        }
+    }
+
+    pub fn vector(&self, along: i32, across: i32) -> Vector {
+        match self {
+            Orientation::Horizontal => Vector::new(along, across),
+            Orientation::Vertical => Vector::new(across, along),
+        }
+    }
+
+    pub fn size(&self, length: i32, breadth: i32) -> Size {
+        match self {
+            Orientation::Horizontal => Size::new(length, breadth),
+            Orientation::Vertical => Size::new(breadth, length),
+        }
+    }
+}
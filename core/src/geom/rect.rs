@@ -1,7 +1,7 @@
 use crate::geom::{Point, Size, Vector};
 use std::fmt::{Debug, Formatter};
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
 pub struct Rect {
     pub origin: Point,
     pub size: Size,
@@ -73,6 +73,15 @@ impl Rect {
         let h = self.size.height + 2 * height;
         Self::from_xywh(x, y, w, h)
     }
+
+    /// Moves the rectangle the minimal distance so that it lies within `bounds`. If it is larger
+    /// than `bounds`, it is aligned with the top left corner of `bounds`.
+    pub fn constrain(&self, bounds: Rect) -> Self {
+        let limit = bounds.limit() - self.size.as_vector();
+        let x = self.origin.x.min(limit.x).max(bounds.origin.x);
+        let y = self.origin.y.min(limit.y).max(bounds.origin.y);
+        Self::new((x, y).into(), self.size)
+    }
 }
 
 impl From<(i32, i32, i32, i32)> for Rect {
@@ -129,4 +138,23 @@ mod test {
         assert_eq!(Rect::from_xywh(8, 23, 34, -1), rect.inflate(2, -3));
         assert_eq!(Rect::from_xywh(12, 23, 26, -1), rect.inflate(-2, -3));
     }
+
+    #[test]
+    fn test_constrain() {
+        let bounds = Rect::from_xywh(0, 0, 100, 50);
+        let rect = Rect::from_xywh(10, 20, 30, 5);
+        assert_eq!(rect, rect.constrain(bounds));
+        assert_eq!(
+            Rect::from_xywh(70, 45, 30, 5),
+            Rect::from_xywh(90, 48, 30, 5).constrain(bounds)
+        );
+        assert_eq!(
+            Rect::from_xywh(0, 0, 30, 5),
+            Rect::from_xywh(-5, -1, 30, 5).constrain(bounds)
+        );
+        assert_eq!(
+            Rect::from_xywh(0, 10, 120, 5),
+            Rect::from_xywh(10, 10, 120, 5).constrain(bounds)
+        );
+    }
 }
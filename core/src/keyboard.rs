@@ -0,0 +1,10 @@
+mod key;
+mod modifiers;
+mod shortcut;
+
+pub use key::Key;
+pub use modifiers::Modifiers;
+pub use shortcut::{
+    KeyChord, ScopeId, Shortcut, ShortcutConflict, ShortcutMatch, ShortcutScope, Shortcuts,
+    DEFAULT_SEQUENCE_TIMEOUT,
+};
@@ -0,0 +1,24 @@
+/// A backend-neutral key identifier.
+///
+/// Printable keys are reported through [`Key::Character`] using their unshifted character (e.g.
+/// `'a'` for the A-key, regardless of Shift or Caps Lock). Keys that have no character
+/// representation get their own variant.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Key {
+    Character(char),
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Function(u8),
+}
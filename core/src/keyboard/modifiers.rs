@@ -0,0 +1,112 @@
+use crate::bitops::{ClearBits, IsSet, SetBits};
+use std::fmt::{Debug, Formatter};
+use std::ops::BitOr;
+
+/// A set of modifier keys. Left and right variants of a modifier are not distinguished.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Modifiers {
+    flags: u8,
+}
+
+impl Modifiers {
+    pub const NONE: Self = Self { flags: 0 };
+    pub const SHIFT: Self = Self { flags: 0b0001 };
+    pub const CTRL: Self = Self { flags: 0b0010 };
+    pub const ALT: Self = Self { flags: 0b0100 };
+    pub const SUPER: Self = Self { flags: 0b1000 };
+
+    pub fn is_empty(&self) -> bool {
+        self.flags == 0
+    }
+
+    /// Whether all modifiers in `other` are part of this set.
+    pub fn contains(&self, other: Self) -> bool {
+        self.flags.is_set(other.flags)
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.flags.set_bits(other.flags);
+    }
+
+    pub fn remove(&mut self, other: Self) {
+        self.flags.clear_bits(other.flags);
+    }
+
+    pub fn shift(&self) -> bool {
+        self.contains(Self::SHIFT)
+    }
+
+    pub fn ctrl(&self) -> bool {
+        self.contains(Self::CTRL)
+    }
+
+    pub fn alt(&self) -> bool {
+        self.contains(Self::ALT)
+    }
+
+    pub fn super_key(&self) -> bool {
+        self.contains(Self::SUPER)
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self {
+            flags: self.flags | rhs.flags,
+        }
+    }
+}
+
+impl Debug for Modifiers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let names = [
+            (Self::SHIFT, "Shift"),
+            (Self::CTRL, "Ctrl"),
+            (Self::ALT, "Alt"),
+            (Self::SUPER, "Super"),
+        ];
+        let mut first = true;
+        for (modifier, name) in names {
+            if self.contains(modifier) {
+                if !first {
+                    write!(f, "+")?;
+                }
+                write!(f, "{name}")?;
+                first = false;
+            }
+        }
+
+        if first {
+            write!(f, "-")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_modifiers() {
+        let mut mods = Modifiers::CTRL | Modifiers::SHIFT;
+        assert!(mods.ctrl());
+        assert!(mods.shift());
+        assert!(!mods.alt());
+        assert!(mods.contains(Modifiers::CTRL));
+        assert!(mods.contains(Modifiers::NONE));
+        assert!(!mods.contains(Modifiers::CTRL | Modifiers::ALT));
+        assert_eq!("Shift+Ctrl", format!("{mods:?}"));
+
+        mods.remove(Modifiers::SHIFT);
+        assert_eq!(Modifiers::CTRL, mods);
+
+        mods.insert(Modifiers::SUPER);
+        assert!(mods.super_key());
+        mods.remove(Modifiers::CTRL | Modifiers::SUPER);
+        assert!(mods.is_empty());
+        assert_eq!("-", format!("{mods:?}"));
+    }
+}
@@ -0,0 +1,636 @@
+use crate::action::ActionId;
+use crate::keyboard::{Key, Modifiers};
+use crate::time::Timestamp;
+use crate::{GlobalEvent, VuiError, WindowEvent, WindowId};
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How long the registry waits for the next key of a multi-stroke shortcut.
+pub const DEFAULT_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// A key pressed together with a set of modifiers, such as Ctrl+S.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct KeyChord {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+impl KeyChord {
+    pub fn new(key: Key, modifiers: Modifiers) -> Self {
+        Self { key, modifiers }
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // The order used by most platforms' menus, which differs from the order of the flags.
+        let names = [
+            (Modifiers::CTRL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::SUPER, "Super"),
+        ];
+        for (modifier, name) in names {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        match self.key {
+            Key::Character(' ') => write!(f, "Space"),
+            Key::Character(chr) => write!(f, "{}", chr.to_uppercase()),
+            Key::Enter => write!(f, "Enter"),
+            Key::Escape => write!(f, "Esc"),
+            Key::Tab => write!(f, "Tab"),
+            Key::Backspace => write!(f, "Backspace"),
+            Key::Delete => write!(f, "Del"),
+            Key::Insert => write!(f, "Ins"),
+            Key::Left => write!(f, "Left"),
+            Key::Right => write!(f, "Right"),
+            Key::Up => write!(f, "Up"),
+            Key::Down => write!(f, "Down"),
+            Key::Home => write!(f, "Home"),
+            Key::End => write!(f, "End"),
+            Key::PageUp => write!(f, "PageUp"),
+            Key::PageDown => write!(f, "PageDown"),
+            Key::Function(n) => write!(f, "F{n}"),
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = VuiError;
+
+    /// Parses a chord like `Ctrl+Shift+S`. Names are case-insensitive, and `Ctrl++` is the
+    /// plus key.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (modifiers, key) = match s.strip_suffix("++") {
+            Some(rest) => (Some(rest), "+"),
+            None if s == "+" => (None, "+"),
+            None => match s.rsplit_once('+') {
+                Some((rest, key)) => (Some(rest), key),
+                None => (None, s),
+            },
+        };
+
+        let mut chord = KeyChord::new(parse_key(key)?, Modifiers::NONE);
+        for name in modifiers.into_iter().flat_map(|m| m.split('+')) {
+            let modifier = match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => Modifiers::CTRL,
+                "alt" => Modifiers::ALT,
+                "shift" => Modifiers::SHIFT,
+                "super" | "win" | "cmd" => Modifiers::SUPER,
+                _ => return Err(format!("Unknown modifier in shortcut: {name:?}").into()),
+            };
+            chord.modifiers.insert(modifier);
+        }
+        Ok(chord)
+    }
+}
+
+fn parse_key(name: &str) -> Result<Key, VuiError> {
+    let mut chars = name.chars();
+    if let (Some(chr), None) = (chars.next(), chars.next()) {
+        // Character keys are reported unshifted.
+        return Ok(Key::Character(chr.to_ascii_lowercase()));
+    }
+
+    let key = match name.to_ascii_lowercase().as_str() {
+        "space" => Key::Character(' '),
+        "enter" | "return" => Key::Enter,
+        "esc" | "escape" => Key::Escape,
+        "tab" => Key::Tab,
+        "backspace" => Key::Backspace,
+        "del" | "delete" => Key::Delete,
+        "ins" | "insert" => Key::Insert,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" | "pgup" => Key::PageUp,
+        "pagedown" | "pgdn" => Key::PageDown,
+        lower => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            Some(n @ 1..=24) => Key::Function(n),
+            _ => return Err(format!("Unknown key in shortcut: {name:?}").into()),
+        },
+    };
+    Ok(key)
+}
+
+/// A sequence of one or more key chords, such as Ctrl+S or Ctrl+K Ctrl+C.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Shortcut {
+    chords: Vec<KeyChord>,
+}
+
+impl Shortcut {
+    pub fn new(chord: KeyChord) -> Self {
+        Self {
+            chords: vec![chord],
+        }
+    }
+
+    /// Appends a chord that has to be pressed after the ones so far.
+    pub fn then(mut self, chord: KeyChord) -> Self {
+        self.chords.push(chord);
+        self
+    }
+
+    pub fn chords(&self) -> &[KeyChord] {
+        &self.chords
+    }
+
+    /// Whether pressing this shortcut passes through `other` or the other way round, which would
+    /// make the two ambiguous.
+    fn overlaps(&self, other: &Shortcut) -> bool {
+        self.chords.starts_with(&other.chords) || other.chords.starts_with(&self.chords)
+    }
+}
+
+impl From<KeyChord> for Shortcut {
+    fn from(chord: KeyChord) -> Self {
+        Self::new(chord)
+    }
+}
+
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, chord) in self.chords.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{chord}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = VuiError;
+
+    /// Parses a whitespace-separated sequence of chords like `Ctrl+K Ctrl+C`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chords = s
+            .split_whitespace()
+            .map(KeyChord::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        if chords.is_empty() {
+            return Err("Empty shortcut".to_string().into());
+        }
+        Ok(Self { chords })
+    }
+}
+
+/// Identifies a part of the component tree that has shortcuts of its own. Created through
+/// [`Shortcuts::new_scope()`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ScopeId(u64);
+
+/// Where a shortcut is active.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ShortcutScope {
+    /// In every window.
+    Global,
+    /// While the window has the keyboard focus.
+    Window(WindowId),
+    /// While the focused component is part of the subtree, see [`Shortcuts::set_focus_path()`].
+    Subtree(ScopeId),
+}
+
+/// The outcome of passing a key press to [`Shortcuts::handle_event()`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ShortcutMatch {
+    /// The key is not part of any shortcut and should be passed on to the components.
+    NoMatch,
+    /// The key started or continued a multi-stroke shortcut.
+    Pending,
+    /// The key didn't complete the pending multi-stroke shortcut. Like the keys before it, it
+    /// should not be passed on.
+    Aborted,
+    /// The key completed a shortcut.
+    Action(ActionId),
+}
+
+impl ShortcutMatch {
+    /// Whether the key was used up by the registry.
+    pub fn is_consumed(&self) -> bool {
+        !matches!(self, ShortcutMatch::NoMatch)
+    }
+
+    pub fn action(&self) -> Option<ActionId> {
+        match self {
+            ShortcutMatch::Action(action) => Some(*action),
+            _ => None,
+        }
+    }
+}
+
+/// Returned when registering a shortcut that is ambiguous with one that is already registered
+/// in the same scope.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ShortcutConflict {
+    pub scope: ShortcutScope,
+    pub shortcut: Shortcut,
+    /// The registered shortcut that is equal to, a prefix of, or prefixed by `shortcut`.
+    pub existing: Shortcut,
+    /// The action `existing` is bound to.
+    pub action: ActionId,
+}
+
+impl Display for ShortcutConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Shortcut {} conflicts with {} (bound to {:?}) in scope {:?}",
+            self.shortcut, self.existing, self.action, self.scope
+        )
+    }
+}
+
+impl std::error::Error for ShortcutConflict {}
+
+#[derive(Debug, Clone)]
+struct Binding {
+    scope: ShortcutScope,
+    shortcut: Shortcut,
+    action: ActionId,
+}
+
+#[derive(Debug)]
+struct Registry {
+    next_scope: u64,
+    bindings: Vec<Binding>,
+    /// From the outermost to the innermost scope containing the focused component.
+    focus_path: Vec<ScopeId>,
+    pending: Vec<KeyChord>,
+    pending_since: Timestamp,
+    sequence_timeout: Duration,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self {
+            next_scope: 0,
+            bindings: Vec::new(),
+            focus_path: Vec::new(),
+            pending: Vec::new(),
+            pending_since: Timestamp::default(),
+            sequence_timeout: DEFAULT_SEQUENCE_TIMEOUT,
+        }
+    }
+}
+
+impl Registry {
+    /// The active scopes, from the one taking the highest precedence to the lowest.
+    fn active_scopes(&self, window: WindowId) -> Vec<ShortcutScope> {
+        let subtrees = self.focus_path.iter().rev().copied();
+        subtrees
+            .map(ShortcutScope::Subtree)
+            .chain([ShortcutScope::Window(window), ShortcutScope::Global])
+            .collect()
+    }
+
+    fn handle_key(&mut self, chord: KeyChord, window: WindowId, now: Timestamp) -> ShortcutMatch {
+        let mut keys = std::mem::take(&mut self.pending);
+        if now.duration_since(self.pending_since) > self.sequence_timeout {
+            keys.clear();
+        }
+        let continuing = !keys.is_empty();
+        keys.push(chord);
+
+        // The innermost scope that knows the keys decides, so that subtrees can shadow shortcuts
+        // of the window, which can in turn shadow global ones.
+        for scope in self.active_scopes(window) {
+            let mut is_prefix = false;
+            for binding in self.bindings.iter().filter(|b| b.scope == scope) {
+                if binding.shortcut.chords == keys {
+                    return ShortcutMatch::Action(binding.action);
+                }
+                is_prefix |= binding.shortcut.chords.starts_with(&keys);
+            }
+
+            if is_prefix {
+                self.pending = keys;
+                self.pending_since = now;
+                return ShortcutMatch::Pending;
+            }
+        }
+
+        if continuing {
+            ShortcutMatch::Aborted
+        } else {
+            ShortcutMatch::NoMatch
+        }
+    }
+}
+
+/// The keyboard shortcut registry.
+///
+/// Maps shortcuts to the actions they invoke, independent of which component has the keyboard
+/// focus. This is a cheaply cloneable handle, so that components can register shortcuts of their
+/// own. The event loop passes every event to [`Shortcuts::handle_event()`] before dispatching it,
+/// and only dispatches key presses the registry didn't consume.
+///
+/// When a key press matches in several active scopes, the innermost focused subtree takes
+/// precedence, followed by the window and finally the global scope. Within a scope, shortcuts
+/// must not be ambiguous, which is checked when registering them.
+#[derive(Debug, Clone, Default)]
+pub struct Shortcuts {
+    registry: Rc<RefCell<Registry>>,
+}
+
+impl Shortcuts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_sequence_timeout(&self, timeout: Duration) {
+        self.registry.borrow_mut().sequence_timeout = timeout;
+    }
+
+    /// Creates a scope for shortcuts that are only active while the focus is within a subtree.
+    pub fn new_scope(&self) -> ScopeId {
+        let mut registry = self.registry.borrow_mut();
+        let id = ScopeId(registry.next_scope);
+        registry.next_scope += 1;
+        id
+    }
+
+    /// Tells the registry which subtree scopes contain the focused component, from the outermost
+    /// to the innermost one. Components track their focus themselves, so the application is
+    /// expected to call this whenever the focus changes.
+    pub fn set_focus_path(&self, path: Vec<ScopeId>) {
+        self.registry.borrow_mut().focus_path = path;
+    }
+
+    /// Binds `shortcut` to `action` in `scope`.
+    ///
+    /// Fails if the scope already contains the same shortcut, or one that either starts with or is
+    /// the start of `shortcut`, since the registry couldn't tell them apart while they are typed.
+    pub fn register(
+        &self,
+        scope: ShortcutScope,
+        shortcut: Shortcut,
+        action: ActionId,
+    ) -> Result<(), ShortcutConflict> {
+        let mut registry = self.registry.borrow_mut();
+        let conflict = registry
+            .bindings
+            .iter()
+            .find(|b| b.scope == scope && b.shortcut.overlaps(&shortcut));
+        if let Some(existing) = conflict {
+            return Err(ShortcutConflict {
+                scope,
+                shortcut,
+                existing: existing.shortcut.clone(),
+                action: existing.action,
+            });
+        }
+
+        registry.bindings.push(Binding {
+            scope,
+            shortcut,
+            action,
+        });
+        Ok(())
+    }
+
+    /// Removes the shortcut from the scope, returning the action it was bound to.
+    pub fn unregister(&self, scope: ShortcutScope, shortcut: &Shortcut) -> Option<ActionId> {
+        let mut registry = self.registry.borrow_mut();
+        let index = registry
+            .bindings
+            .iter()
+            .position(|b| b.scope == scope && b.shortcut == *shortcut)?;
+        Some(registry.bindings.remove(index).action)
+    }
+
+    /// Removes all shortcuts of the scope.
+    pub fn clear_scope(&self, scope: ShortcutScope) {
+        self.registry
+            .borrow_mut()
+            .bindings
+            .retain(|b| b.scope != scope);
+    }
+
+    /// The first shortcut registered for `action`, e.g. to show it next to a menu item.
+    pub fn shortcut_for(&self, action: ActionId) -> Option<Shortcut> {
+        self.registry
+            .borrow()
+            .bindings
+            .iter()
+            .find(|b| b.action == action)
+            .map(|b| b.shortcut.clone())
+    }
+
+    /// The keys typed so far of a multi-stroke shortcut, e.g. to show them in a status bar.
+    pub fn pending(&self) -> Option<Shortcut> {
+        let registry = self.registry.borrow();
+        (!registry.pending.is_empty()).then(|| Shortcut {
+            chords: registry.pending.clone(),
+        })
+    }
+
+    /// Matches key presses in `window`, the window with the keyboard focus, against the registered
+    /// shortcuts.
+    ///
+    /// Other events are never consumed, but pressing a mouse button or losing the focus discards
+    /// a pending multi-stroke shortcut.
+    pub fn handle_event(&self, event: &GlobalEvent, window: WindowId) -> ShortcutMatch {
+        let mut registry = self.registry.borrow_mut();
+        match event {
+            GlobalEvent::KeyDown(key, modifiers, timestamp) => {
+                registry.handle_key(KeyChord::new(*key, *modifiers), window, *timestamp)
+            }
+            GlobalEvent::Window(WindowEvent::FocusLost, _) | GlobalEvent::MouseButtonDown(..) => {
+                registry.pending.clear();
+                ShortcutMatch::NoMatch
+            }
+            _ => ShortcutMatch::NoMatch,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const WINDOW: WindowId = WindowId(1);
+
+    fn shortcut(s: &str) -> Shortcut {
+        s.parse().unwrap()
+    }
+
+    fn press(shortcuts: &Shortcuts, chord: &str, millis: u64) -> ShortcutMatch {
+        let chord: KeyChord = chord.parse().unwrap();
+        let event =
+            GlobalEvent::KeyDown(chord.key, chord.modifiers, Timestamp::from_millis(millis));
+        shortcuts.handle_event(&event, WINDOW)
+    }
+
+    #[test]
+    fn test_parse() {
+        let s = shortcut("ctrl+shift+s");
+        assert_eq!(
+            &[KeyChord::new(
+                Key::Character('s'),
+                Modifiers::CTRL | Modifiers::SHIFT
+            )],
+            s.chords()
+        );
+        assert_eq!("Ctrl+Shift+S", s.to_string());
+
+        let s = shortcut("Ctrl+K  Ctrl+Alt+Del");
+        assert_eq!(2, s.chords().len());
+        assert_eq!("Ctrl+K Ctrl+Alt+Del", s.to_string());
+
+        assert_eq!(
+            KeyChord::new(Key::Character('+'), Modifiers::CTRL),
+            "Ctrl++".parse().unwrap()
+        );
+        assert_eq!(
+            KeyChord::new(Key::Function(4), Modifiers::ALT),
+            "Alt+F4".parse().unwrap()
+        );
+        assert_eq!("Space", shortcut("space").to_string());
+
+        assert!("".parse::<Shortcut>().is_err());
+        assert!("Hyper+A".parse::<Shortcut>().is_err());
+        assert!("Ctrl+Foo".parse::<Shortcut>().is_err());
+        assert!("F25".parse::<Shortcut>().is_err());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let shortcuts = Shortcuts::new();
+        let scope = ShortcutScope::Window(WINDOW);
+        shortcuts
+            .register(scope, shortcut("Ctrl+S"), ActionId(1))
+            .unwrap();
+        shortcuts
+            .register(scope, shortcut("Ctrl+K Ctrl+C"), ActionId(2))
+            .unwrap();
+
+        let err = shortcuts
+            .register(scope, shortcut("Ctrl+S"), ActionId(3))
+            .unwrap_err();
+        assert_eq!(ActionId(1), err.action);
+
+        // A prefix of a sequence, and a sequence starting with a shortcut.
+        assert!(shortcuts
+            .register(scope, shortcut("Ctrl+K"), ActionId(3))
+            .is_err());
+        assert!(shortcuts
+            .register(scope, shortcut("Ctrl+S Ctrl+A"), ActionId(3))
+            .is_err());
+
+        // Same shortcut in another scope is allowed.
+        shortcuts
+            .register(ShortcutScope::Global, shortcut("Ctrl+S"), ActionId(3))
+            .unwrap();
+
+        assert_eq!(
+            Some(ActionId(1)),
+            shortcuts.unregister(scope, &shortcut("Ctrl+S"))
+        );
+        shortcuts
+            .register(scope, shortcut("Ctrl+S Ctrl+A"), ActionId(3))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sequences() {
+        let shortcuts = Shortcuts::new();
+        let scope = ShortcutScope::Global;
+        shortcuts
+            .register(scope, shortcut("Ctrl+K Ctrl+C"), ActionId(1))
+            .unwrap();
+        shortcuts
+            .register(scope, shortcut("Ctrl+S"), ActionId(2))
+            .unwrap();
+
+        assert_eq!(ShortcutMatch::NoMatch, press(&shortcuts, "a", 0));
+        assert_eq!(
+            ShortcutMatch::Action(ActionId(2)),
+            press(&shortcuts, "Ctrl+S", 0)
+        );
+
+        assert_eq!(ShortcutMatch::Pending, press(&shortcuts, "Ctrl+K", 0));
+        assert_eq!(Some(shortcut("Ctrl+K")), shortcuts.pending());
+        assert_eq!(
+            ShortcutMatch::Action(ActionId(1)),
+            press(&shortcuts, "Ctrl+C", 100)
+        );
+        assert_eq!(None, shortcuts.pending());
+
+        // A key that doesn't continue the sequence is swallowed.
+        assert_eq!(ShortcutMatch::Pending, press(&shortcuts, "Ctrl+K", 200));
+        assert_eq!(ShortcutMatch::Aborted, press(&shortcuts, "Ctrl+S", 300));
+        assert_eq!(None, shortcuts.pending());
+
+        // After the timeout, the next key starts over.
+        assert_eq!(ShortcutMatch::Pending, press(&shortcuts, "Ctrl+K", 1000));
+        assert_eq!(ShortcutMatch::NoMatch, press(&shortcuts, "Ctrl+C", 3000));
+
+        // Clicking discards the pending keys.
+        assert_eq!(ShortcutMatch::Pending, press(&shortcuts, "Ctrl+K", 4000));
+        shortcuts.handle_event(
+            &GlobalEvent::MouseButtonDown(
+                crate::mouse::MouseButton::Left,
+                Default::default(),
+                Modifiers::NONE,
+                Timestamp::from_millis(4100),
+            ),
+            WINDOW,
+        );
+        assert_eq!(ShortcutMatch::NoMatch, press(&shortcuts, "Ctrl+C", 4200));
+    }
+
+    #[test]
+    fn test_scopes() {
+        let shortcuts = Shortcuts::new();
+        let outer = shortcuts.new_scope();
+        let inner = shortcuts.new_scope();
+        let other_window = ShortcutScope::Window(WindowId(2));
+        shortcuts
+            .register(ShortcutScope::Global, shortcut("Ctrl+C"), ActionId(1))
+            .unwrap();
+        shortcuts
+            .register(ShortcutScope::Window(WINDOW), shortcut("F5"), ActionId(2))
+            .unwrap();
+        shortcuts
+            .register(other_window, shortcut("F6"), ActionId(3))
+            .unwrap();
+        shortcuts
+            .register(
+                ShortcutScope::Subtree(outer),
+                shortcut("Ctrl+C"),
+                ActionId(4),
+            )
+            .unwrap();
+        shortcuts
+            .register(ShortcutScope::Subtree(inner), shortcut("F5"), ActionId(5))
+            .unwrap();
+
+        assert_eq!(Some(ActionId(1)), press(&shortcuts, "Ctrl+C", 0).action());
+        assert_eq!(Some(ActionId(2)), press(&shortcuts, "F5", 0).action());
+        assert_eq!(ShortcutMatch::NoMatch, press(&shortcuts, "F6", 0));
+
+        shortcuts.set_focus_path(vec![outer]);
+        assert_eq!(Some(ActionId(4)), press(&shortcuts, "Ctrl+C", 0).action());
+        assert_eq!(Some(ActionId(2)), press(&shortcuts, "F5", 0).action());
+
+        shortcuts.set_focus_path(vec![outer, inner]);
+        assert_eq!(Some(ActionId(4)), press(&shortcuts, "Ctrl+C", 0).action());
+        assert_eq!(Some(ActionId(5)), press(&shortcuts, "F5", 0).action());
+
+        assert_eq!(Some(shortcut("F5")), shortcuts.shortcut_for(ActionId(2)));
+        shortcuts.clear_scope(ShortcutScope::Subtree(inner));
+        assert_eq!(Some(ActionId(2)), press(&shortcuts, "F5", 0).action());
+    }
+}
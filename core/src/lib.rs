@@ -1,12 +1,25 @@
 use crate::component::{ComponentEvent, FramedPosition};
-use crate::geom::{Point, Rect};
+use crate::geom::{Point, Rect, Size, Vector};
+use crate::keyboard::{Key, Modifiers};
 use crate::mouse::MouseButton;
+use crate::time::Timestamp;
+use crate::timer::TimerId;
 use std::fmt::{Debug, Display, Formatter};
+use std::time::Duration;
 
+pub mod action;
+pub mod anim;
 pub mod bitops;
+pub mod cache;
+pub mod color;
 pub mod component;
 pub mod geom;
+pub mod keyboard;
 pub mod mouse;
+pub mod overlay;
+pub mod text;
+pub mod time;
+pub mod timer;
 
 pub type VuiResult<T> = Result<T, Box<dyn std::error::Error>>;
 
@@ -35,27 +48,92 @@ impl From<String> for VuiError {
     }
 }
 
+/// Identifies a window of the application. The values are assigned by the backend.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct WindowId(pub u32);
+
+/// Backend-neutral events concerning the window (as opposed to its content).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum WindowEvent {
+    Resized(Size),
+    FocusGained,
+    FocusLost,
+    Minimized,
+    Restored,
+    /// The pointer has left the window.
+    PointerLeft,
+    /// The user asked to close the window (e.g. using the close button in the title bar).
+    CloseRequested,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum GlobalEvent {
-    LoopStart,
-    MouseMotion(Point),
-    MouseButtonDown(MouseButton, Point),
-    MouseButtonUp(MouseButton, Point),
+    /// The start of an iteration of the event loop, carrying the current time and the time that
+    /// passed since the start of the previous iteration.
+    LoopStart(Timestamp, Duration),
+    MouseMotion(Point, Modifiers, Timestamp),
+    MouseButtonDown(MouseButton, Point, Modifiers, Timestamp),
+    MouseButtonUp(MouseButton, Point, Modifiers, Timestamp),
+    /// The mouse wheel was scrolled while the pointer was at the position. The amount is in
+    /// notches, with positive values scrolling up (away from the user) and to the right.
+    MouseWheel(Vector, Point, Modifiers, Timestamp),
+    KeyDown(Key, Modifiers, Timestamp),
+    KeyUp(Key, Modifiers, Timestamp),
+    Window(WindowEvent, Timestamp),
+    /// A timer that was started through [`timer::Timers`] went off.
+    Timer(TimerId, Timestamp),
 }
 
 impl GlobalEvent {
+    pub fn timestamp(&self) -> Timestamp {
+        match self {
+            GlobalEvent::LoopStart(ts, _)
+            | GlobalEvent::MouseMotion(_, _, ts)
+            | GlobalEvent::MouseButtonDown(_, _, _, ts)
+            | GlobalEvent::MouseButtonUp(_, _, _, ts)
+            | GlobalEvent::MouseWheel(_, _, _, ts)
+            | GlobalEvent::KeyDown(_, _, ts)
+            | GlobalEvent::KeyUp(_, _, ts)
+            | GlobalEvent::Window(_, ts)
+            | GlobalEvent::Timer(_, ts) => *ts,
+        }
+    }
+
+    /// The modifier keys that were held down when the event occurred. This is empty for
+    /// events that don't originate from the user's input devices.
+    pub fn modifiers(&self) -> Modifiers {
+        match self {
+            GlobalEvent::LoopStart(..) | GlobalEvent::Window(..) | GlobalEvent::Timer(..) => {
+                Modifiers::NONE
+            }
+            GlobalEvent::MouseMotion(_, mods, _)
+            | GlobalEvent::MouseButtonDown(_, _, mods, _)
+            | GlobalEvent::MouseButtonUp(_, _, mods, _)
+            | GlobalEvent::MouseWheel(_, _, mods, _)
+            | GlobalEvent::KeyDown(_, mods, _)
+            | GlobalEvent::KeyUp(_, mods, _) => *mods,
+        }
+    }
+
     pub fn into_component_event(self, comp_rect: Rect) -> ComponentEvent {
         match self {
-            GlobalEvent::LoopStart => ComponentEvent::LoopStart,
-            GlobalEvent::MouseMotion(pos) => {
-                ComponentEvent::MouseMotion(FramedPosition::new(pos, comp_rect))
+            GlobalEvent::LoopStart(ts, delta) => ComponentEvent::LoopStart(ts, delta),
+            GlobalEvent::MouseMotion(pos, mods, ts) => {
+                ComponentEvent::MouseMotion(FramedPosition::new(pos, comp_rect), mods, ts)
+            }
+            GlobalEvent::MouseButtonDown(btn, pos, mods, ts) => {
+                ComponentEvent::MouseButtonDown(btn, FramedPosition::new(pos, comp_rect), mods, ts)
             }
-            GlobalEvent::MouseButtonDown(btn, pos) => {
-                ComponentEvent::MouseButtonDown(btn, FramedPosition::new(pos, comp_rect))
+            GlobalEvent::MouseButtonUp(btn, pos, mods, ts) => {
+                ComponentEvent::MouseButtonUp(btn, FramedPosition::new(pos, comp_rect), mods, ts)
             }
-            GlobalEvent::MouseButtonUp(btn, pos) => {
-                ComponentEvent::MouseButtonUp(btn, FramedPosition::new(pos, comp_rect))
+            GlobalEvent::MouseWheel(amount, pos, mods, ts) => {
+                ComponentEvent::MouseWheel(amount, FramedPosition::new(pos, comp_rect), mods, ts)
             }
+            GlobalEvent::KeyDown(key, mods, ts) => ComponentEvent::KeyDown(key, mods, ts),
+            GlobalEvent::KeyUp(key, mods, ts) => ComponentEvent::KeyUp(key, mods, ts),
+            GlobalEvent::Window(evt, ts) => ComponentEvent::Window(evt, ts),
+            GlobalEvent::Timer(id, ts) => ComponentEvent::Timer(id, ts),
         }
     }
 }
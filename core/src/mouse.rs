@@ -1,7 +1,11 @@
 mod button;
 mod click_state;
+mod cursor;
+mod drag_state;
 mod hover_state;
 
 pub use button::MouseButton;
-pub use click_state::ClickStates;
+pub use click_state::{ClickConfig, ClickStates};
+pub use cursor::CursorShape;
+pub use drag_state::{DragStates, DEFAULT_DRAG_THRESHOLD};
 pub use hover_state::HoverState;
@@ -3,4 +3,27 @@ pub enum MouseButton {
     Left,
     Middle,
     Right,
+    /// The first extra button, commonly mapped to "back".
+    X1,
+    /// The second extra button, commonly mapped to "forward".
+    X2,
 }
+
+impl MouseButton {
+    pub const COUNT: usize = 5;
+    pub const ALL: [MouseButton; Self::COUNT] = [
+        MouseButton::Left,
+        MouseButton::Middle,
+        MouseButton::Right,
+        MouseButton::X1,
+        MouseButton::X2,
+    ];
+
+    /// The bit for this button in a mask of button states.
+    pub(crate) fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+// Button states are stored as `u8` bit masks.
+const _: () = assert!(MouseButton::COUNT <= u8::BITS as usize);
@@ -1,11 +1,46 @@
 use crate::bitops::{ClearBits, IsSet, SetBits};
+use crate::geom::Point;
 use crate::mouse::MouseButton;
+use crate::time::Timestamp;
 use std::fmt::{Debug, Formatter};
+use std::time::Duration;
+
+/// Configures how presses are grouped into multi-clicks and when a press becomes a long press.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ClickConfig {
+    /// The maximum time between two presses for them to count as a multi-click.
+    pub interval: Duration,
+    /// The maximum distance (in pixels) between two presses for them to count as a multi-click.
+    pub distance: i32,
+    /// The time a button has to be held down for a long press.
+    pub long_press: Duration,
+}
+
+impl Default for ClickConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(500),
+            distance: 4,
+            long_press: Duration::from_millis(800),
+        }
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+struct Press {
+    timestamp: Timestamp,
+    position: Point,
+    count: u32,
+}
 
 #[derive(Default, Clone, Eq, PartialEq)]
 pub struct ClickStates {
     click_state: u8,
     event_state: u8,
+    long_press_state: u8,
+    long_press_event_state: u8,
+    presses: [Press; MouseButton::COUNT],
+    config: ClickConfig,
 }
 
 impl Debug for ClickStates {
@@ -14,55 +49,124 @@ impl Debug for ClickStates {
     }
 }
 
-fn mask_for(btn: MouseButton) -> u8 {
-    1 << btn as u8
-}
-
 impl ClickStates {
+    pub fn with_config(config: ClickConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn config(&self) -> &ClickConfig {
+        &self.config
+    }
+
     pub fn clear_event_state(&mut self) {
         self.event_state = 0;
+        self.long_press_event_state = 0;
     }
 
     pub fn has_click_started(&self, btn: MouseButton) -> bool {
-        let mask = mask_for(btn);
+        let mask = btn.mask();
         self.click_state.is_set(mask) && self.event_state.is_set(mask)
     }
 
     pub fn has_click_completed(&self, btn: MouseButton) -> bool {
-        let mask = mask_for(btn);
+        let mask = btn.mask();
         !self.click_state.is_set(mask) && self.event_state.is_set(mask)
     }
 
     pub fn is_up(&self, btn: MouseButton) -> bool {
-        !self.click_state.is_set(mask_for(btn))
+        !self.click_state.is_set(btn.mask())
     }
 
     pub fn is_down(&self, btn: MouseButton) -> bool {
-        self.click_state.is_set(mask_for(btn))
+        self.click_state.is_set(btn.mask())
+    }
+
+    /// The number of presses in the current (or last) multi-click sequence for `btn`, e.g. `2`
+    /// for the second press of a double click.
+    pub fn click_count(&self, btn: MouseButton) -> u32 {
+        self.presses[btn as usize].count
+    }
+
+    pub fn has_double_click_completed(&self, btn: MouseButton) -> bool {
+        self.has_click_completed(btn) && self.click_count(btn) == 2
+    }
+
+    pub fn has_long_press_started(&self, btn: MouseButton) -> bool {
+        let mask = btn.mask();
+        self.long_press_state.is_set(mask) && self.long_press_event_state.is_set(mask)
+    }
+
+    pub fn is_long_pressed(&self, btn: MouseButton) -> bool {
+        self.long_press_state.is_set(btn.mask())
     }
 
     pub fn click(&mut self, btn: MouseButton) {
-        let mask = mask_for(btn);
+        let mask = btn.mask();
         self.click_state |= mask;
         self.event_state |= mask;
+        self.long_press_state.clear_bits(mask);
+        self.long_press_event_state.clear_bits(mask);
+    }
+
+    /// Like [`Self::click()`], but also keeps track of multi-clicks and long presses.
+    pub fn click_at(&mut self, btn: MouseButton, position: Point, timestamp: Timestamp) {
+        self.click(btn);
+
+        let config = &self.config;
+        let last = &mut self.presses[btn as usize];
+        let delta = position - last.position;
+        let is_repeat = last.count > 0
+            && timestamp.duration_since(last.timestamp) <= config.interval
+            && delta.x.abs() <= config.distance
+            && delta.y.abs() <= config.distance;
+
+        *last = Press {
+            timestamp,
+            position,
+            count: if is_repeat { last.count + 1 } else { 1 },
+        };
+    }
+
+    /// Detects long presses for buttons that have been held down since before `now`.
+    pub fn update_time(&mut self, now: Timestamp) {
+        for btn in MouseButton::ALL {
+            let mask = btn.mask();
+            if !self.click_state.is_set(mask) || self.long_press_state.is_set(mask) {
+                continue;
+            }
+
+            let press = &self.presses[btn as usize];
+            if press.count > 0 && now.duration_since(press.timestamp) >= self.config.long_press {
+                self.long_press_state.set_bits(mask);
+                self.long_press_event_state.set_bits(mask);
+            }
+        }
     }
 
+    /// Ends a click. Note that a long press does not complete a click.
     pub fn unclick(&mut self, btn: MouseButton) {
-        let mask = mask_for(btn);
+        let mask = btn.mask();
 
-        if self.click_state.is_set(mask) {
+        if self.click_state.is_set(mask) && !self.long_press_state.is_set(mask) {
             self.event_state.set_bits(mask);
         } else {
             self.event_state.clear_bits(mask);
         }
 
         self.click_state.clear_bits(mask);
+        self.long_press_state.clear_bits(mask);
     }
 
     pub fn clear(&mut self, btn: MouseButton) {
-        let mask = mask_for(btn);
+        let mask = btn.mask();
         self.click_state.clear_bits(mask);
         self.event_state.clear_bits(mask);
+        self.long_press_state.clear_bits(mask);
+        self.long_press_event_state.clear_bits(mask);
+        self.presses[btn as usize].count = 0;
     }
 }
 
@@ -126,4 +230,106 @@ mod test {
         assert!(!mcs.has_click_completed(MouseButton::Middle));
         mcs.check_flags(0b001, 0b001);
     }
+
+    #[test]
+    fn test_extra_buttons() {
+        let mut mcs = ClickStates::default();
+        mcs.click(MouseButton::X1);
+        mcs.click(MouseButton::X2);
+        assert_eq!(0b11000, mcs.click_state);
+        assert!(mcs.has_click_started(MouseButton::X1));
+        assert!(mcs.is_up(MouseButton::Right));
+
+        mcs.unclick(MouseButton::X2);
+        assert!(mcs.has_click_completed(MouseButton::X2));
+        assert!(mcs.is_down(MouseButton::X1));
+    }
+
+    #[test]
+    fn test_click_count() {
+        let mut mcs = ClickStates::default();
+        let t0 = Timestamp::from_millis(1000);
+
+        mcs.click_at(MouseButton::Left, (10, 10).into(), t0);
+        mcs.unclick(MouseButton::Left);
+        assert_eq!(1, mcs.click_count(MouseButton::Left));
+        assert!(!mcs.has_double_click_completed(MouseButton::Left));
+
+        mcs.clear_event_state();
+        mcs.click_at(
+            MouseButton::Left,
+            (12, 9).into(),
+            t0 + Duration::from_millis(300),
+        );
+        assert_eq!(2, mcs.click_count(MouseButton::Left));
+        mcs.unclick(MouseButton::Left);
+        assert!(mcs.has_double_click_completed(MouseButton::Left));
+
+        mcs.clear_event_state();
+        mcs.click_at(
+            MouseButton::Left,
+            (12, 9).into(),
+            t0 + Duration::from_millis(600),
+        );
+        assert_eq!(3, mcs.click_count(MouseButton::Left));
+        mcs.unclick(MouseButton::Left);
+        assert!(!mcs.has_double_click_completed(MouseButton::Left));
+
+        // Too late.
+        mcs.clear_event_state();
+        mcs.click_at(
+            MouseButton::Left,
+            (12, 9).into(),
+            t0 + Duration::from_millis(1200),
+        );
+        assert_eq!(1, mcs.click_count(MouseButton::Left));
+
+        // Too far away.
+        mcs.click_at(
+            MouseButton::Left,
+            (20, 9).into(),
+            t0 + Duration::from_millis(1300),
+        );
+        assert_eq!(1, mcs.click_count(MouseButton::Left));
+
+        // Other buttons are counted separately.
+        mcs.click_at(
+            MouseButton::Right,
+            (20, 9).into(),
+            t0 + Duration::from_millis(1400),
+        );
+        assert_eq!(1, mcs.click_count(MouseButton::Right));
+        assert_eq!(1, mcs.click_count(MouseButton::Left));
+    }
+
+    #[test]
+    fn test_long_press() {
+        let config = ClickConfig {
+            long_press: Duration::from_millis(500),
+            ..ClickConfig::default()
+        };
+        let mut mcs = ClickStates::with_config(config);
+        let t0 = Timestamp::from_millis(1000);
+
+        mcs.click_at(MouseButton::Left, (10, 10).into(), t0);
+        mcs.update_time(t0 + Duration::from_millis(499));
+        assert!(!mcs.is_long_pressed(MouseButton::Left));
+
+        mcs.update_time(t0 + Duration::from_millis(500));
+        assert!(mcs.has_long_press_started(MouseButton::Left));
+        assert!(mcs.is_long_pressed(MouseButton::Left));
+
+        mcs.clear_event_state();
+        mcs.update_time(t0 + Duration::from_millis(600));
+        assert!(!mcs.has_long_press_started(MouseButton::Left));
+        assert!(mcs.is_long_pressed(MouseButton::Left));
+
+        mcs.unclick(MouseButton::Left);
+        assert!(!mcs.is_long_pressed(MouseButton::Left));
+        assert!(!mcs.has_click_completed(MouseButton::Left));
+
+        // Buttons that are not down never become long presses.
+        mcs.update_time(t0 + Duration::from_millis(2000));
+        assert!(!mcs.is_long_pressed(MouseButton::Left));
+    }
 }
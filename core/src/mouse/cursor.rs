@@ -0,0 +1,15 @@
+/// The shape of the mouse cursor. Components report the shape they'd like while the pointer is
+/// over them, and the application applies it through its backend.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CursorShape {
+    #[default]
+    Arrow,
+    /// The I-beam shown over editable text.
+    Text,
+    /// The pointing hand shown over links.
+    Hand,
+    /// A double arrow pointing left and right.
+    ResizeHorizontal,
+    /// A double arrow pointing up and down.
+    ResizeVertical,
+}
@@ -0,0 +1,207 @@
+use crate::bitops::{ClearBits, IsSet, SetBits};
+use crate::geom::{Point, Vector};
+use crate::mouse::MouseButton;
+use std::fmt::{Debug, Formatter};
+
+/// The distance (in pixels) the pointer has to travel while pressed before a drag starts.
+pub const DEFAULT_DRAG_THRESHOLD: i32 = 4;
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+struct Drag {
+    origin: Point,
+    current: Point,
+}
+
+impl Drag {
+    fn offset(&self) -> Vector {
+        (self.current - self.origin).as_vector()
+    }
+}
+
+/// Tracks drag gestures per mouse button.
+///
+/// A drag is initiated by a press, but only starts once the pointer has moved further than the
+/// threshold distance from the press origin. From that point on the drag continues until the
+/// button is released, regardless of where the pointer goes.
+#[derive(Clone, Eq, PartialEq)]
+pub struct DragStates {
+    threshold: i32,
+    drags: [Drag; MouseButton::COUNT],
+    press_state: u8,
+    drag_state: u8,
+    event_state: u8,
+}
+
+impl Default for DragStates {
+    fn default() -> Self {
+        Self::new(DEFAULT_DRAG_THRESHOLD)
+    }
+}
+
+impl Debug for DragStates {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:08b}/{:08b}/{:08b} {:?}",
+            self.press_state, self.drag_state, self.event_state, self.drags
+        )
+    }
+}
+
+impl DragStates {
+    pub fn new(threshold: i32) -> Self {
+        Self {
+            threshold,
+            drags: Default::default(),
+            press_state: 0,
+            drag_state: 0,
+            event_state: 0,
+        }
+    }
+
+    pub fn threshold(&self) -> i32 {
+        self.threshold
+    }
+
+    pub fn clear_event_state(&mut self) {
+        self.event_state = 0;
+    }
+
+    pub fn has_drag_started(&self, btn: MouseButton) -> bool {
+        let mask = btn.mask();
+        self.drag_state.is_set(mask) && self.event_state.is_set(mask)
+    }
+
+    pub fn is_dragging(&self, btn: MouseButton) -> bool {
+        self.drag_state.is_set(btn.mask())
+    }
+
+    pub fn has_drag_ended(&self, btn: MouseButton) -> bool {
+        let mask = btn.mask();
+        !self.drag_state.is_set(mask) && self.event_state.is_set(mask)
+    }
+
+    /// Whether any button is pressed, whether or not it has started dragging.
+    pub fn is_tracking(&self) -> bool {
+        self.press_state != 0
+    }
+
+    /// The position where the (current or last) drag for `btn` was initiated.
+    pub fn origin(&self, btn: MouseButton) -> Point {
+        self.drags[btn as usize].origin
+    }
+
+    /// The offset of the pointer to the origin of the (current or last) drag for `btn`.
+    pub fn offset(&self, btn: MouseButton) -> Vector {
+        self.drags[btn as usize].offset()
+    }
+
+    pub fn press(&mut self, btn: MouseButton, pos: Point) {
+        let mask = btn.mask();
+        self.drags[btn as usize] = Drag {
+            origin: pos,
+            current: pos,
+        };
+        self.press_state.set_bits(mask);
+        self.drag_state.clear_bits(mask);
+        self.event_state.clear_bits(mask);
+    }
+
+    pub fn motion(&mut self, pos: Point) {
+        let threshold_sq = i64::from(self.threshold) * i64::from(self.threshold);
+        for btn in MouseButton::ALL {
+            let mask = btn.mask();
+            if !self.press_state.is_set(mask) {
+                continue;
+            }
+
+            let drag = &mut self.drags[btn as usize];
+            drag.current = pos;
+
+            let offset = drag.offset();
+            let (dx, dy) = (i64::from(offset.x), i64::from(offset.y));
+            if !self.drag_state.is_set(mask) && dx * dx + dy * dy > threshold_sq {
+                self.drag_state.set_bits(mask);
+                self.event_state.set_bits(mask);
+            }
+        }
+    }
+
+    pub fn release(&mut self, btn: MouseButton, pos: Point) {
+        let mask = btn.mask();
+        if !self.press_state.is_set(mask) {
+            return;
+        }
+
+        self.press_state.clear_bits(mask);
+        if self.drag_state.is_set(mask) {
+            self.drags[btn as usize].current = pos;
+            self.drag_state.clear_bits(mask);
+            self.event_state.set_bits(mask);
+        }
+    }
+
+    /// Aborts tracking `btn` without reporting the end of a drag.
+    pub fn cancel(&mut self, btn: MouseButton) {
+        let mask = btn.mask();
+        self.press_state.clear_bits(mask);
+        self.drag_state.clear_bits(mask);
+        self.event_state.clear_bits(mask);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_drag_states() {
+        let mut ds = DragStates::new(3);
+        ds.press(MouseButton::Left, (10, 10).into());
+        assert!(ds.is_tracking());
+        assert!(!ds.is_dragging(MouseButton::Left));
+
+        // Within the threshold.
+        ds.motion((12, 12).into());
+        assert!(!ds.is_dragging(MouseButton::Left));
+        assert_eq!(Vector::new(2, 2), ds.offset(MouseButton::Left));
+
+        ds.motion((14, 10).into());
+        assert!(ds.has_drag_started(MouseButton::Left));
+        assert!(ds.is_dragging(MouseButton::Left));
+        assert!(!ds.is_dragging(MouseButton::Right));
+
+        ds.clear_event_state();
+        ds.motion((-20, 30).into());
+        assert!(!ds.has_drag_started(MouseButton::Left));
+        assert!(ds.is_dragging(MouseButton::Left));
+        assert_eq!(Point::new(10, 10), ds.origin(MouseButton::Left));
+        assert_eq!(Vector::new(-30, 20), ds.offset(MouseButton::Left));
+
+        ds.release(MouseButton::Left, (-25, 30).into());
+        assert!(ds.has_drag_ended(MouseButton::Left));
+        assert!(!ds.is_dragging(MouseButton::Left));
+        assert!(!ds.is_tracking());
+        assert_eq!(Vector::new(-35, 20), ds.offset(MouseButton::Left));
+
+        ds.clear_event_state();
+        assert!(!ds.has_drag_ended(MouseButton::Left));
+    }
+
+    #[test]
+    fn test_drag_states_no_drag() {
+        let mut ds = DragStates::new(3);
+        ds.press(MouseButton::Right, (10, 10).into());
+        ds.motion((11, 10).into());
+        ds.release(MouseButton::Right, (11, 10).into());
+        assert!(!ds.has_drag_started(MouseButton::Right));
+        assert!(!ds.has_drag_ended(MouseButton::Right));
+
+        ds.press(MouseButton::Right, (10, 10).into());
+        ds.motion((20, 10).into());
+        assert!(ds.has_drag_started(MouseButton::Right));
+        ds.cancel(MouseButton::Right);
+        assert!(!ds.is_dragging(MouseButton::Right));
+        assert!(!ds.has_drag_ended(MouseButton::Right));
+    }
+}
@@ -0,0 +1,473 @@
+use crate::component::ComponentEvent;
+use crate::geom::{Direction, Point, Rect, Size};
+use crate::{GlobalEvent, WindowEvent};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Identifies an overlay that was opened through [`Overlays`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct OverlayId(u64);
+
+/// Where to put an overlay relative to its anchor.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Placement {
+    /// Below the anchor, aligned with its leading edge. This is what drop-down lists use.
+    #[default]
+    Below,
+    Above,
+    /// Right of the anchor, or left of it in right-to-left direction, top-aligned. This is what
+    /// submenus use.
+    Right,
+    /// Left of the anchor, or right of it in right-to-left direction.
+    Left,
+}
+
+impl Placement {
+    pub fn opposite(self) -> Self {
+        match self {
+            Placement::Below => Placement::Above,
+            Placement::Above => Placement::Below,
+            Placement::Right => Placement::Left,
+            Placement::Left => Placement::Right,
+        }
+    }
+
+    /// Computes the rectangle of an overlay of `size` next to `anchor`, which is mirrored
+    /// horizontally in right-to-left `direction`.
+    ///
+    /// If the overlay doesn't fit within `bounds` on the preferred side, but does on the opposite
+    /// side, it is flipped over. It is then moved to lie within `bounds` as far as possible.
+    pub fn place(self, anchor: Rect, size: Size, bounds: Rect, direction: Direction) -> Rect {
+        let fits = |rect: Rect| rect.constrain(bounds) == rect;
+
+        let rect = self.place_unconstrained(anchor, size, direction);
+        let rect = if fits(rect) {
+            rect
+        } else {
+            let flipped = self.opposite().place_unconstrained(anchor, size, direction);
+            if fits(flipped) {
+                flipped
+            } else {
+                rect
+            }
+        };
+
+        rect.constrain(bounds)
+    }
+
+    fn place_unconstrained(self, anchor: Rect, size: Size, direction: Direction) -> Rect {
+        let limit = anchor.limit();
+        let x = match direction {
+            Direction::LeftToRight => anchor.origin.x,
+            Direction::RightToLeft => limit.x - size.width,
+        };
+        let placement = match (self, direction) {
+            (Placement::Right | Placement::Left, Direction::RightToLeft) => self.opposite(),
+            _ => self,
+        };
+        let origin = match placement {
+            Placement::Below => Point::new(x, limit.y),
+            Placement::Above => Point::new(x, anchor.origin.y - size.height),
+            Placement::Right => Point::new(limit.x, anchor.origin.y),
+            Placement::Left => Point::new(anchor.origin.x - size.width, anchor.origin.y),
+        };
+        Rect::new(origin, size)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Overlay {
+    id: OverlayId,
+    anchor: Rect,
+    rect: Rect,
+    modal: bool,
+}
+
+#[derive(Debug, Default)]
+struct OverlayStack {
+    next_id: u64,
+    /// Ordered from bottom to top.
+    overlays: Vec<Overlay>,
+    bounds: Rect,
+}
+
+impl OverlayStack {
+    fn index_of(&self, id: OverlayId) -> Option<usize> {
+        self.overlays.iter().position(|overlay| overlay.id == id)
+    }
+
+    fn hit_test(&self, point: Point) -> Option<usize> {
+        self.overlays
+            .iter()
+            .rposition(|overlay| overlay.rect.contains(point))
+    }
+
+    fn open(&mut self, anchor: Rect, rect: Rect, modal: bool) -> OverlayId {
+        let id = OverlayId(self.next_id);
+        self.next_id += 1;
+        self.overlays.push(Overlay {
+            id,
+            anchor,
+            rect,
+            modal,
+        });
+        id
+    }
+
+    /// The number of overlays up to and including the topmost modal one, which are never
+    /// dismissed implicitly.
+    fn modal_len(&self) -> usize {
+        self.overlays
+            .iter()
+            .rposition(|overlay| overlay.modal)
+            .map_or(0, |index| index + 1)
+    }
+
+    /// Closes every overlay the user clicked outside of.
+    ///
+    /// Clicking an overlay or its anchor keeps that overlay and all overlays below it open.
+    fn light_dismiss(&mut self, point: Point) {
+        let keep = self
+            .overlays
+            .iter()
+            .rposition(|overlay| overlay.rect.contains(point) || overlay.anchor.contains(point))
+            .map_or(0, |index| index + 1);
+        self.overlays.truncate(keep.max(self.modal_len()));
+    }
+}
+
+/// The overlay layer.
+///
+/// Overlays are rectangular areas on top of the regular content, such as drop-down lists, menus
+/// or dialogs. They are stacked in the order they were opened. Components open overlays through
+/// this cheaply cloneable handle and render their content during the overlay render pass.
+///
+/// Pointer events pass through [`Overlays::route()`] before they are dispatched, which records
+/// the topmost overlay under the pointer in every position. Components on the base layer will
+/// not consider such positions a hit, while components that pass events on to the content of an
+/// overlay use [`ComponentEvent::enter_overlay()`] so that the content is hit as usual.
+///
+/// Pressing a mouse button outside of an overlay and its anchor closes it (light dismiss), as
+/// does losing the window focus. Owners notice this through [`Overlays::is_open()`]. Modal
+/// overlays, such as dialogs, are exempt from this and protect the overlays below them as well.
+#[derive(Debug, Clone, Default)]
+pub struct Overlays {
+    stack: Rc<RefCell<OverlayStack>>,
+}
+
+impl Overlays {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens an overlay covering `rect` on top of all others. Clicks on `anchor` (usually the
+    /// component that opened the overlay) don't dismiss it.
+    pub fn open(&self, anchor: Rect, rect: Rect) -> OverlayId {
+        self.stack.borrow_mut().open(anchor, rect, false)
+    }
+
+    /// Opens a modal overlay covering `rect` on top of all others. It is only closed explicitly,
+    /// and usually covers the whole window so that the content below doesn't receive any pointer
+    /// input. The content below must not receive keyboard input either, see
+    /// [`Overlays::has_modal()`].
+    pub fn open_modal(&self, rect: Rect) -> OverlayId {
+        self.stack.borrow_mut().open(Rect::default(), rect, true)
+    }
+
+    /// Opens an overlay of `size` next to `anchor`, within the bounds of the window. See
+    /// [`Placement::place()`].
+    pub fn open_at(
+        &self,
+        anchor: Rect,
+        size: Size,
+        placement: Placement,
+        direction: Direction,
+    ) -> OverlayId {
+        let rect = placement.place(anchor, size, self.bounds(), direction);
+        self.open(anchor, rect)
+    }
+
+    /// Closes the overlay along with all overlays above it, since those usually depend on it
+    /// (such as submenus). Closing an overlay that is not open has no effect.
+    pub fn close(&self, id: OverlayId) {
+        let mut stack = self.stack.borrow_mut();
+        if let Some(index) = stack.index_of(id) {
+            stack.overlays.truncate(index);
+        }
+    }
+
+    pub fn close_all(&self) {
+        self.stack.borrow_mut().overlays.clear();
+    }
+
+    pub fn is_open(&self, id: OverlayId) -> bool {
+        self.stack.borrow().index_of(id).is_some()
+    }
+
+    /// Whether a modal overlay is open.
+    pub fn has_modal(&self) -> bool {
+        self.stack.borrow().modal_len() > 0
+    }
+
+    /// Whether no modal overlay is open above the overlay `id`, or above the base layer for
+    /// `None`. Only such layers should receive keyboard input.
+    pub fn is_interactive(&self, id: Option<OverlayId>) -> bool {
+        let stack = self.stack.borrow();
+        let index = id.and_then(|id| stack.index_of(id));
+        match index {
+            Some(index) => index + 1 >= stack.modal_len(),
+            None => id.is_none() && stack.modal_len() == 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.borrow().overlays.is_empty()
+    }
+
+    /// The overlay on top of all others.
+    pub fn top(&self) -> Option<OverlayId> {
+        self.stack
+            .borrow()
+            .overlays
+            .last()
+            .map(|overlay| overlay.id)
+    }
+
+    pub fn rect(&self, id: OverlayId) -> Option<Rect> {
+        let stack = self.stack.borrow();
+        stack.index_of(id).map(|index| stack.overlays[index].rect)
+    }
+
+    pub fn set_rect(&self, id: OverlayId, rect: Rect) {
+        let mut stack = self.stack.borrow_mut();
+        if let Some(index) = stack.index_of(id) {
+            stack.overlays[index].rect = rect;
+        }
+    }
+
+    /// The topmost overlay containing `point`.
+    pub fn hit_test(&self, point: Point) -> Option<OverlayId> {
+        let stack = self.stack.borrow();
+        stack.hit_test(point).map(|index| stack.overlays[index].id)
+    }
+
+    /// The area overlays are placed within, which is the window rectangle passed to the last
+    /// call of [`Overlays::route()`].
+    pub fn bounds(&self) -> Rect {
+        self.stack.borrow().bounds
+    }
+
+    /// Turns `event` into an event for the component tree covering `window_rect`, applying light
+    /// dismiss and recording the overlay under the pointer.
+    pub fn route(&self, event: GlobalEvent, window_rect: Rect) -> ComponentEvent {
+        let mut stack = self.stack.borrow_mut();
+        stack.bounds = window_rect;
+
+        match &event {
+            GlobalEvent::MouseButtonDown(_, pos, ..) => stack.light_dismiss(*pos),
+            GlobalEvent::Window(WindowEvent::FocusLost, _) => {
+                let keep = stack.modal_len();
+                stack.overlays.truncate(keep);
+            }
+            _ => {}
+        }
+
+        event.into_component_event(window_rect).map_position(|pos| {
+            let hit_layer = stack
+                .hit_test(pos.absolute_position())
+                .map(|index| stack.overlays[index].id);
+            pos.with_hit_layer(hit_layer)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::keyboard::Modifiers;
+    use crate::mouse::MouseButton;
+    use crate::time::Timestamp;
+
+    fn press(x: i32, y: i32) -> GlobalEvent {
+        GlobalEvent::MouseButtonDown(
+            MouseButton::Left,
+            Point::new(x, y),
+            Modifiers::NONE,
+            Timestamp::default(),
+        )
+    }
+
+    fn motion(x: i32, y: i32) -> GlobalEvent {
+        GlobalEvent::MouseMotion(Point::new(x, y), Modifiers::NONE, Timestamp::default())
+    }
+
+    #[test]
+    fn test_place() {
+        let bounds = Rect::from_xywh(0, 0, 200, 100);
+        let anchor = Rect::from_xywh(20, 40, 50, 10);
+        let size = Size::new(60, 30);
+        assert_eq!(
+            Rect::from_xywh(20, 50, 60, 30),
+            Placement::Below.place(anchor, size, bounds, Direction::LeftToRight)
+        );
+        assert_eq!(
+            Rect::from_xywh(20, 10, 60, 30),
+            Placement::Above.place(anchor, size, bounds, Direction::LeftToRight)
+        );
+        assert_eq!(
+            Rect::from_xywh(70, 40, 60, 30),
+            Placement::Right.place(anchor, size, bounds, Direction::LeftToRight)
+        );
+        // Doesn't fit on the left, so it's flipped over.
+        assert_eq!(
+            Rect::from_xywh(70, 40, 60, 30),
+            Placement::Left.place(anchor, size, bounds, Direction::LeftToRight)
+        );
+
+        // Doesn't fit on either side, so it's kept within the bounds.
+        let size = Size::new(60, 60);
+        assert_eq!(
+            Rect::from_xywh(20, 40, 60, 60),
+            Placement::Below.place(anchor, size, bounds, Direction::LeftToRight)
+        );
+    }
+
+    #[test]
+    fn test_place_right_to_left() {
+        let bounds = Rect::from_xywh(0, 0, 200, 100);
+        let anchor = Rect::from_xywh(100, 40, 50, 10);
+        let size = Size::new(60, 30);
+        let direction = Direction::RightToLeft;
+        assert_eq!(
+            Rect::from_xywh(90, 50, 60, 30),
+            Placement::Below.place(anchor, size, bounds, direction)
+        );
+        assert_eq!(
+            Rect::from_xywh(90, 10, 60, 30),
+            Placement::Above.place(anchor, size, bounds, direction)
+        );
+        assert_eq!(
+            Rect::from_xywh(40, 40, 60, 30),
+            Placement::Right.place(anchor, size, bounds, direction)
+        );
+        // Doesn't fit on the right, so it's flipped over.
+        assert_eq!(
+            Rect::from_xywh(40, 40, 60, 30),
+            Placement::Left.place(anchor, size, bounds, direction)
+        );
+    }
+
+    #[test]
+    fn test_routing() {
+        let overlays = Overlays::new();
+        let window_rect = Rect::from_xywh(0, 0, 200, 100);
+        let id = overlays.open(
+            Rect::from_xywh(10, 10, 20, 10),
+            Rect::from_xywh(10, 20, 50, 50),
+        );
+
+        let event = overlays.route(motion(15, 25), window_rect);
+        let pos = event.position().unwrap();
+        assert!(pos.is_occluded());
+        assert!(!pos.is_hit());
+
+        let event = event.enter_overlay(id, overlays.rect(id).unwrap());
+        let pos = event.position().unwrap();
+        assert!(!pos.is_occluded());
+        assert!(pos.is_hit());
+        assert_eq!(Point::new(5, 5), pos.relative_position());
+
+        let event = overlays.route(motion(100, 25), window_rect);
+        assert!(event.position().unwrap().is_hit());
+        let event = event.enter_overlay(id, overlays.rect(id).unwrap());
+        assert!(!event.position().unwrap().is_hit());
+    }
+
+    #[test]
+    fn test_light_dismiss() {
+        let overlays = Overlays::new();
+        let window_rect = Rect::from_xywh(0, 0, 200, 100);
+        let menu = overlays.open(
+            Rect::from_xywh(0, 0, 20, 10),
+            Rect::from_xywh(0, 10, 50, 50),
+        );
+        let submenu = overlays.open(
+            Rect::from_xywh(0, 20, 50, 10),
+            Rect::from_xywh(50, 20, 50, 50),
+        );
+        assert_eq!(Some(submenu), overlays.top());
+
+        // Inside the submenu.
+        overlays.route(press(60, 30), window_rect);
+        assert!(overlays.is_open(menu));
+        assert!(overlays.is_open(submenu));
+
+        // Inside the menu, but outside of the submenu.
+        overlays.route(press(10, 50), window_rect);
+        assert!(overlays.is_open(menu));
+        assert!(!overlays.is_open(submenu));
+
+        // On the anchor of the menu.
+        overlays.route(press(10, 5), window_rect);
+        assert!(overlays.is_open(menu));
+
+        let event = overlays.route(press(150, 50), window_rect);
+        assert!(overlays.is_empty());
+        assert!(event.position().unwrap().is_hit());
+    }
+
+    #[test]
+    fn test_modal() {
+        let overlays = Overlays::new();
+        let window_rect = Rect::from_xywh(0, 0, 200, 100);
+        let menu = overlays.open(
+            Rect::from_xywh(0, 0, 20, 10),
+            Rect::from_xywh(0, 10, 50, 50),
+        );
+        assert!(overlays.is_interactive(None));
+        assert!(!overlays.has_modal());
+
+        let dialog = overlays.open_modal(window_rect);
+        let popup = overlays.open(
+            Rect::from_xywh(60, 40, 50, 10),
+            Rect::from_xywh(60, 50, 50, 30),
+        );
+        assert!(overlays.has_modal());
+        assert!(!overlays.is_interactive(None));
+        assert!(!overlays.is_interactive(Some(menu)));
+        assert!(overlays.is_interactive(Some(dialog)));
+        assert!(overlays.is_interactive(Some(popup)));
+
+        // Only the popup above the dialog is dismissed.
+        overlays.route(press(150, 20), window_rect);
+        assert!(overlays.is_open(menu));
+        assert!(overlays.is_open(dialog));
+        assert!(!overlays.is_open(popup));
+
+        overlays.route(
+            GlobalEvent::Window(WindowEvent::FocusLost, Timestamp::default()),
+            window_rect,
+        );
+        assert!(overlays.is_open(dialog));
+
+        overlays.close(dialog);
+        assert!(overlays.is_open(menu));
+        assert!(overlays.is_interactive(Some(menu)));
+        assert!(!overlays.has_modal());
+    }
+
+    #[test]
+    fn test_close() {
+        let overlays = Overlays::new();
+        let a = overlays.open(Rect::default(), Rect::from_xywh(0, 0, 10, 10));
+        let b = overlays.open(Rect::default(), Rect::from_xywh(5, 5, 10, 10));
+        let c = overlays.open(Rect::default(), Rect::from_xywh(20, 20, 10, 10));
+        assert_eq!(Some(b), overlays.hit_test(Point::new(7, 7)));
+
+        overlays.close(b);
+        assert!(overlays.is_open(a));
+        assert!(!overlays.is_open(b));
+        assert!(!overlays.is_open(c));
+        assert_eq!(Some(a), overlays.hit_test(Point::new(7, 7)));
+    }
+}
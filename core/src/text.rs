@@ -0,0 +1,4 @@
+pub mod bidi;
+mod piece_table;
+
+pub use piece_table::PieceTable;
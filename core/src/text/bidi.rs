@@ -0,0 +1,541 @@
+use crate::geom::Direction;
+use crate::VuiResult;
+use std::ops::Range;
+
+/// The bidi classes of UAX #9, table 4.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Class {
+    L,
+    R,
+    Al,
+    En,
+    Es,
+    Et,
+    An,
+    Cs,
+    Nsm,
+    Bn,
+    B,
+    S,
+    Ws,
+    On,
+}
+
+use Class::*;
+
+fn class_of(chr: char) -> Class {
+    match u32::from(chr) {
+        0x0a | 0x0d | 0x1c..=0x1e | 0x85 | 0x2029 => B,
+        0x09 | 0x0b | 0x1f => S,
+        0x0c | 0x20 | 0x1680 | 0x2000..=0x200a | 0x2028 | 0x205f | 0x3000 => Ws,
+        0x00..=0x08 | 0x0e..=0x1b | 0x7f..=0x84 | 0x86..=0x9f | 0xad => Bn,
+        0x200b..=0x200d | 0x202a..=0x202e | 0x2060..=0x2069 | 0xfeff => Bn,
+        0x30..=0x39 | 0xb2 | 0xb3 | 0xb9 | 0x06f0..=0x06f9 | 0x2070..=0x2079 | 0xff10..=0xff19 => {
+            En
+        }
+        0x2b | 0x2d | 0x207a | 0x207b | 0xfb29 | 0xfe62 | 0xfe63 | 0xff0b | 0xff0d => Es,
+        0x23..=0x25 | 0xa2..=0xa5 | 0xb0 | 0xb1 | 0x066a | 0x2030..=0x2034 | 0x20a0..=0x20cf => Et,
+        0x0600..=0x0605 | 0x0660..=0x0669 | 0x066b | 0x066c | 0x06dd | 0x08e2 => An,
+        0x2c | 0x2e | 0x2f | 0x3a | 0xa0 | 0x060c | 0x202f | 0x2044 | 0xff0c | 0xff0e | 0xff0f => {
+            Cs
+        }
+        0x0300..=0x036f
+        | 0x0591..=0x05bd
+        | 0x05bf
+        | 0x05c1..=0x05c2
+        | 0x05c4..=0x05c5
+        | 0x05c7
+        | 0x0610..=0x061a
+        | 0x064b..=0x065f
+        | 0x0670
+        | 0x06d6..=0x06dc
+        | 0x06df..=0x06e4
+        | 0x06e7..=0x06e8
+        | 0x06ea..=0x06ed
+        | 0x20d0..=0x20ff
+        | 0xfe00..=0xfe0f
+        | 0xfe20..=0xfe2f => Nsm,
+        0x0590..=0x05ff | 0x07c0..=0x085f | 0x200f | 0xfb1d..=0xfb4f | 0x10800..=0x10fff => R,
+        0x0600..=0x07bf | 0x0860..=0x08ff | 0xfb50..=0xfdff | 0xfe70..=0xfefe => Al,
+        0x1e800..=0x1efff => R,
+        0x21..=0x22
+        | 0x26..=0x2a
+        | 0x3b..=0x40
+        | 0x5b..=0x60
+        | 0x7b..=0x7e
+        | 0xa1
+        | 0xa6..=0xa9
+        | 0xab..=0xac
+        | 0xae..=0xaf
+        | 0xb4
+        | 0xb6..=0xb8
+        | 0xbb..=0xbf
+        | 0xd7
+        | 0xf7
+        | 0x2010..=0x2027
+        | 0x2035..=0x2043
+        | 0x2045..=0x205e
+        | 0x2190..=0x245f
+        | 0x2500..=0x2bff
+        | 0x2e00..=0x2e7f
+        | 0x3001..=0x3004
+        | 0x3008..=0x3020
+        | 0xfe10..=0xfe19
+        | 0xfe30..=0xfe4f
+        | 0xff01..=0xff02
+        | 0xff06..=0xff0a
+        | 0xff1b..=0xff20
+        | 0xff3b..=0xff40
+        | 0xff5b..=0xff65
+        | 0x1f000..=0x1faff => On,
+        _ => L,
+    }
+}
+
+/// The direction of the first character of `text` that has a strong direction, if any.
+pub fn base_direction(text: &str) -> Option<Direction> {
+    first_strong(text.chars().map(class_of))
+}
+
+fn first_strong(mut classes: impl Iterator<Item = Class>) -> Option<Direction> {
+    classes.find_map(|class| match class {
+        L => Some(Direction::LeftToRight),
+        R | Al => Some(Direction::RightToLeft),
+        _ => None,
+    })
+}
+
+/// Whether `text` contains characters that are written right to left.
+pub fn has_rtl(text: &str) -> bool {
+    text.chars().any(|chr| matches!(class_of(chr), R | Al | An))
+}
+
+/// The embedding levels of the characters of `text` after a simplified version of UAX #9, without
+/// explicit embeddings. Odd levels run right to left. Without a `direction`, each paragraph
+/// takes the one of its first strong character.
+pub fn levels(text: &str, direction: Option<Direction>) -> Vec<u8> {
+    let classes: Vec<_> = text.chars().map(class_of).collect();
+    let mut levels = Vec::with_capacity(classes.len());
+    for paragraph in classes.split_inclusive(|&class| class == B) {
+        let base = direction
+            .or_else(|| first_strong(paragraph.iter().copied()))
+            .unwrap_or_default();
+        levels.extend(resolve_paragraph(paragraph, base));
+    }
+    levels
+}
+
+fn resolve_paragraph(classes: &[Class], base: Direction) -> Vec<u8> {
+    let base_level = u8::from(base.is_rtl());
+    // The type of the start and the end of the paragraph.
+    let edge = if base.is_rtl() { R } else { L };
+    let mut types = classes.to_vec();
+
+    // W1: Marks take the type of the character before them. Boundary neutrals are treated the
+    // same rather than being removed.
+    let mut prev = edge;
+    for class in types.iter_mut() {
+        if matches!(class, Nsm | Bn) {
+            *class = prev;
+        }
+        prev = *class;
+    }
+
+    // W2, W3: Numbers after Arabic letters are Arabic numbers, and Arabic letters are R.
+    let mut last_strong = edge;
+    for class in types.iter_mut() {
+        match *class {
+            L | R => last_strong = *class,
+            Al => {
+                last_strong = Al;
+                *class = R;
+            }
+            En if last_strong == Al => *class = An,
+            _ => {}
+        }
+    }
+
+    // W4: Single separators between numbers of the same kind join them.
+    for i in 1..types.len().saturating_sub(1) {
+        let (before, after) = (types[i - 1], types[i + 1]);
+        types[i] = match types[i] {
+            Es if before == En && after == En => En,
+            Cs if before == after && matches!(before, En | An) => before,
+            class => class,
+        };
+    }
+
+    // W5: Terminators next to European numbers are part of them.
+    let mut i = 0;
+    while i < types.len() {
+        let end = i + types[i..].iter().take_while(|&&class| class == Et).count();
+        if end > i {
+            let adjacent = (i > 0 && types[i - 1] == En) || types.get(end) == Some(&En);
+            if adjacent {
+                types[i..end].fill(En);
+            }
+        }
+        i = end.max(i + 1);
+    }
+
+    // W6, W7: Remaining separators are neutral, and European numbers in left-to-right context
+    // are L.
+    let mut last_strong = edge;
+    for class in types.iter_mut() {
+        match *class {
+            Es | Et | Cs => *class = On,
+            L | R => last_strong = *class,
+            En if last_strong == L => *class = L,
+            _ => {}
+        }
+    }
+
+    // N1, N2: Neutrals between characters of the same direction take that direction, and the
+    // base direction otherwise. Numbers count as R.
+    let strong = |class: Class| match class {
+        L => L,
+        _ => R,
+    };
+    let mut i = 0;
+    while i < types.len() {
+        let is_neutral = |class: &Class| matches!(class, B | S | Ws | On);
+        let end = i + types[i..]
+            .iter()
+            .take_while(|class| is_neutral(class))
+            .count();
+        if end > i {
+            let before = if i == 0 { edge } else { strong(types[i - 1]) };
+            let after = types.get(end).map_or(edge, |&class| strong(class));
+            let resolved = if before == after { before } else { edge };
+            types[i..end].fill(resolved);
+        }
+        i = end.max(i + 1);
+    }
+
+    // I1, I2
+    let mut levels: Vec<u8> = types
+        .iter()
+        .map(|class| match (base_level, class) {
+            (0, R) => 1,
+            (0, An | En) => 2,
+            (0, _) => 0,
+            (_, L | En | An) => 2,
+            _ => 1,
+        })
+        .collect();
+
+    // L1: Separators and the whitespace before them or at the end of the paragraph are at the
+    // base level.
+    let mut trailing = true;
+    for (level, class) in levels.iter_mut().zip(classes).rev() {
+        match class {
+            B | S => {
+                *level = base_level;
+                trailing = true;
+            }
+            Ws | Bn if trailing => *level = base_level,
+            _ => trailing = false,
+        }
+    }
+    levels
+}
+
+/// The logical indices of the characters of a line with the embedding `levels`, as displayed.
+pub fn visual_order(levels: &[u8]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..levels.len()).collect();
+    let highest = levels.iter().copied().max().unwrap_or(0);
+    let Some(lowest_odd) = levels.iter().copied().filter(|level| level % 2 == 1).min() else {
+        return order;
+    };
+
+    // L2: From the highest level down to the lowest odd level, every sequence of characters at
+    // that level or higher is reversed.
+    for level in (lowest_odd..=highest).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            let len = order[i..]
+                .iter()
+                .take_while(|&&index| levels[index] >= level)
+                .count();
+            order[i..i + len].reverse();
+            i += len.max(1);
+        }
+    }
+    order
+}
+
+/// Rearranges `text` into the order in which its characters are displayed, line by line, with
+/// brackets mirrored in right-to-left runs.
+pub fn reorder(text: &str, direction: Option<Direction>) -> String {
+    if direction != Some(Direction::RightToLeft) && !has_rtl(text) {
+        return text.to_string();
+    }
+
+    let lines: Vec<_> = text
+        .split('\n')
+        .map(|line| reorder_line(line, direction))
+        .collect();
+    lines.join("\n")
+}
+
+fn reorder_line(line: &str, direction: Option<Direction>) -> String {
+    let visual = VisualLine::new(line, direction);
+    visual.prefix(line, visual.len())
+}
+
+/// A line of text in the order it is displayed, as clusters of a character and the marks after
+/// it, which are never separated. Carets are byte offsets into the line; the caret index is the
+/// number of clusters displayed left of a caret.
+#[derive(Debug, Clone)]
+pub struct VisualLine {
+    /// The byte offsets of the clusters, followed by the length of the line.
+    offsets: Vec<usize>,
+    levels: Vec<u8>,
+    /// The logical index of the cluster at each visual position.
+    order: Vec<usize>,
+    /// The visual position of each cluster.
+    positions: Vec<usize>,
+}
+
+impl VisualLine {
+    /// Orders `line` like [`reorder()`] does.
+    pub fn new(line: &str, direction: Option<Direction>) -> Self {
+        let mut offsets = Vec::new();
+        let mut cluster_levels = Vec::new();
+        for ((offset, chr), level) in line.char_indices().zip(levels(line, direction)) {
+            if offsets.is_empty() || class_of(chr) != Nsm {
+                offsets.push(offset);
+                cluster_levels.push(level);
+            }
+        }
+        offsets.push(line.len());
+        let order = visual_order(&cluster_levels);
+        let mut positions = vec![0; order.len()];
+        for (position, &index) in order.iter().enumerate() {
+            positions[index] = position;
+        }
+        Self {
+            offsets,
+            levels: cluster_levels,
+            order,
+            positions,
+        }
+    }
+
+    /// The number of clusters.
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    fn is_rtl(&self, cluster: usize) -> bool {
+        self.levels[cluster] % 2 == 1
+    }
+
+    /// The byte range of the cluster displayed at `index`.
+    pub fn cluster_range(&self, index: usize) -> Range<usize> {
+        let cluster = self.order[index];
+        self.offsets[cluster]..self.offsets[cluster + 1]
+    }
+
+    /// The first `index` clusters of `line` as displayed, with brackets mirrored in right-to-left
+    /// runs.
+    pub fn prefix(&self, line: &str, index: usize) -> String {
+        let mut prefix = String::new();
+        for position in 0..index {
+            let rtl = self.is_rtl(self.order[position]);
+            let cluster = line[self.cluster_range(position)].chars();
+            prefix.extend(cluster.map(|chr| if rtl { mirror(chr) } else { chr }));
+        }
+        prefix
+    }
+
+    /// The left edge of every cluster of `line` as displayed, followed by the right edge of the
+    /// last, measuring clusters with `width_of`.
+    pub fn edges<F>(&self, line: &str, width_of: F) -> VuiResult<Vec<i32>>
+    where
+        F: Fn(&str) -> VuiResult<i32>,
+    {
+        let mut edges = Vec::with_capacity(self.len() + 1);
+        let mut edge = 0;
+        edges.push(edge);
+        for index in 0..self.len() {
+            edge += width_of(&line[self.cluster_range(index)])?;
+            edges.push(edge);
+        }
+        Ok(edges)
+    }
+
+    /// The caret index closest to `x`, given the `edges` of the clusters.
+    pub fn index_at_x(edges: &[i32], x: i32) -> usize {
+        match edges.partition_point(|&edge| edge < x) {
+            0 => 0,
+            index if index == edges.len() => index - 1,
+            index if x - edges[index - 1] <= edges[index] - x => index - 1,
+            index => index,
+        }
+    }
+
+    /// The caret index of `caret`. The caret is displayed at the leading edge of the cluster
+    /// after it, or at the trailing edge of the last cluster.
+    pub fn caret_index(&self, caret: usize) -> usize {
+        let cluster = self.offsets.partition_point(|&offset| offset < caret);
+        if cluster < self.len() {
+            self.positions[cluster] + usize::from(self.is_rtl(cluster))
+        } else if let Some(last) = cluster.checked_sub(1) {
+            self.positions[last] + usize::from(!self.is_rtl(last))
+        } else {
+            0
+        }
+    }
+
+    /// The caret that is displayed at the caret index `index`.
+    pub fn caret_at(&self, index: usize) -> usize {
+        if let Some(&cluster) = self.order.get(index) {
+            // The caret is at the left edge of the cluster.
+            self.offsets[cluster + usize::from(self.is_rtl(cluster))]
+        } else if let Some(&cluster) = index.checked_sub(1).and_then(|i| self.order.get(i)) {
+            // The caret is at the right edge of the last cluster.
+            self.offsets[cluster + usize::from(!self.is_rtl(cluster))]
+        } else {
+            0
+        }
+    }
+
+    /// The caret `delta` positions right of `caret` as displayed, which in right-to-left text is
+    /// towards the start. The caret stays in place at either end of the line.
+    pub fn move_visually(&self, caret: usize, delta: isize) -> usize {
+        let mut index = self.caret_index(caret) as isize;
+        loop {
+            index += delta;
+            if index < 0 || index as usize > self.len() {
+                return caret;
+            }
+            // Between runs of different directions, neighbouring positions may map to the same
+            // caret, which would leave it in place.
+            let moved = self.caret_at(index as usize);
+            if moved != caret {
+                return moved;
+            }
+        }
+    }
+}
+
+/// The mirrored glyph of `chr` for right-to-left runs, if it has one.
+fn mirror(chr: char) -> char {
+    match chr {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '\u{ab}' => '\u{bb}',
+        '\u{bb}' => '\u{ab}',
+        '\u{2039}' => '\u{203a}',
+        '\u{203a}' => '\u{2039}',
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(vec![0, 0, 0, 1, 1], levels("ab אב", None));
+        assert_eq!(vec![1, 1, 1, 2, 2], levels("אב ab", None));
+        // Numbers in right-to-left text keep their order.
+        assert_eq!(vec![1, 1, 2, 2, 2], levels("א 1.5", None));
+        // Trailing whitespace is at the base level.
+        assert_eq!(vec![0, 1, 0], levels("aא ", None));
+        assert_eq!(vec![2, 1, 1], levels("a .", Some(Direction::RightToLeft)));
+    }
+
+    #[test]
+    fn test_visual_order() {
+        assert_eq!(vec![0, 1, 4, 3, 2], visual_order(&[0, 0, 1, 1, 1]));
+        assert_eq!(vec![3, 4, 2, 1, 0], visual_order(&[1, 1, 1, 2, 2]));
+        assert_eq!(vec![0, 1, 2], visual_order(&[0, 0, 0]));
+    }
+
+    #[test]
+    fn test_reorder() {
+        assert_eq!("plain text", reorder("plain text", None));
+        assert_eq!("say םולש!", reorder("say שלום!", None));
+        assert_eq!("!say םולש", reorder("שלום say!", None));
+        assert_eq!("ב 2024 א", reorder("א 2024 ב", None));
+        assert_eq!("(ב) א", reorder("א (ב)", None));
+        // The point stays after the mark.
+        assert_eq!("בּא", reorder("אבּ", None));
+        assert_eq!("ba\nדג", reorder("ba\nגד", None));
+    }
+
+    #[test]
+    fn test_visual_line_clusters() {
+        let line = "אבּ";
+        let visual = VisualLine::new(line, None);
+        assert_eq!(2, visual.len());
+        assert_eq!(2..6, visual.cluster_range(0));
+        assert_eq!(reorder(line, None), visual.prefix(line, 2));
+        let edges = visual
+            .edges(line, |text| Ok(text.chars().count() as i32 * 10))
+            .unwrap();
+        assert_eq!(vec![0, 20, 30], edges);
+    }
+
+    #[test]
+    fn test_caret_index() {
+        // Displayed as "ab בא".
+        let visual = VisualLine::new("ab אב", None);
+        assert_eq!(0, visual.caret_index(0));
+        assert_eq!(2, visual.caret_index(2));
+        assert_eq!(5, visual.caret_index(3));
+        assert_eq!(4, visual.caret_index(5));
+        assert_eq!(3, visual.caret_index(7));
+        assert_eq!(0, VisualLine::new("", None).caret_index(0));
+    }
+
+    #[test]
+    fn test_caret_at() {
+        let visual = VisualLine::new("ab אב", None);
+        assert_eq!(
+            vec![0, 1, 2, 7, 5, 3],
+            (0..=5).map(|i| visual.caret_at(i)).collect::<Vec<_>>()
+        );
+        for caret in [0, 1, 2, 3, 5, 7] {
+            assert_eq!(caret, visual.caret_at(visual.caret_index(caret)));
+        }
+    }
+
+    #[test]
+    fn test_move_visually() {
+        let visual = VisualLine::new("ab אב", None);
+        assert_eq!(7, visual.move_visually(2, 1));
+        assert_eq!(5, visual.move_visually(7, 1));
+        assert_eq!(3, visual.move_visually(5, 1));
+        assert_eq!(3, visual.move_visually(3, 1));
+        assert_eq!(0, visual.move_visually(0, -1));
+
+        // The caret never ends up between a letter and its mark.
+        let visual = VisualLine::new("אבּ", None);
+        assert_eq!(2, visual.move_visually(0, -1));
+        assert_eq!(6, visual.move_visually(2, -1));
+        assert_eq!(2, visual.move_visually(6, 1));
+    }
+
+    #[test]
+    fn test_index_at_x() {
+        let edges = [0, 10, 20];
+        assert_eq!(0, VisualLine::index_at_x(&edges, -3));
+        assert_eq!(0, VisualLine::index_at_x(&edges, 5));
+        assert_eq!(1, VisualLine::index_at_x(&edges, 6));
+        assert_eq!(2, VisualLine::index_at_x(&edges, 100));
+    }
+}
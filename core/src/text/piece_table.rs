@@ -0,0 +1,349 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Source {
+    Original,
+    Added,
+}
+
+/// A span of one of the buffers of a [`PieceTable`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Piece {
+    source: Source,
+    start: usize,
+    len: usize,
+}
+
+/// A text buffer for editing large texts.
+///
+/// The text is never moved around when editing. Instead it consists of pieces, which refer either
+/// to the original text or to an append-only buffer holding everything that was inserted. Typing
+/// extends the last piece, so the number of pieces grows with the number of places that were
+/// edited rather than with the number of edits. The starts of the lines are indexed, so lines can
+/// be looked up without scanning the text.
+///
+/// Offsets are byte offsets into the text, and must lie on character boundaries.
+#[derive(Debug, Clone)]
+pub struct PieceTable {
+    original: String,
+    added: String,
+    pieces: Vec<Piece>,
+    len: usize,
+    /// The offsets at which lines start. The first line always starts at 0.
+    line_starts: Vec<usize>,
+}
+
+impl Default for PieceTable {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl PieceTable {
+    pub fn new(text: &str) -> Self {
+        let pieces = if text.is_empty() {
+            vec![]
+        } else {
+            vec![Piece {
+                source: Source::Original,
+                start: 0,
+                len: text.len(),
+            }]
+        };
+        Self {
+            original: text.to_string(),
+            added: String::new(),
+            pieces,
+            len: text.len(),
+            line_starts: line_starts(text, 0),
+        }
+    }
+
+    /// The length of the text in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of lines. A text ending with a newline ends with an empty line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The range of `line`, without its line break.
+    pub fn line_range(&self, line: usize) -> Range<usize> {
+        let start = self.line_starts[line];
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.len, |next| next - 1);
+        start..end
+    }
+
+    /// The text of `line`, without its line break.
+    pub fn line(&self, line: usize) -> String {
+        self.slice(self.line_range(line))
+    }
+
+    /// The line containing `offset`.
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.line_starts
+            .partition_point(|&start| start <= offset)
+            .saturating_sub(1)
+    }
+
+    pub fn slice(&self, range: Range<usize>) -> String {
+        let mut out = String::with_capacity(range.len());
+        let mut piece_start = 0;
+        for piece in &self.pieces {
+            let piece_end = piece_start + piece.len;
+            if piece_end > range.start && piece_start < range.end {
+                let from = range.start.max(piece_start) - piece_start;
+                let to = range.end.min(piece_end) - piece_start;
+                out.push_str(&self.text_of(piece)[from..to]);
+            }
+            if piece_end >= range.end {
+                break;
+            }
+            piece_start = piece_end;
+        }
+        out
+    }
+
+    /// The offset of the character before `offset`, or 0 at the start of the text.
+    pub fn prev_boundary(&self, offset: usize) -> usize {
+        // A character takes at most 4 bytes.
+        let start = offset.saturating_sub(4);
+        let bytes = self.slice_bytes(start..offset);
+        (0..bytes.len())
+            .rev()
+            .find(|&i| !is_continuation_byte(bytes[i]))
+            .map_or(start, |i| start + i)
+    }
+
+    /// The offset of the character after the one at `offset`, or the length at the end of the
+    /// text.
+    pub fn next_boundary(&self, offset: usize) -> usize {
+        let end = (offset + 4).min(self.len);
+        let bytes = self.slice_bytes(offset..end);
+        (1..bytes.len())
+            .find(|&i| !is_continuation_byte(bytes[i]))
+            .map_or(end, |i| offset + i)
+    }
+
+    pub fn insert(&mut self, offset: usize, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        let start = self.added.len();
+        self.added.push_str(text);
+        let new_piece = Piece {
+            source: Source::Added,
+            start,
+            len: text.len(),
+        };
+
+        let (index, within) = self.locate(offset);
+        if within == 0 && index > 0 {
+            // Typing appends to the piece that was inserted last.
+            let previous = &mut self.pieces[index - 1];
+            if previous.source == Source::Added && previous.start + previous.len == start {
+                previous.len += text.len();
+            } else {
+                self.pieces.insert(index, new_piece);
+            }
+        } else if within == 0 {
+            self.pieces.insert(index, new_piece);
+        } else {
+            let piece = self.pieces[index];
+            let (head, tail) = split_piece(piece, within);
+            self.pieces.splice(index..=index, [head, new_piece, tail]);
+        }
+        self.len += text.len();
+
+        let line = self.line_of(offset);
+        for start in &mut self.line_starts[line + 1..] {
+            *start += text.len();
+        }
+        let new_starts = line_starts(text, offset).into_iter().skip(1);
+        self.line_starts.splice(line + 1..line + 1, new_starts);
+    }
+
+    pub fn delete(&mut self, range: Range<usize>) {
+        let range = range.start.min(self.len)..range.end.min(self.len);
+        if range.is_empty() {
+            return;
+        }
+
+        let (first, within) = self.locate(range.start);
+        if within > 0 {
+            let (head, tail) = split_piece(self.pieces[first], within);
+            self.pieces.splice(first..=first, [head, tail]);
+        }
+        let first = if within > 0 { first + 1 } else { first };
+        let (last, within) = self.locate(range.end);
+        if within > 0 {
+            let (head, tail) = split_piece(self.pieces[last], within);
+            self.pieces.splice(last..=last, [head, tail]);
+        }
+        let last = if within > 0 { last + 1 } else { last };
+        self.pieces.drain(first..last);
+        self.len -= range.len();
+
+        let first_line = self.line_of(range.start);
+        let removed = self.line_starts[first_line + 1..]
+            .iter()
+            .take_while(|&&start| start <= range.end)
+            .count();
+        self.line_starts
+            .drain(first_line + 1..first_line + 1 + removed);
+        for start in &mut self.line_starts[first_line + 1..] {
+            *start -= range.len();
+        }
+    }
+
+    /// Replaces the whole text.
+    pub fn set_text(&mut self, text: &str) {
+        *self = Self::new(text);
+    }
+
+    fn text_of(&self, piece: &Piece) -> &str {
+        let buffer = match piece.source {
+            Source::Original => &self.original,
+            Source::Added => &self.added,
+        };
+        &buffer[piece.start..piece.start + piece.len]
+    }
+
+    fn slice_bytes(&self, range: Range<usize>) -> Vec<u8> {
+        let mut out = Vec::with_capacity(range.len());
+        let mut piece_start = 0;
+        for piece in &self.pieces {
+            let piece_end = piece_start + piece.len;
+            if piece_end > range.start && piece_start < range.end {
+                let from = range.start.max(piece_start) - piece_start;
+                let to = range.end.min(piece_end) - piece_start;
+                out.extend_from_slice(&self.text_of(piece).as_bytes()[from..to]);
+            }
+            piece_start = piece_end;
+        }
+        out
+    }
+
+    /// The index of the piece containing `offset` and the offset within that piece. At the end of
+    /// the text, this is one past the last piece.
+    fn locate(&self, offset: usize) -> (usize, usize) {
+        let mut piece_start = 0;
+        for (index, piece) in self.pieces.iter().enumerate() {
+            if offset < piece_start + piece.len {
+                return (index, offset - piece_start);
+            }
+            piece_start += piece.len;
+        }
+        (self.pieces.len(), 0)
+    }
+}
+
+impl Display for PieceTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.pieces
+            .iter()
+            .try_for_each(|piece| f.write_str(self.text_of(piece)))
+    }
+}
+
+fn split_piece(piece: Piece, at: usize) -> (Piece, Piece) {
+    let head = Piece { len: at, ..piece };
+    let tail = Piece {
+        start: piece.start + at,
+        len: piece.len - at,
+        ..piece
+    };
+    (head, tail)
+}
+
+fn is_continuation_byte(byte: u8) -> bool {
+    byte & 0xc0 == 0x80
+}
+
+/// The starts of the lines of `text`, which itself starts at `offset`.
+fn line_starts(text: &str, offset: usize) -> Vec<usize> {
+    std::iter::once(offset)
+        .chain(text.match_indices('\n').map(|(i, _)| offset + i + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_edit() {
+        let mut text = PieceTable::new("Hello world");
+        text.insert(5, ",");
+        text.insert(12, "!");
+        text.insert(0, ">> ");
+        assert_eq!(">> Hello, world!", text.to_string());
+        assert_eq!(text.to_string().len(), text.len());
+
+        text.delete(3..10);
+        assert_eq!(">> world!", text.to_string());
+        text.delete(0..100);
+        assert!(text.is_empty());
+        assert_eq!("", text.to_string());
+    }
+
+    #[test]
+    fn test_typing_extends_piece() {
+        let mut text = PieceTable::new("ac");
+        for (i, chr) in ["b", "b", "b"].iter().enumerate() {
+            text.insert(1 + i, chr);
+        }
+        assert_eq!("abbbc", text.to_string());
+        assert_eq!(3, text.pieces.len());
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(1, PieceTable::default().line_count());
+
+        let mut text = PieceTable::new("one\ntwo\n");
+        assert_eq!(3, text.line_count());
+        assert_eq!("two", text.line(1));
+        assert_eq!("", text.line(2));
+        assert_eq!(1, text.line_of(4));
+        assert_eq!(1, text.line_of(7));
+        assert_eq!(2, text.line_of(8));
+
+        text.insert(2, "\nx\n");
+        assert_eq!("on\nx\ne\ntwo\n", text.to_string());
+        assert_eq!(5, text.line_count());
+        assert_eq!(["on", "x", "e", "two", ""], lines(&text).as_slice());
+
+        text.delete(1..6);
+        assert_eq!("o\ntwo\n", text.to_string());
+        assert_eq!(["o", "two", ""], lines(&text).as_slice());
+        assert_eq!(0..1, text.line_range(0));
+    }
+
+    #[test]
+    fn test_boundaries() {
+        let mut text = PieceTable::new("aé");
+        text.insert(3, "€b");
+        assert_eq!(1, text.prev_boundary(3));
+        assert_eq!(3, text.prev_boundary(6));
+        assert_eq!(0, text.prev_boundary(0));
+        assert_eq!(3, text.next_boundary(1));
+        assert_eq!(6, text.next_boundary(3));
+        assert_eq!(7, text.next_boundary(7));
+    }
+
+    fn lines(text: &PieceTable) -> Vec<String> {
+        (0..text.line_count()).map(|line| text.line(line)).collect()
+    }
+}
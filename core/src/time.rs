@@ -0,0 +1,58 @@
+use std::fmt::{Debug, Formatter};
+use std::ops::{Add, Sub};
+use std::time::Duration;
+
+/// A point in time, expressed in milliseconds since some backend-specific epoch.
+///
+/// Timestamps are only meaningful relative to other timestamps from the same backend.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Timestamp {
+    millis: u64,
+}
+
+impl Timestamp {
+    pub fn from_millis(millis: u64) -> Self {
+        Self { millis }
+    }
+
+    pub fn as_millis(&self) -> u64 {
+        self.millis
+    }
+
+    /// The time elapsed since `earlier`, or zero if `earlier` is actually later.
+    pub fn duration_since(&self, earlier: Timestamp) -> Duration {
+        Duration::from_millis(self.millis.saturating_sub(earlier.millis))
+    }
+}
+
+impl Debug for Timestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}ms", self.millis)
+    }
+}
+
+impl Add<Duration> for Timestamp {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        let millis = u64::try_from(rhs.as_millis()).unwrap_or(u64::MAX);
+        Self::from_millis(self.millis.saturating_add(millis))
+    }
+}
+
+impl Sub<Duration> for Timestamp {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        let millis = u64::try_from(rhs.as_millis()).unwrap_or(u64::MAX);
+        Self::from_millis(self.millis.saturating_sub(millis))
+    }
+}
+
+impl Sub for Timestamp {
+    type Output = Duration;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.duration_since(rhs)
+    }
+}
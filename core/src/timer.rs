@@ -0,0 +1,189 @@
+use crate::time::Timestamp;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Identifies a timer that was started through [`Timers`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct TimerId(u64);
+
+#[derive(Debug, Clone)]
+struct Timer {
+    id: TimerId,
+    deadline: Timestamp,
+    interval: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct TimerQueue {
+    next_id: u64,
+    timers: Vec<Timer>,
+    awake_until: Option<Timestamp>,
+}
+
+impl TimerQueue {
+    fn start(&mut self, deadline: Timestamp, interval: Option<Duration>) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push(Timer {
+            id,
+            deadline,
+            interval,
+        });
+        id
+    }
+}
+
+/// A timer service.
+///
+/// This is a cheaply cloneable handle to a shared set of timers, so that components can hold on
+/// to it and start timers from within their event handling. The event loop periodically calls
+/// [`Timers::fire()`] and delivers an event for every timer that went off, which the components
+/// recognize by the [`TimerId`] they got when starting the timer.
+#[derive(Debug, Clone, Default)]
+pub struct Timers {
+    queue: Rc<RefCell<TimerQueue>>,
+}
+
+impl Timers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a timer that goes off once, `delay` after `now`.
+    pub fn start_once(&self, now: Timestamp, delay: Duration) -> TimerId {
+        self.queue.borrow_mut().start(now + delay, None)
+    }
+
+    /// Starts a timer that goes off every `interval`, starting `interval` after `now`, until it
+    /// is cancelled.
+    pub fn start_repeating(&self, now: Timestamp, interval: Duration) -> TimerId {
+        let interval = interval.max(Duration::from_millis(1));
+        self.queue
+            .borrow_mut()
+            .start(now + interval, Some(interval))
+    }
+
+    /// Cancels the timer. Cancelling a timer that has already finished has no effect.
+    pub fn cancel(&self, id: TimerId) {
+        self.queue
+            .borrow_mut()
+            .timers
+            .retain(|timer| timer.id != id);
+    }
+
+    pub fn is_active(&self, id: TimerId) -> bool {
+        self.queue
+            .borrow()
+            .timers
+            .iter()
+            .any(|timer| timer.id == id)
+    }
+
+    /// Requests the event loop to keep iterating until `deadline` rather than waiting for the
+    /// next event, e.g. because an animation is running.
+    pub fn keep_awake_until(&self, deadline: Timestamp) {
+        let mut queue = self.queue.borrow_mut();
+        queue.awake_until = queue.awake_until.max(Some(deadline));
+    }
+
+    /// Whether the event loop was requested to keep iterating at `now`.
+    pub fn is_awake(&self, now: Timestamp) -> bool {
+        self.queue
+            .borrow()
+            .awake_until
+            .is_some_and(|deadline| now < deadline)
+    }
+
+    /// The point in time when the next timer goes off, if any.
+    pub fn next_deadline(&self) -> Option<Timestamp> {
+        self.queue
+            .borrow()
+            .timers
+            .iter()
+            .map(|timer| timer.deadline)
+            .min()
+    }
+
+    /// Returns the timers that went off at or before `now`, in order of their deadlines.
+    ///
+    /// One-shot timers are removed and repeating timers are rescheduled. A repeating timer is
+    /// reported only once, even if multiple intervals have passed since the last call.
+    pub fn fire(&self, now: Timestamp) -> Vec<TimerId> {
+        let mut queue = self.queue.borrow_mut();
+        let mut fired: Vec<(Timestamp, TimerId)> = Vec::new();
+
+        queue.timers.retain_mut(|timer| {
+            if timer.deadline > now {
+                return true;
+            }
+
+            fired.push((timer.deadline, timer.id));
+            match timer.interval {
+                Some(interval) => {
+                    while timer.deadline <= now {
+                        timer.deadline = timer.deadline + interval;
+                    }
+                    true
+                }
+                None => false,
+            }
+        });
+
+        fired.sort();
+        fired.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn at(millis: u64) -> Timestamp {
+        Timestamp::from_millis(millis)
+    }
+
+    #[test]
+    fn test_timers() {
+        let timers = Timers::new();
+        let once = timers.start_once(at(1000), Duration::from_millis(300));
+        let repeating = timers.start_repeating(at(1000), Duration::from_millis(100));
+        assert_eq!(Some(at(1100)), timers.next_deadline());
+
+        assert_eq!(Vec::<TimerId>::new(), timers.fire(at(1099)));
+        assert_eq!(vec![repeating], timers.fire(at(1100)));
+        assert_eq!(Some(at(1200)), timers.next_deadline());
+
+        // Missed intervals are coalesced.
+        assert_eq!(vec![repeating, once], timers.fire(at(1350)));
+        assert!(!timers.is_active(once));
+        assert!(timers.is_active(repeating));
+        assert_eq!(Some(at(1400)), timers.next_deadline());
+
+        timers.cancel(repeating);
+        assert!(!timers.is_active(repeating));
+        assert_eq!(None, timers.next_deadline());
+        assert_eq!(Vec::<TimerId>::new(), timers.fire(at(5000)));
+    }
+
+    #[test]
+    fn test_keep_awake() {
+        let timers = Timers::new();
+        assert!(!timers.is_awake(at(0)));
+
+        timers.keep_awake_until(at(200));
+        timers.keep_awake_until(at(100));
+        assert!(timers.is_awake(at(199)));
+        assert!(!timers.is_awake(at(200)));
+    }
+
+    #[test]
+    fn test_timers_shared() {
+        let timers = Timers::new();
+        let handle = timers.clone();
+        let id = handle.start_once(at(0), Duration::from_millis(10));
+        assert!(timers.is_active(id));
+        assert_eq!(vec![id], timers.fire(at(10)));
+        assert!(!handle.is_active(id));
+    }
+}
@@ -1,17 +1,33 @@
-use crate::ui::main_form::{MainForm, MainFormState};
+use crate::ui::main_form::{
+    MainForm, ACTION_QUIT, ACTION_RIGHT_TO_LEFT, ACTION_SLIDER_MAX, ACTION_SLIDER_MIN,
+    ACTION_WORD_WRAP, LINK_CLEAR_NOTES, LINK_QUIT, QUIT_DIALOG_QUIT, SHORTCUTS,
+};
 use amulet_core::component::{HandleEvent, Layout};
-use amulet_core::geom::Rect;
-use amulet_ez::theme::Theme;
+use amulet_core::geom::{Direction, Rect};
+use amulet_core::keyboard::{Key, ShortcutMatch, ShortcutScope, Shortcuts};
+use amulet_core::overlay::Overlays;
+use amulet_core::timer::Timers;
+use amulet_core::{GlobalEvent, WindowEvent, WindowId};
+use amulet_ez::theme::{FontConfig, FontSource, Theme};
+use amulet_sdl2::cursor::Cursors;
 use amulet_sdl2::lossy::LossyInto;
 use amulet_sdl2::render::{Render, RenderContext};
-use amulet_sdl2::{event_iterator, Event};
-use sdl2::event::{Event as SdlEvent, WindowEvent};
-use sdl2::keyboard::Keycode;
+use amulet_sdl2::{Event, EventSource};
+use sdl2::event::Event as SdlEvent;
 use sdl2::pixels::Color;
 use std::ops::{Deref, DerefMut};
 
 mod ui;
 
+/// System fonts that cover the accented, CJK and emoji characters of the demo, which
+/// MuseoModerno lacks. Fonts that aren't installed are skipped.
+const FALLBACK_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/ancient-scripts/Symbola_hint.ttf",
+];
+
 #[derive(Debug, Default)]
 struct AppState {
     click_count: u64,
@@ -52,11 +68,12 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
     let video_subsystem = sdl_context.video()?;
 
     let window = video_subsystem
-        .window("Amulet Demo", 800, 600)
+        .window("Amulet Demo", 1024, 640)
         .position_centered()
         .resizable()
         .build()?;
 
+    let window_id = WindowId(window.id());
     let mut window_rect = ChangeDetector::new({
         let size: (i32, i32) = window.size().lossy_into();
         Rect::from_size(size.into())
@@ -66,68 +83,123 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let ttf_context = sdl2::ttf::init()?;
     let texture_creator = canvas.texture_creator();
-    let theme = Theme::create(&ttf_context, &texture_creator)?;
+    let font_config = FALLBACK_FONTS
+        .iter()
+        .fold(FontConfig::default(), |config, path| {
+            config.with_font(FontSource::File(path.into()))
+        });
+    let theme = Theme::create_with_fonts(&ttf_context, &texture_creator, &font_config)?;
 
     let mut event_pump = sdl_context.event_pump()?;
+    let mut event_source = EventSource::new(sdl_context.timer()?, Timers::new());
+    let overlays = Overlays::new();
+    let shortcuts = Shortcuts::new();
+    for (shortcut, action) in SHORTCUTS {
+        shortcuts.register(ShortcutScope::Window(window_id), shortcut.parse()?, *action)?;
+    }
+
+    let mut cursors = Cursors::new();
 
     let mut app_state = ChangeDetector::new(AppState::default());
-    let mut main_form_state = MainFormState::default();
+    let mut main_form = MainForm::new(
+        &theme,
+        event_source.timers().clone(),
+        overlays.clone(),
+        *window_rect,
+        app_state.click_count,
+    )?;
+    let mut main_form_state = main_form.create_state();
     // main_form_state.dyn_text.push_str("Hello: ");
 
-    let mut main_form = MainForm::new(&theme, *window_rect, app_state.click_count)?;
-
     'running: loop {
-        for event in event_iterator(&mut event_pump) {
+        let direction = if main_form_state
+            .menu_bar
+            .menu()
+            .is_checked(ACTION_RIGHT_TO_LEFT)
+        {
+            Direction::RightToLeft
+        } else {
+            Direction::LeftToRight
+        };
+        let mut invoked = Vec::new();
+        for event in event_source.wait(&mut event_pump) {
             match event {
+                Event::Amulet(GlobalEvent::KeyDown(Key::Escape, ..))
+                    if overlays.is_empty() && main_form_state.planet_table.editing().is_none() =>
+                {
+                    break 'running
+                }
                 Event::Amulet(evt) => {
-                    main_form.handle_event(
-                        &mut main_form_state,
-                        evt.into_component_event(*window_rect),
-                    )?;
+                    let matched = if overlays.has_modal() {
+                        ShortcutMatch::NoMatch
+                    } else {
+                        shortcuts.handle_event(&evt, window_id)
+                    };
+                    if matched.action() == Some(ACTION_WORD_WRAP) {
+                        // Menu items toggle their check mark themselves, shortcuts don't.
+                        let menu = main_form_state.menu_bar.menu_mut();
+                        let checked = menu.is_checked(ACTION_WORD_WRAP);
+                        menu.set_checked(ACTION_WORD_WRAP, !checked);
+                    }
+                    invoked.extend(matched.action());
+                    if matched.is_consumed() {
+                        continue;
+                    }
+
+                    if let GlobalEvent::Window(WindowEvent::Resized(size), _) = evt {
+                        *window_rect = window_rect.resize(size);
+                    }
+
+                    let evt = overlays.route(evt, *window_rect).with_direction(direction);
+                    main_form.handle_event(&mut main_form_state, evt)?;
                 }
                 Event::Sdl(evt) => match evt {
-                    SdlEvent::Quit { .. }
-                    | SdlEvent::KeyDown {
-                        keycode: Some(Keycode::Escape),
-                        ..
-                    } => break 'running,
-                    SdlEvent::Window { win_event, .. } => match win_event {
-                        WindowEvent::SizeChanged(x, y) => {
-                            *window_rect = window_rect.resize((x, y).into());
-                        }
-                        WindowEvent::Resized(x, y) => {
-                            *window_rect = window_rect.resize((x, y).into());
-                        }
-                        _ => {}
-                    },
-                    SdlEvent::TextInput {text, ..} => {
-                        main_form_state.text_input.update(&text);
+                    SdlEvent::Quit { .. } => break 'running,
+                    SdlEvent::TextInput { text, .. } => {
+                        main_form_state.text_input.input_text(&text);
+                        main_form_state.editable_combo_box.input_text(&text);
+                        main_form_state.planet_table.input_text(&text);
+                        main_form_state.notes.input_text(&text);
                     }
-                    SdlEvent::KeyDown { keycode, ..} => match keycode {
-                        Some(Keycode::Backspace) => {
-                            // let new_len = main_form_state.dyn_text.len() - 1;
-                            // main_form_state.dyn_text.truncate(new_len);
-                        }
-                        _ => {}
-                    },
                     _ => {}
                 },
             }
         }
 
+        main_form.update_planets(&mut main_form_state);
+        cursors.set(main_form.cursor(&main_form_state))?;
         if main_form_state.button.was_clicked() {
             app_state.click_count += 1;
         }
         if main_form_state.btn_cancel.was_clicked() {
+            main_form.open_quit_dialog(&mut main_form_state);
+        }
+        match main_form_state.about.clicked_link() {
+            Some(LINK_CLEAR_NOTES) => main_form_state.notes.set_text(""),
+            Some(LINK_QUIT) => main_form.open_quit_dialog(&mut main_form_state),
+            _ => {}
+        }
+        if main_form_state.quit_dialog.response() == Some(QUIT_DIALOG_QUIT) {
             break 'running;
         }
+        invoked.extend(main_form_state.menu_bar.invoked());
+        invoked.extend(main_form_state.slider_menu.invoked());
+        for action in invoked {
+            match action {
+                ACTION_QUIT => main_form.open_quit_dialog(&mut main_form_state),
+                ACTION_SLIDER_MIN => main_form_state.slider.set_value(0),
+                ACTION_SLIDER_MAX => main_form_state.slider.set_value(100),
+                _ => {}
+            }
+        }
 
         canvas.set_draw_color(Color::RGB(0x3c, 0x3f, 0x41));
         canvas.clear();
 
         let mut render_ctx = RenderContext::new(&mut canvas);
-        let layout = Layout::new(*window_rect);
-        main_form.render(&main_form_state, layout, &mut render_ctx)?;
+        let layout = Layout::new(*window_rect).with_direction(direction);
+        main_form.render(&main_form_state, layout.clone(), &mut render_ctx)?;
+        main_form.render_overlay(&main_form_state, layout, &mut render_ctx)?;
 
         canvas.present();
 
@@ -135,7 +207,7 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
             main_form.update_click_count(app_state.click_count)?;
         }
         if window_rect.changed() {
-            main_form.resize(*window_rect)?;
+            main_form.resize(&mut main_form_state, *window_rect)?;
         }
     }
 
@@ -1,33 +1,193 @@
+use amulet_core::action::ActionId;
+use amulet_core::anim::Easing;
 use amulet_core::component::{
-    AsChain, ComponentEvent, Frame, HandleEvent, Layout, Position, PositionAttr, SizeAttr,
-    UpdateLayout,
+    AnimatedPosition, AnimatedPositionState, AsChain, ComponentEvent, Frame, HandleEvent, Layout,
+    Position, PositionAttr, SizeAttr, UpdateLayout,
 };
-use amulet_core::geom::{Point, Rect, Size};
+use amulet_core::geom::{Orientation, Point, Rect, Size};
+use amulet_core::mouse::CursorShape;
+use amulet_core::overlay::Overlays;
+use amulet_core::timer::Timers;
 use amulet_core::VuiResult;
+use amulet_ez::text::{Alignment, RichText, TextFormat, TextStyle};
 use amulet_ez::theme::Theme;
-use amulet_ez::widget::{Button, ButtonState, DynText, TextInput, TextInputState};
+use amulet_ez::widget::{
+    Button, ButtonState, ComboBox, ComboBoxState, ContextMenu, ContextMenuState, Dialog,
+    DialogState, DynText, Image, ListView, ListViewState, MenuBar, MenuBarState, MenuEntry,
+    RichLabel, RichLabelState, Scrollbar, ScrollbarState, SelectionMode, Slider, SliderState,
+    SortOrder, Split, SplitState, Table, TableColumn, TableState, Tabs, TabsState, TextArea,
+    TextAreaState, TextInput, TextInputState, Tooltip, TooltipState, TreeModel, TreeView,
+    TreeViewState, ValueRange,
+};
 use amulet_sdl2::render::{Render, RenderContext};
+use sdl2::pixels::Color;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
 
 #[derive(Debug, Default)]
 pub struct MainFormState {
+    pub anchor: AnimatedPositionState,
     pub button: ButtonState,
+    pub button_tooltip: TooltipState,
     pub btn_ok: ButtonState,
     pub btn_defaults: ButtonState,
     pub btn_cancel: ButtonState,
     pub text_input: TextInputState,
+    pub slider: SliderState,
+    pub scrollbar: ScrollbarState,
+    pub combo_box: ComboBoxState,
+    pub editable_combo_box: ComboBoxState,
+    pub menu_bar: MenuBarState,
+    pub slider_menu: ContextMenuState,
+    pub quit_dialog: DialogState,
+    pub log_view: ListViewState,
+    pub planet_table: TableState,
+    pub outline: TreeViewState,
+    pub color_tabs: TabsState,
+    pub color_channels: [SliderState; 4],
+    pub pane_split: SplitState,
+    pub notes: TextAreaState,
+    pub about: RichLabelState,
 }
 
 pub struct MainForm<'a> {
     theme: &'a Theme<'a>,
+    timers: Timers,
+    overlays: Overlays,
+    menu_bar: MenuBar<'a>,
     button: (Position, Button<'a>),
-    /// Anchor position for `btn_*` components.
-    anchor: Position,
+    button_tooltip: (Position, Tooltip<'a>),
+    /// Anchor position for `btn_*` components, which slides along when the window is resized.
+    anchor: AnimatedPosition,
+    anchor_target: Point,
     btn_ok: (Position, Button<'a>),
     btn_defaults: (Position, Button<'a>),
     btn_cancel: (Position, Button<'a>),
-    text_input: TextInput<'a>,
+    text_input: (Position, TextInput<'a>),
+    slider: (Position, Slider<'a>),
+    slider_menu: (Position, ContextMenu<'a>),
+    scrollbar: (Position, Scrollbar<'a>),
+    combo_box: (Position, ComboBox<'a>),
+    editable_combo_box: (Position, ComboBox<'a>),
+    quit_dialog: Dialog<'a, Image<'a>>,
+    log_view: (Position, ListView<'a>),
+    planets: Rc<RefCell<Vec<Planet>>>,
+    planet_table: (Position, Table<'a>),
+    outline: (Position, TreeView<'a>),
+    color_tabs: (Position, Tabs<'a, Slider<'a>>),
+    pane_split: (Position, Split<'a, Image<'a>, Image<'a>>),
+    notes: (Position, TextArea<'a>),
+    about: (Position, RichLabel<'a>),
+}
+
+pub const ACTION_NEW: ActionId = ActionId(1);
+pub const ACTION_OPEN: ActionId = ActionId(2);
+pub const ACTION_SAVE: ActionId = ActionId(3);
+pub const ACTION_QUIT: ActionId = ActionId(4);
+pub const ACTION_SHOW_TOOLTIPS: ActionId = ActionId(5);
+pub const ACTION_WORD_WRAP: ActionId = ActionId(6);
+pub const ACTION_ABOUT: ActionId = ActionId(7);
+pub const ACTION_SLIDER_MIN: ActionId = ActionId(8);
+pub const ACTION_SLIDER_MAX: ActionId = ActionId(9);
+pub const ACTION_RIGHT_TO_LEFT: ActionId = ActionId(10);
+
+/// The response of the quit dialog that confirms quitting.
+pub const QUIT_DIALOG_QUIT: usize = 0;
+
+/// The targets of the links in the about text.
+pub const LINK_CLEAR_NOTES: &str = "clear-notes";
+pub const LINK_QUIT: &str = "quit";
+
+/// The keyboard shortcuts of the window, which are also shown in the menus.
+pub const SHORTCUTS: &[(&str, ActionId)] = &[
+    ("Ctrl+N", ACTION_NEW),
+    ("Ctrl+O", ACTION_OPEN),
+    ("Ctrl+S", ACTION_SAVE),
+    ("Ctrl+Q", ACTION_QUIT),
+    ("Ctrl+K Ctrl+W", ACTION_WORD_WRAP),
+];
+
+fn accelerator(action: ActionId) -> &'static str {
+    SHORTCUTS
+        .iter()
+        .find(|(_, a)| *a == action)
+        .map_or("", |(shortcut, _)| shortcut)
+}
+
+const LOG_LINES: usize = 100_000;
+
+#[derive(Debug, Clone)]
+struct Planet {
+    name: String,
+    moons: u32,
+    /// In units of 1000 km.
+    radius: f32,
+}
+
+const PLANETS: &[(&str, u32, f32)] = &[
+    ("Mercury", 0, 2.44),
+    ("Venus", 0, 6.05),
+    ("Earth", 1, 6.37),
+    ("Mars", 2, 3.39),
+    ("Jupiter", 95, 69.91),
+    ("Saturn", 146, 58.23),
+    ("Uranus", 28, 25.36),
+    ("Neptune", 16, 24.62),
+];
+
+const PLANET_NAME: usize = 0;
+const PLANET_MOONS: usize = 1;
+const PLANET_RADIUS: usize = 2;
+
+/// A made-up outline whose nodes are generated as they are expanded.
+struct Outline;
+
+impl Outline {
+    const DEPTH: usize = 4;
 }
 
+impl TreeModel for Outline {
+    fn child_count(&self, path: &[usize]) -> usize {
+        if path.len() < Self::DEPTH {
+            5 - path.len()
+        } else {
+            0
+        }
+    }
+
+    fn label(&self, path: &[usize]) -> String {
+        let numbers: Vec<_> = path.iter().map(|index| (index + 1).to_string()).collect();
+        format!("Section {}", numbers.join("."))
+    }
+
+    fn has_children(&self, path: &[usize]) -> bool {
+        path.len() < Self::DEPTH
+    }
+}
+
+const SPLIT_TEXT: &str = "Drag the handle between the panes to distribute the space between them. \
+    The text in the top pane is centered, while the text in the bottom pane is justified and \
+    limited to six lines. Characters that the main font lacks, like those of “Καλημέρα” or \
+    “日本語”, are rendered with fallback fonts.";
+
+const FRUITS: &[&str] = &[
+    "Apple",
+    "Apricot",
+    "Banana",
+    "Blueberry",
+    "Cherry",
+    "Grape",
+    "Kiwi",
+    "Lemon",
+    "Mango",
+    "Orange",
+    "Peach",
+    "Pear",
+    "Plum",
+    "Strawberry",
+];
+
 trait AlignCenter {
     fn align_center(self, max_size: Size) -> (Frame, Position, Self);
 }
@@ -43,8 +203,15 @@ where
 }
 
 impl<'a> MainForm<'a> {
-    pub fn new(theme: &'a Theme<'a>, rect: Rect, click_count: u64) -> VuiResult<Self> {
+    pub fn new(
+        theme: &'a Theme<'a>,
+        timers: Timers,
+        overlays: Overlays,
+        rect: Rect,
+        click_count: u64,
+    ) -> VuiResult<Self> {
         let button = Self::create_button(theme, click_count)?;
+        let button_tooltip = Self::create_button_tooltip(theme, &timers, &button)?;
 
         let lbl_ok = theme.label("OK")?;
         let lbl_defaults = theme.label("Defaults")?;
@@ -67,42 +234,349 @@ impl<'a> MainForm<'a> {
             Position::new(pos),
             theme.button(lbl_cancel.align_center(max))?,
         );
-        let anchor = Self::calc_anchor(rect, &btn_cancel);
+        let anchor = AnimatedPosition::new(timers.clone());
+        let anchor_target = Self::calc_anchor(rect, &btn_cancel);
         let text_input_content = (
             Frame::new((100, 32).into()),
             Position::new(Point::zero()),
             DynText::new(theme),
         );
-        let text_input = theme.text_input(text_input_content)?;
+        let text_input = (
+            Position::new((80, 40).into()),
+            theme.text_input(text_input_content)?,
+        );
+        let slider = (
+            Position::new((80, 160).into()),
+            theme.slider(
+                Orientation::Horizontal,
+                200,
+                ValueRange::new(0, 100).with_step(5),
+            )?,
+        );
+        let scrollbar = (
+            Position::new((300, 200).into()),
+            theme.scrollbar(Orientation::Vertical, 150, 1000, 150)?,
+        );
+        let combo_box = (
+            Position::new((80, 200).into()),
+            theme.combo_box(160, FRUITS, overlays.clone())?,
+        );
+        let editable_combo_box = (
+            Position::new((80, 240).into()),
+            theme
+                .combo_box(160, FRUITS, overlays.clone())?
+                .editable(DynText::new(theme)),
+        );
+        let menu_bar = Self::create_menu_bar(theme, &overlays, rect)?;
+        let slider_menu = (
+            slider.0.clone(),
+            theme.context_menu(
+                slider.1.size(),
+                &[
+                    MenuEntry::item("Reset to &minimum", ACTION_SLIDER_MIN),
+                    MenuEntry::item("Reset to ma&ximum", ACTION_SLIDER_MAX),
+                ],
+                overlays.clone(),
+            )?,
+        );
+        let quit_dialog = theme.dialog(
+            "Quit",
+            theme.label("Quit without saving your changes?")?,
+            &["Quit", "Cancel"],
+            overlays.clone(),
+        )?;
+        let log_text = DynText::new(theme);
+        let log_view = (
+            Position::new((360, 40).into()),
+            theme
+                .list_view(
+                    Size::new(400, 300),
+                    LOG_LINES,
+                    20,
+                    move |row, layout, render_ctx| {
+                        let line = format!("{:06} Something happened", row.index + 1);
+                        log_text.render(&line, layout, render_ctx)
+                    },
+                )?
+                .with_selection_mode(SelectionMode::Multiple),
+        );
+
+        let planets = Rc::new(RefCell::new(
+            PLANETS
+                .iter()
+                .map(|&(name, moons, radius)| Planet {
+                    name: name.to_string(),
+                    moons,
+                    radius,
+                })
+                .collect::<Vec<_>>(),
+        ));
+        let planet_table = (
+            Position::new((360, 360).into()),
+            Self::create_planet_table(theme, planets.clone())?,
+        );
+        let outline = (
+            Position::new((80, 290).into()),
+            theme.tree_view(Size::new(200, 240), 20)?,
+        );
+        let color_tabs = (
+            Position::new((800, 40).into()),
+            Self::create_color_tabs(theme)?,
+        );
+        let pane_split = (
+            Position::new((800, 180).into()),
+            theme
+                .split(
+                    Orientation::Vertical,
+                    Size::new(200, 340),
+                    theme.formatted_label(
+                        SPLIT_TEXT,
+                        &TextFormat::new()
+                            .with_width(200)
+                            .with_wrap(true)
+                            .with_alignment(Alignment::Center),
+                    )?,
+                    theme.formatted_label(
+                        SPLIT_TEXT,
+                        &TextFormat::new()
+                            .with_width(200)
+                            .with_wrap(true)
+                            .with_alignment(Alignment::Justify)
+                            .with_max_lines(6)
+                            .with_ellipsis(),
+                    )?,
+                )?
+                .with_min_sizes(40, 40),
+        );
+        let notes = (
+            Position::new((80, 545).into()),
+            theme.text_area(Size::new(660, 85))?,
+        );
+        let about = (
+            Position::new((800, 545).into()),
+            theme.rich_label(&Self::about_text(), Some(200))?,
+        );
 
         Ok(Self {
             theme,
+            timers,
+            overlays,
+            menu_bar,
             button,
+            button_tooltip,
             anchor,
+            anchor_target,
             btn_ok,
             btn_defaults,
             btn_cancel,
             text_input,
+            slider,
+            slider_menu,
+            scrollbar,
+            combo_box,
+            editable_combo_box,
+            quit_dialog,
+            log_view,
+            planets,
+            planet_table,
+            outline,
+            color_tabs,
+            pane_split,
+            notes,
+            about,
         })
     }
 
+    fn about_text() -> RichText {
+        let plain = TextStyle::new();
+        RichText::new()
+            .with_span("Rich text ", plain.with_bold())
+            .with_span("mixes ", plain)
+            .with_span("italic", plain.with_italic())
+            .with_span(", ", plain)
+            .with_span("struck", plain.with_strikethrough())
+            .with_span(", ", plain)
+            .with_span("large", plain.with_size(20))
+            .with_span(" and ", plain)
+            .with_span("colored", plain.with_color(Color::RGB(0xe0, 0x8a, 0x4c)))
+            .with_span(" text. Links ", plain)
+            .with_link("clear the notes", plain, LINK_CLEAR_NOTES)
+            .with_span(" or ", plain)
+            .with_link("quit", plain.with_bold(), LINK_QUIT)
+            .with_span(".", plain)
+    }
+
+    fn create_color_tabs(theme: &'a Theme<'a>) -> VuiResult<Tabs<'a, Slider<'a>>> {
+        let pages = ["Red", "Green", "Blue", "Alpha"]
+            .into_iter()
+            .map(|title| {
+                let slider = theme.slider(
+                    Orientation::Horizontal,
+                    180,
+                    ValueRange::new(0, 255).with_step(5),
+                )?;
+                Ok((title, slider))
+            })
+            .collect::<VuiResult<Vec<_>>>()?;
+        theme.tabs(Size::new(200, 120), pages, true)
+    }
+
+    fn create_planet_table(
+        theme: &'a Theme<'a>,
+        planets: Rc<RefCell<Vec<Planet>>>,
+    ) -> VuiResult<Table<'a>> {
+        let columns = [
+            TableColumn::new("Planet", 160).sortable().editable(),
+            TableColumn::new("Moons", 80).sortable(),
+            TableColumn::new("Radius", 120).sortable(),
+        ];
+        let row_count = planets.borrow().len();
+        let text = DynText::new(theme);
+        theme.table(
+            Size::new(400, 170),
+            &columns,
+            row_count,
+            20,
+            move |cell, layout, render_ctx| {
+                let planets = planets.borrow();
+                let planet = &planets[cell.row];
+                let value = match cell.column {
+                    PLANET_NAME => planet.name.clone(),
+                    PLANET_MOONS => planet.moons.to_string(),
+                    _ => format!("{:.2} Mm", planet.radius),
+                };
+                text.render(&value, layout, render_ctx)
+            },
+        )
+    }
+
     fn create_button(theme: &'a Theme<'a>, click_count: u64) -> VuiResult<(Position, Button<'a>)> {
         let text = theme.label(&format!("EZ Button ({} clicks)", click_count))?;
         let content = (Frame::new(text.size()), Position::new(Point::zero()), text);
         Ok((Position::new((80, 100).into()), theme.button(content)?))
     }
 
-    fn calc_anchor(rect: Rect, btn: &(Position, Button)) -> Position {
-        Position::new(rect.limit() - Rect::new(btn.0.position(), btn.1.size()).limit())
+    fn create_button_tooltip(
+        theme: &'a Theme<'a>,
+        timers: &Timers,
+        button: &(Position, Button<'a>),
+    ) -> VuiResult<(Position, Tooltip<'a>)> {
+        let tooltip = theme.tooltip(
+            "Counts how often it was clicked",
+            button.1.size(),
+            timers.clone(),
+        )?;
+        Ok((button.0.clone(), tooltip))
+    }
+
+    fn create_menu_bar(
+        theme: &'a Theme<'a>,
+        overlays: &Overlays,
+        rect: Rect,
+    ) -> VuiResult<MenuBar<'a>> {
+        let file = vec![
+            MenuEntry::item("&New", ACTION_NEW).with_accelerator(accelerator(ACTION_NEW)),
+            MenuEntry::item("&Open...", ACTION_OPEN).with_accelerator(accelerator(ACTION_OPEN)),
+            MenuEntry::item("&Save", ACTION_SAVE).with_accelerator(accelerator(ACTION_SAVE)),
+            MenuEntry::Separator,
+            MenuEntry::submenu(
+                "&Recent files",
+                vec![
+                    MenuEntry::item("notes.txt", ACTION_OPEN),
+                    MenuEntry::item("todo.txt", ACTION_OPEN),
+                ],
+            ),
+            MenuEntry::Separator,
+            MenuEntry::item("&Quit", ACTION_QUIT).with_accelerator(accelerator(ACTION_QUIT)),
+        ];
+        let view = vec![
+            MenuEntry::check_item("Show &tooltips", ACTION_SHOW_TOOLTIPS),
+            MenuEntry::check_item("&Word wrap", ACTION_WORD_WRAP)
+                .with_accelerator(accelerator(ACTION_WORD_WRAP)),
+            MenuEntry::check_item("&Right to left", ACTION_RIGHT_TO_LEFT),
+        ];
+        let help = vec![MenuEntry::item("&About", ACTION_ABOUT)];
+
+        theme.menu_bar(
+            rect.size.width,
+            &[("&File", file), ("&View", view), ("&Help", help)],
+            overlays.clone(),
+        )
     }
 
-    pub fn resize(&mut self, rect: Rect) -> VuiResult<()> {
-        self.anchor = Self::calc_anchor(rect, &self.btn_cancel);
+    fn calc_anchor(rect: Rect, btn: &(Position, Button)) -> Point {
+        rect.limit() - Rect::new(btn.0.position(), btn.1.size()).limit()
+    }
+
+    pub fn create_state(&self) -> MainFormState {
+        MainFormState {
+            anchor: AnimatedPositionState::new(self.anchor_target),
+            ..MainFormState::default()
+        }
+    }
+
+    pub fn open_quit_dialog(&self, gui_state: &mut MainFormState) {
+        self.quit_dialog.open(&mut gui_state.quit_dialog);
+    }
+
+    /// Applies sorting and edits of the planet table to the planets. Called once per iteration of
+    /// the event loop, after all events were handled.
+    pub fn update_planets(&self, gui_state: &mut MainFormState) {
+        let table = &mut gui_state.planet_table;
+        let mut planets = self.planets.borrow_mut();
+        if let Some(cell) = table.edit_started() {
+            table.set_edit_text(&planets[cell.row].name);
+        }
+        if let Some(cell) = table.committed() {
+            let name = table.edit_text().trim();
+            if !name.is_empty() {
+                planets[cell.row].name = name.to_string();
+            }
+        }
+        if table.sort_changed() {
+            if let Some((column, order)) = table.sort() {
+                planets.sort_by(|a, b| {
+                    let ordering = match column {
+                        PLANET_NAME => a.name.cmp(&b.name),
+                        PLANET_MOONS => a.moons.cmp(&b.moons),
+                        PLANET_RADIUS => a.radius.total_cmp(&b.radius),
+                        _ => std::cmp::Ordering::Equal,
+                    };
+                    match order {
+                        SortOrder::Ascending => ordering,
+                        SortOrder::Descending => ordering.reverse(),
+                    }
+                });
+            }
+            // The selection follows the position, not the planet.
+            table.set_selected_row(None);
+        }
+    }
+
+    /// The shape the mouse cursor should have.
+    pub fn cursor(&self, gui_state: &MainFormState) -> CursorShape {
+        self.pane_split
+            .1
+            .cursor(&gui_state.pane_split)
+            .or_else(|| self.about.1.cursor(&gui_state.about))
+            .unwrap_or_default()
+    }
+
+    pub fn resize(&mut self, gui_state: &mut MainFormState, rect: Rect) -> VuiResult<()> {
+        self.anchor_target = Self::calc_anchor(rect, &self.btn_cancel);
+        self.anchor.animate_to(
+            &mut gui_state.anchor,
+            self.anchor_target,
+            Duration::from_millis(250),
+            Easing::CubicOut,
+        );
+        self.menu_bar = Self::create_menu_bar(self.theme, &self.overlays, rect)?;
         Ok(())
     }
 
     pub fn update_click_count(&mut self, click_count: u64) -> VuiResult<()> {
         self.button = Self::create_button(self.theme, click_count)?;
+        self.button_tooltip = Self::create_button_tooltip(self.theme, &self.timers, &self.button)?;
         Ok(())
     }
 }
@@ -115,12 +589,25 @@ impl HandleEvent for MainForm<'_> {
         gui_state: &mut MainFormState,
         event: ComponentEvent,
     ) -> VuiResult<ComponentEvent> {
+        // Checked up front, so that the key closing the dialog doesn't reach the form.
+        let blocked = event.is_keyboard() && !self.overlays.is_interactive(None);
+        self.quit_dialog
+            .handle_event((&mut gui_state.quit_dialog, ()), event.clone())?;
+        if blocked {
+            return Ok(event);
+        }
+
         self.button
             .as_chain()
             .handle_event(((), &mut gui_state.button), event.clone())?;
+        self.button_tooltip
+            .as_chain()
+            .handle_event(((), &mut gui_state.button_tooltip), event.clone())?;
 
         {
-            let event = self.anchor.handle_event((), event.clone())?;
+            let event = self
+                .anchor
+                .handle_event(&mut gui_state.anchor, event.clone())?;
             self.btn_ok
                 .as_chain()
                 .handle_event(((), &mut gui_state.btn_ok), event.clone())?;
@@ -133,7 +620,50 @@ impl HandleEvent for MainForm<'_> {
         }
 
         self.text_input
-            .handle_event(&mut gui_state.text_input, event.clone())?;
+            .as_chain()
+            .handle_event(((), &mut gui_state.text_input), event.clone())?;
+        self.slider
+            .as_chain()
+            .handle_event(((), &mut gui_state.slider), event.clone())?;
+        self.slider_menu
+            .as_chain()
+            .handle_event(((), &mut gui_state.slider_menu), event.clone())?;
+        self.scrollbar
+            .as_chain()
+            .handle_event(((), &mut gui_state.scrollbar), event.clone())?;
+        self.combo_box
+            .as_chain()
+            .handle_event(((), &mut gui_state.combo_box), event.clone())?;
+        self.editable_combo_box
+            .as_chain()
+            .handle_event(((), &mut gui_state.editable_combo_box), event.clone())?;
+        self.log_view
+            .as_chain()
+            .handle_event(((), &mut gui_state.log_view), event.clone())?;
+        self.planet_table
+            .as_chain()
+            .handle_event(((), &mut gui_state.planet_table), event.clone())?;
+        self.outline
+            .as_chain()
+            .handle_event(((), (&mut gui_state.outline, &Outline)), event.clone())?;
+        self.pane_split
+            .as_chain()
+            .handle_event(((), (&mut gui_state.pane_split, (), ())), event.clone())?;
+        self.color_tabs.as_chain().handle_event(
+            (
+                (),
+                (&mut gui_state.color_tabs, &mut gui_state.color_channels),
+            ),
+            event.clone(),
+        )?;
+        self.notes
+            .as_chain()
+            .handle_event(((), &mut gui_state.notes), event.clone())?;
+        self.about
+            .as_chain()
+            .handle_event(((), &mut gui_state.about), event.clone())?;
+        self.menu_bar
+            .handle_event(&mut gui_state.menu_bar, event.clone())?;
 
         // Kind of nonsensical =)
         Ok(event)
@@ -152,7 +682,9 @@ impl Render for MainForm<'_> {
         self.button
             .render(((), &gui_state.button), layout.clone(), render_ctx)?;
         {
-            let layout = self.anchor.update_layout((), layout.clone())?;
+            let layout = self
+                .anchor
+                .update_layout(&gui_state.anchor, layout.clone())?;
             self.btn_ok
                 .render(((), &gui_state.btn_ok), layout.clone(), render_ctx)?;
             self.btn_defaults
@@ -162,8 +694,73 @@ impl Render for MainForm<'_> {
         }
 
         self.text_input
-            .render(&gui_state.text_input, layout, render_ctx)?;
+            .render(((), &gui_state.text_input), layout.clone(), render_ctx)?;
+        self.slider
+            .render(((), &gui_state.slider), layout.clone(), render_ctx)?;
+        self.scrollbar
+            .render(((), &gui_state.scrollbar), layout.clone(), render_ctx)?;
+        self.combo_box
+            .render(((), &gui_state.combo_box), layout.clone(), render_ctx)?;
+        self.editable_combo_box.render(
+            ((), &gui_state.editable_combo_box),
+            layout.clone(),
+            render_ctx,
+        )?;
+        self.log_view
+            .render(((), &gui_state.log_view), layout.clone(), render_ctx)?;
+        self.planet_table
+            .render(((), &gui_state.planet_table), layout.clone(), render_ctx)?;
+        self.outline.render(
+            ((), (&gui_state.outline, &Outline)),
+            layout.clone(),
+            render_ctx,
+        )?;
+        self.color_tabs.render(
+            ((), (&gui_state.color_tabs, &gui_state.color_channels)),
+            layout.clone(),
+            render_ctx,
+        )?;
+        self.pane_split.render(
+            ((), (&gui_state.pane_split, (), ())),
+            layout.clone(),
+            render_ctx,
+        )?;
+        self.notes
+            .render(((), &gui_state.notes), layout.clone(), render_ctx)?;
+        self.about
+            .render(((), &gui_state.about), layout.clone(), render_ctx)?;
+        self.menu_bar
+            .render(&gui_state.menu_bar, layout, render_ctx)?;
 
         Ok(())
     }
+
+    fn render_overlay(
+        &self,
+        gui_state: Self::State<'_>,
+        layout: Layout,
+        render_ctx: &mut RenderContext,
+    ) -> VuiResult<()> {
+        self.button_tooltip.render_overlay(
+            ((), &gui_state.button_tooltip),
+            layout.clone(),
+            render_ctx,
+        )?;
+        self.combo_box
+            .render_overlay(((), &gui_state.combo_box), layout.clone(), render_ctx)?;
+        self.editable_combo_box.render_overlay(
+            ((), &gui_state.editable_combo_box),
+            layout.clone(),
+            render_ctx,
+        )?;
+        self.slider_menu.render_overlay(
+            ((), &gui_state.slider_menu),
+            layout.clone(),
+            render_ctx,
+        )?;
+        self.menu_bar
+            .render_overlay(&gui_state.menu_bar, layout.clone(), render_ctx)?;
+        self.quit_dialog
+            .render_overlay((&gui_state.quit_dialog, ()), layout, render_ctx)
+    }
 }
@@ -1,2 +1,3 @@
+pub mod text;
 pub mod theme;
 pub mod widget;
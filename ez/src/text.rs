@@ -0,0 +1,433 @@
+use crate::theme::FontCollection;
+use amulet_core::geom::{Point, Size};
+use amulet_core::VuiResult;
+use std::ops::Range;
+
+mod rich_text;
+
+pub use rich_text::{RichText, RichTextLayout, Span, StyledRun, TextStyle};
+
+const ELLIPSIS: &str = "\u{2026}";
+
+/// How the lines of a [`TextLayout`] are aligned horizontally.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// Stretches the spaces between the words of wrapped lines so that the lines fill the width
+    /// of the layout. The last line of a paragraph is aligned left.
+    Justify,
+}
+
+/// How a [`TextLayout`] arranges text. By default, every paragraph is laid out on a single line
+/// of unlimited width.
+#[derive(Debug, Clone, Default)]
+pub struct TextFormat {
+    width: Option<i32>,
+    wrap: bool,
+    alignment: Alignment,
+    max_lines: Option<usize>,
+    ellipsis: bool,
+}
+
+impl TextFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the lines to `width`. Longer lines are wrapped if [`Self::with_wrap()`] is set, and
+    /// truncated otherwise.
+    pub fn with_width(self, width: i32) -> Self {
+        Self {
+            width: Some(width.max(1)),
+            ..self
+        }
+    }
+
+    /// Breaks lines that are wider than the width at word boundaries.
+    pub fn with_wrap(self, wrap: bool) -> Self {
+        Self { wrap, ..self }
+    }
+
+    pub fn with_alignment(self, alignment: Alignment) -> Self {
+        Self { alignment, ..self }
+    }
+
+    /// Drops the lines beyond `max_lines`.
+    pub fn with_max_lines(self, max_lines: usize) -> Self {
+        Self {
+            max_lines: Some(max_lines.max(1)),
+            ..self
+        }
+    }
+
+    /// Ends lines that were truncated with an ellipsis. If lines were dropped, the last remaining
+    /// line ends with one.
+    pub fn with_ellipsis(self) -> Self {
+        Self {
+            ellipsis: true,
+            ..self
+        }
+    }
+}
+
+/// A piece of a [`TextLayout`] that is rendered in one go.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GlyphRun {
+    pub text: String,
+    /// Relative to the layout.
+    pub position: Point,
+    pub size: Size,
+}
+
+/// A line before it is positioned.
+struct Line {
+    text: String,
+    /// Whether the line is stretched when justifying, which isn't the case for the last line of
+    /// a paragraph.
+    justifiable: bool,
+}
+
+/// Text that was measured and arranged on lines, ready to be rendered.
+///
+/// Lines end at line breaks in the text and, if wrapping is enabled, where the next word wouldn't
+/// fit the width of the [`TextFormat`]. The layout is as wide as its widest line, and the lines
+/// are aligned within that width.
+#[derive(Debug, Clone)]
+pub struct TextLayout {
+    runs: Vec<GlyphRun>,
+    line_count: usize,
+    size: Size,
+    truncated: bool,
+}
+
+impl TextLayout {
+    /// Lays out `text`, measuring it with `fonts`.
+    pub fn new(fonts: &FontCollection, text: &str, format: &TextFormat) -> VuiResult<Self> {
+        let width_of = |text: &str| -> VuiResult<i32> { Ok(fonts.size_of(text)?.width) };
+        let (lines, truncated) = layout_lines(text, format, width_of)?;
+
+        let widths = lines
+            .iter()
+            .map(|line| width_of(&line.text))
+            .collect::<VuiResult<Vec<_>>>()?;
+        let natural_width = widths.iter().copied().max().unwrap_or(0);
+        let width = format.width.map_or(natural_width, |w| natural_width.min(w));
+        let height = fonts.height();
+        let line_spacing = fonts.line_spacing();
+
+        let mut runs = Vec::new();
+        for (index, (line, line_width)) in lines.iter().zip(widths).enumerate() {
+            let y = index as i32 * line_spacing;
+            if format.alignment == Alignment::Justify && line.justifiable {
+                let words: Vec<_> = line.text.split(' ').filter(|w| !w.is_empty()).collect();
+                if words.len() > 1 {
+                    justify(&words, width, y, height, width_of, &mut runs)?;
+                    continue;
+                }
+            }
+            if line.text.is_empty() {
+                continue;
+            }
+
+            let x = match format.alignment {
+                Alignment::Left | Alignment::Justify => 0,
+                Alignment::Center => (width - line_width) / 2,
+                Alignment::Right => width - line_width,
+            };
+            runs.push(GlyphRun {
+                text: line.text.clone(),
+                position: Point::new(x, y),
+                size: Size::new(line_width, height),
+            });
+        }
+
+        let line_count = lines.len();
+        let size = Size::new(
+            width,
+            (line_count as i32 - 1).max(0) * line_spacing + height,
+        );
+        Ok(Self {
+            runs,
+            line_count,
+            size,
+            truncated,
+        })
+    }
+
+    /// The pieces of text to render, which are on a line each unless lines are justified.
+    pub fn runs(&self) -> &[GlyphRun] {
+        &self.runs
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// The size the text takes up. The width doesn't exceed the width of the format.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Whether lines were truncated or dropped to respect the format.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+/// Splits `text` into the lines of a layout in `format`, and reports whether lines were truncated
+/// or dropped.
+fn layout_lines<F>(text: &str, format: &TextFormat, width_of: F) -> VuiResult<(Vec<Line>, bool)>
+where
+    F: Fn(&str) -> VuiResult<i32>,
+{
+    let mut lines = Vec::new();
+    let mut truncated = false;
+    for paragraph in text.split('\n') {
+        match format.width {
+            Some(width) if format.wrap => {
+                let rows = break_line(paragraph, width, &width_of)?;
+                let last = rows.len() - 1;
+                lines.extend(rows.into_iter().enumerate().map(|(index, row)| Line {
+                    text: paragraph[row].trim_end_matches(' ').to_string(),
+                    justifiable: index < last,
+                }));
+            }
+            Some(width) if width_of(paragraph)? > width => {
+                truncated = true;
+                let text = if format.ellipsis {
+                    ellipsize(paragraph, Some(width), &width_of)?
+                } else {
+                    paragraph.to_string()
+                };
+                lines.push(Line {
+                    text,
+                    justifiable: false,
+                });
+            }
+            _ => lines.push(Line {
+                text: paragraph.to_string(),
+                justifiable: false,
+            }),
+        }
+    }
+
+    if let Some(max_lines) = format.max_lines.filter(|&max| lines.len() > max) {
+        lines.truncate(max_lines);
+        truncated = true;
+        if format.ellipsis {
+            let last = lines.last_mut().expect("at least one line is kept");
+            last.text = ellipsize(&last.text, format.width, &width_of)?;
+            last.justifiable = false;
+        }
+    }
+
+    Ok((lines, truncated))
+}
+
+/// Splits a line of text into rows that fit `width`, measuring text with `width_of`.
+///
+/// Rows are broken after spaces where possible, and the spaces at the end of a row may extend
+/// beyond the width. A word that doesn't fit a row on its own is broken between characters. The
+/// returned ranges cover the whole line, and there's at least one of them.
+pub fn break_line<F>(line: &str, width: i32, width_of: F) -> VuiResult<Vec<Range<usize>>>
+where
+    F: Fn(&str) -> VuiResult<i32>,
+{
+    let width = width.max(1);
+    let mut rows = Vec::new();
+    let mut start = 0;
+    while let Some(len) = first_row_len(&line[start..], width, &width_of)? {
+        rows.push(start..start + len);
+        start += len;
+    }
+    rows.push(start..line.len());
+    Ok(rows)
+}
+
+/// The length of the first row of `text`, or `None` if all of it fits `width`. Only the text up
+/// to the word that doesn't fit is measured.
+fn first_row_len<F>(text: &str, width: i32, width_of: &F) -> VuiResult<Option<usize>>
+where
+    F: Fn(&str) -> VuiResult<i32>,
+{
+    let end = text.trim_end_matches(' ').len();
+    let word_ends = text[..end]
+        .match_indices(' ')
+        .map(|(i, _)| i)
+        .filter(|&i| i > 0)
+        .chain([end]);
+    let mut len = None;
+    for word_end in word_ends {
+        if width_of(&text[..word_end])? > width {
+            break;
+        }
+        if word_end == end {
+            return Ok(None);
+        }
+        len = Some(word_end + 1);
+    }
+    if len.is_some() {
+        return Ok(len);
+    }
+
+    // The first word doesn't fit, so it is broken, leaving at least one character per row.
+    let mut boundaries = text[..end].char_indices().map(|(i, _)| i).skip(1);
+    let mut len = boundaries.next().unwrap_or(end);
+    for boundary in boundaries {
+        if width_of(&text[..boundary])? > width {
+            break;
+        }
+        len = boundary;
+    }
+    // A single character that doesn't fit, along with the spaces after it, ends the line.
+    Ok((len < end).then_some(len))
+}
+
+/// Shortens `text` so that it fits `width` with an ellipsis appended. Only the ellipsis remains
+/// if not even a single character fits.
+fn ellipsize<F>(text: &str, width: Option<i32>, width_of: F) -> VuiResult<String>
+where
+    F: Fn(&str) -> VuiResult<i32>,
+{
+    let shortened = |end: usize| format!("{}{ELLIPSIS}", text[..end].trim_end_matches(' '));
+    let Some(width) = width else {
+        return Ok(shortened(text.len()));
+    };
+
+    // Widths grow with the length of the text, so the longest prefix that fits is bisected.
+    let boundaries: Vec<_> = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([text.len()])
+        .collect();
+    let (mut low, mut high) = (0, boundaries.len() - 1);
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if width_of(&shortened(boundaries[mid]))? <= width {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Ok(shortened(boundaries[low]))
+}
+
+/// Adds a run for each of `words`, distributing the space left in `width` between them.
+fn justify<F>(
+    words: &[&str],
+    width: i32,
+    y: i32,
+    height: i32,
+    width_of: F,
+    runs: &mut Vec<GlyphRun>,
+) -> VuiResult<()>
+where
+    F: Fn(&str) -> VuiResult<i32>,
+{
+    let widths = words
+        .iter()
+        .map(|word| width_of(word))
+        .collect::<VuiResult<Vec<_>>>()?;
+    let gaps = words.len() as i32 - 1;
+    let space = (width - widths.iter().sum::<i32>()).max(0);
+    let mut x = 0;
+    for (index, (word, word_width)) in words.iter().zip(widths).enumerate() {
+        runs.push(GlyphRun {
+            text: word.to_string(),
+            position: Point::new(x, y),
+            size: Size::new(word_width, height),
+        });
+        // The first gaps take the pixels that can't be distributed evenly.
+        let extra = i32::from((index as i32) < space % gaps);
+        x += word_width + space / gaps + extra;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Measures every character as 10 pixels wide.
+    fn width_of(text: &str) -> VuiResult<i32> {
+        Ok(text.chars().count() as i32 * 10)
+    }
+
+    fn texts(lines: &[Line]) -> Vec<&str> {
+        lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_break_line() {
+        assert_eq!(
+            vec![0..8, 8..11],
+            break_line("aaa bbb ccc", 70, width_of).unwrap()
+        );
+        assert_eq!(
+            vec![0..11],
+            break_line("aaa bbb ccc", 110, width_of).unwrap()
+        );
+        assert_eq!(vec![0..6], break_line("aaa   ", 30, width_of).unwrap());
+        assert_eq!(vec![0..0], break_line("", 30, width_of).unwrap());
+
+        // Words that don't fit a row on their own are broken between characters.
+        assert_eq!(
+            vec![0..3, 3..6, 6..9, 9..11],
+            break_line("ab abcdefgh", 30, width_of).unwrap()
+        );
+        assert_eq!(vec![0..1, 1..3], break_line("ab ", 5, width_of).unwrap());
+    }
+
+    #[test]
+    fn test_ellipsize() {
+        assert_eq!(
+            "abcd\u{2026}",
+            ellipsize("abcdefgh", Some(50), width_of).unwrap()
+        );
+        assert_eq!(
+            "ab\u{2026}",
+            ellipsize("ab cdefg", Some(40), width_of).unwrap()
+        );
+        assert_eq!("\u{2026}", ellipsize("abc", Some(5), width_of).unwrap());
+        assert_eq!("abc\u{2026}", ellipsize("abc", None, width_of).unwrap());
+    }
+
+    #[test]
+    fn test_justify() {
+        let mut runs = Vec::new();
+        justify(&["a", "b", "c", "d"], 62, 5, 12, width_of, &mut runs).unwrap();
+
+        // The 22 pixels left are split into gaps of 7, and the first gap takes the remaining one.
+        let xs: Vec<_> = runs.iter().map(|run| run.position.x).collect();
+        assert_eq!(vec![0, 18, 35, 52], xs);
+        assert!(runs
+            .iter()
+            .all(|run| run.position.y == 5 && run.size == Size::new(10, 12)));
+    }
+
+    #[test]
+    fn test_max_lines() {
+        let text = "aaa bbb ccc ddd eee";
+        let format = TextFormat::new()
+            .with_width(70)
+            .with_wrap(true)
+            .with_max_lines(2);
+        let (lines, truncated) = layout_lines(text, &format, width_of).unwrap();
+        assert_eq!(vec!["aaa bbb", "ccc ddd"], texts(&lines));
+        assert!(truncated);
+        assert!(lines[1].justifiable);
+
+        let (lines, truncated) = layout_lines(text, &format.with_ellipsis(), width_of).unwrap();
+        assert_eq!(vec!["aaa bbb", "ccc dd\u{2026}"], texts(&lines));
+        assert!(truncated);
+        assert!(!lines[1].justifiable);
+
+        let format = TextFormat::new().with_width(50).with_ellipsis();
+        let (lines, truncated) = layout_lines("abcdefgh\nab", &format, width_of).unwrap();
+        assert_eq!(vec!["abcd\u{2026}", "ab"], texts(&lines));
+        assert!(truncated);
+    }
+}
@@ -0,0 +1,460 @@
+use crate::theme::FontCollection;
+use amulet_core::geom::{Point, Rect, Size};
+use amulet_core::VuiResult;
+use sdl2::pixels::Color;
+use sdl2::ttf::FontStyle;
+
+/// How a [`Span`] of [`RichText`] looks. By default, text is rendered with the regular fonts of
+/// the theme in its foreground color.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TextStyle {
+    font_style: FontStyle,
+    color: Option<Color>,
+    size: Option<u16>,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            font_style: FontStyle::NORMAL,
+            color: None,
+            size: None,
+        }
+    }
+}
+
+impl TextStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_bold(self) -> Self {
+        self.with_font_style(FontStyle::BOLD)
+    }
+
+    pub fn with_italic(self) -> Self {
+        self.with_font_style(FontStyle::ITALIC)
+    }
+
+    pub fn with_underline(self) -> Self {
+        self.with_font_style(FontStyle::UNDERLINE)
+    }
+
+    pub fn with_strikethrough(self) -> Self {
+        self.with_font_style(FontStyle::STRIKETHROUGH)
+    }
+
+    pub fn with_color(self, color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+
+    /// Renders the text in `size` points rather than the point size of the theme.
+    pub fn with_size(self, size: u16) -> Self {
+        Self {
+            size: Some(size.max(1)),
+            ..self
+        }
+    }
+
+    fn with_font_style(self, font_style: FontStyle) -> Self {
+        Self {
+            font_style: self.font_style | font_style,
+            ..self
+        }
+    }
+
+    pub fn font_style(&self) -> FontStyle {
+        self.font_style
+    }
+
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
+
+    pub fn size(&self) -> Option<u16> {
+        self.size
+    }
+}
+
+/// A piece of [`RichText`] in a single style.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub style: TextStyle,
+    /// What the span refers to if it is a link, like a URL.
+    pub link: Option<String>,
+}
+
+/// Text that consists of spans in different styles, some of which may be links.
+///
+/// Line breaks may occur within spans and don't end them.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RichText {
+    spans: Vec<Span>,
+}
+
+impl RichText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_span(mut self, text: &str, style: TextStyle) -> Self {
+        self.spans.push(Span {
+            text: text.to_string(),
+            style,
+            link: None,
+        });
+        self
+    }
+
+    /// Appends a span that reports clicks with `target`.
+    pub fn with_link(mut self, text: &str, style: TextStyle, target: &str) -> Self {
+        self.spans.push(Span {
+            text: text.to_string(),
+            style,
+            link: Some(target.to_string()),
+        });
+        self
+    }
+
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// The text without its styles.
+    pub fn plain_text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+}
+
+/// A piece of a [`RichTextLayout`] that is rendered in one go.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StyledRun {
+    pub text: String,
+    /// The index of the span the run belongs to.
+    pub span: usize,
+    /// The top left corner of the run relative to the layout. Runs of different sizes have
+    /// different positions, so that the baselines of their fonts line up.
+    pub position: Point,
+    pub size: Size,
+}
+
+/// A run that hasn't been positioned yet.
+struct Piece {
+    text: String,
+    span: usize,
+    width: i32,
+}
+
+/// The vertical metrics of the fonts of a span.
+#[derive(Debug, Copy, Clone)]
+struct FontMetrics {
+    ascent: i32,
+    height: i32,
+    line_spacing: i32,
+}
+
+impl FontMetrics {
+    fn of(fonts: &FontCollection) -> Self {
+        Self {
+            ascent: fonts.ascent(),
+            height: fonts.height(),
+            line_spacing: fonts.line_spacing(),
+        }
+    }
+}
+
+/// Rich text that was measured and arranged on lines, ready to be rendered.
+///
+/// The runs of a line share its baseline, which is low enough for the tallest font on the line.
+/// Lines end at line breaks in the text and, if a width is given, at a space before the next word
+/// that wouldn't fit. A word that is wider than the width on its own isn't broken.
+#[derive(Debug, Clone)]
+pub struct RichTextLayout {
+    runs: Vec<StyledRun>,
+    line_count: usize,
+    size: Size,
+}
+
+impl RichTextLayout {
+    /// Lays out `text`, measuring every span with its entry in `fonts`.
+    pub fn new(text: &RichText, fonts: &[&FontCollection], width: Option<i32>) -> VuiResult<Self> {
+        let metrics: Vec<_> = fonts.iter().map(|fonts| FontMetrics::of(fonts)).collect();
+        Self::measured(text, &metrics, width, |span, text| {
+            Ok(fonts[span].size_of(text)?.width)
+        })
+    }
+
+    /// Lays out `text` with the font metrics of each span, measuring the text of a span with
+    /// `width_of`.
+    fn measured<F>(
+        text: &RichText,
+        metrics: &[FontMetrics],
+        width: Option<i32>,
+        width_of: F,
+    ) -> VuiResult<Self>
+    where
+        F: Fn(usize, &str) -> VuiResult<i32>,
+    {
+        // Every line starts out with the span that is current there, which determines the height
+        // of empty lines.
+        let mut lines: Vec<(usize, Vec<Piece>)> = vec![(0, Vec::new())];
+        for (span, part) in text.spans.iter().enumerate() {
+            for (index, paragraph) in part.text.split('\n').enumerate() {
+                if index > 0 {
+                    lines.push((span, Vec::new()));
+                }
+                for word in paragraph.split_inclusive(' ') {
+                    let (_, pieces) = lines.last_mut().expect("there's always a line");
+                    let x: i32 = pieces.iter().map(|piece| piece.width).sum();
+                    let word_width = width_of(span, word.trim_end_matches(' '))?;
+                    if width.is_some_and(|w| x + word_width > w) {
+                        // Without a space in between, the word continues the one before it,
+                        // which has to move to the next line along with it.
+                        let tail = match pieces.last() {
+                            Some(last) if !last.text.ends_with(' ') => {
+                                split_off_word(pieces, &width_of)?
+                            }
+                            _ => Vec::new(),
+                        };
+                        if !pieces.is_empty() {
+                            let first_span = tail.first().map_or(span, |piece| piece.span);
+                            lines.push((first_span, tail));
+                        } else {
+                            *pieces = tail;
+                        }
+                    }
+
+                    let (_, pieces) = lines.last_mut().expect("there's always a line");
+                    match pieces.last_mut() {
+                        Some(piece) if piece.span == span => {
+                            piece.text.push_str(word);
+                            piece.width = width_of(span, &piece.text)?;
+                        }
+                        _ => pieces.push(Piece {
+                            text: word.to_string(),
+                            span,
+                            width: width_of(span, word)?,
+                        }),
+                    }
+                }
+            }
+        }
+
+        let mut runs = Vec::new();
+        let mut layout_width = 0;
+        let mut y = 0;
+        let mut height = 0;
+        for (first_span, pieces) in &lines {
+            let line_metrics: Vec<FontMetrics> = if pieces.is_empty() {
+                metrics.get(*first_span).copied().into_iter().collect()
+            } else {
+                pieces.iter().map(|piece| metrics[piece.span]).collect()
+            };
+            let ascent = line_metrics.iter().map(|m| m.ascent).max().unwrap_or(0);
+            let descent = line_metrics
+                .iter()
+                .map(|m| m.height - m.ascent)
+                .max()
+                .unwrap_or(0);
+            let gap = line_metrics
+                .iter()
+                .map(|m| m.line_spacing - m.height)
+                .max()
+                .unwrap_or(0)
+                .max(0);
+
+            let mut x = 0;
+            for piece in pieces {
+                let font = metrics[piece.span];
+                runs.push(StyledRun {
+                    text: piece.text.clone(),
+                    span: piece.span,
+                    position: Point::new(x, y + ascent - font.ascent),
+                    size: Size::new(piece.width, font.height),
+                });
+                x += piece.width;
+            }
+            // The spaces at the end of a line don't take up room.
+            if let Some(last) = pieces.last() {
+                let trailing = last.width - width_of(last.span, last.text.trim_end_matches(' '))?;
+                layout_width = layout_width.max(x - trailing);
+            }
+            height = y + ascent + descent;
+            y = height + gap;
+        }
+
+        Ok(Self {
+            runs,
+            line_count: lines.len(),
+            size: Size::new(layout_width, height),
+        })
+    }
+
+    pub fn runs(&self) -> &[StyledRun] {
+        &self.runs
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// The size the text takes up, which may exceed the width it was laid out for if a word
+    /// doesn't fit on a line of its own.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The areas that the runs of the spans with links take up, along with their spans.
+    pub fn link_areas<'t>(&self, text: &'t RichText) -> Vec<(Rect, &'t str)> {
+        self.runs
+            .iter()
+            .filter_map(|run| {
+                let link = text.spans[run.span].link.as_deref()?;
+                Some((Rect::new(run.position, run.size), link))
+            })
+            .collect()
+    }
+}
+
+/// Removes the word at the end of a line from `pieces`, which may span several of them, and
+/// returns it.
+fn split_off_word<F>(pieces: &mut Vec<Piece>, width_of: &F) -> VuiResult<Vec<Piece>>
+where
+    F: Fn(usize, &str) -> VuiResult<i32>,
+{
+    let mut word = Vec::new();
+    while let Some(mut piece) = pieces.pop() {
+        let Some(space) = piece.text.rfind(' ') else {
+            word.push(piece);
+            continue;
+        };
+        let text = piece.text.split_off(space + 1);
+        if !text.is_empty() {
+            word.push(Piece {
+                width: width_of(piece.span, &text)?,
+                text,
+                span: piece.span,
+            });
+            piece.width = width_of(piece.span, &piece.text)?;
+        }
+        pieces.push(piece);
+        break;
+    }
+    word.reverse();
+    Ok(word)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SMALL: FontMetrics = FontMetrics {
+        ascent: 8,
+        height: 10,
+        line_spacing: 12,
+    };
+    const LARGE: FontMetrics = FontMetrics {
+        ascent: 16,
+        height: 20,
+        line_spacing: 24,
+    };
+
+    /// Lays out `text` measuring every character as 10 pixels wide.
+    fn lay_out(text: &RichText, metrics: &[FontMetrics], width: Option<i32>) -> RichTextLayout {
+        RichTextLayout::measured(text, metrics, width, |_, text| {
+            Ok(text.chars().count() as i32 * 10)
+        })
+        .unwrap()
+    }
+
+    fn runs(layout: &RichTextLayout) -> Vec<(&str, usize, Point)> {
+        layout
+            .runs()
+            .iter()
+            .map(|run| (run.text.as_str(), run.span, run.position))
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap() {
+        let text = RichText::new()
+            .with_span("Some ", TextStyle::new())
+            .with_span("Bold", TextStyle::new().with_bold())
+            .with_span(", text", TextStyle::new());
+        let layout = lay_out(&text, &[SMALL; 3], Some(95));
+
+        // The comma doesn't fit, so the word it is part of moves to the next line with it.
+        assert_eq!(
+            vec![
+                ("Some ", 0, Point::new(0, 0)),
+                ("Bold", 1, Point::new(0, 12)),
+                (", ", 2, Point::new(40, 12)),
+                ("text", 2, Point::new(0, 24)),
+            ],
+            runs(&layout)
+        );
+        assert_eq!(3, layout.line_count());
+        assert_eq!(Size::new(50, 34), layout.size());
+    }
+
+    #[test]
+    fn test_wrap_long_word() {
+        let text = RichText::new()
+            .with_span("Bold", TextStyle::new().with_bold())
+            .with_span(", text", TextStyle::new());
+        let layout = lay_out(&text, &[SMALL; 2], Some(45));
+
+        // A word that doesn't fit on a line of its own stays in one piece.
+        assert_eq!(
+            vec![
+                ("Bold", 0, Point::new(0, 0)),
+                (", ", 1, Point::new(40, 0)),
+                ("text", 1, Point::new(0, 12)),
+            ],
+            runs(&layout)
+        );
+        assert_eq!(Size::new(50, 22), layout.size());
+    }
+
+    #[test]
+    fn test_baselines() {
+        let text = RichText::new()
+            .with_span("ab ", TextStyle::new())
+            .with_span("CD\nef", TextStyle::new().with_size(28));
+        let layout = lay_out(&text, &[SMALL, LARGE], None);
+
+        // The baseline is 16 pixels down on the first line, which the smaller run is moved to.
+        assert_eq!(
+            vec![
+                ("ab ", 0, Point::new(0, 8)),
+                ("CD", 1, Point::new(30, 0)),
+                ("ef", 1, Point::new(0, 24)),
+            ],
+            runs(&layout)
+        );
+        assert_eq!(Size::new(50, 44), layout.size());
+
+        // Empty lines are as high as the span they are in.
+        let text = RichText::new().with_span("\n", TextStyle::new());
+        let layout = lay_out(&text, &[SMALL], None);
+        assert_eq!(2, layout.line_count());
+        assert_eq!(Size::new(0, 22), layout.size());
+    }
+
+    #[test]
+    fn test_link_areas() {
+        let text = RichText::new()
+            .with_span("see ", TextStyle::new())
+            .with_link("docs", TextStyle::new().with_underline(), "docs-url")
+            .with_span(" and ", TextStyle::new());
+        let layout = lay_out(&text, &[SMALL; 3], None);
+        assert_eq!(
+            vec![(Rect::from_xywh(40, 0, 40, 10), "docs-url")],
+            layout.link_areas(&text)
+        );
+    }
+}
@@ -1,32 +1,59 @@
-use crate::widget::{Button, DynText, Image, TextInput};
-use amulet_core::component::{Frame, MouseSensor, Position, SizeAttr};
-use amulet_core::geom::{Rect, Size};
+use crate::text::{RichText, RichTextLayout, TextFormat, TextLayout};
+use crate::widget::{
+    parse_mnemonic, Button, ComboBox, ComboBoxOption, ContextMenu, Dialog, DialogButton, DynText,
+    Image, ListRow, ListView, ListViewImages, Menu, MenuBar, MenuEntry, MenuImages, MenuLabel,
+    MenuMetrics, MenuRow, MenuRowKind, MenuTitle, RichLabel, Scrollbar, Slider, Split, Tab, Table,
+    TableCell, TableColumn, TableHeader, TableImages, TableMetrics, Tabs, TabsImages, TabsMetrics,
+    TextArea, TextAreaImages, TextInput, Tooltip, TreeView, TreeViewImages, ValueRange,
+};
+use amulet_core::component::{Frame, Layout, MouseSensor, Position, SizeAttr};
+use amulet_core::geom::{Orientation, Point, Rect, Size, Vector};
+use amulet_core::overlay::Overlays;
+use amulet_core::timer::Timers;
 use amulet_core::{VuiError, VuiResult};
 use amulet_sdl2::lossy::LossyInto;
+use amulet_sdl2::render::RenderContext;
 use sdl2::pixels::{Color, PixelFormatEnum};
-use sdl2::render::{Canvas, RenderTarget, TextureCreator};
+use sdl2::render::{BlendMode, Canvas, RenderTarget, TextureCreator};
 use sdl2::surface::Surface;
-use sdl2::ttf::{Font, Sdl2TtfContext};
+use sdl2::ttf::{FontStyle, Sdl2TtfContext};
 use sdl2::video::WindowContext;
 use std::rc::Rc;
 
+mod fonts;
+
+pub use fonts::{FontCollection, FontConfig, FontRun, FontSource};
+
 pub struct Theme<'a> {
-    pub font: Font<'a, 'static>,
+    pub fonts: FontCollection<'a>,
     pub texture_creator: &'a TextureCreator<WindowContext>,
+    /// For loading the fonts in other styles and sizes.
+    ttf: &'a Sdl2TtfContext,
+    font_config: FontConfig,
 }
 
 impl<'a> Theme<'a> {
+    /// Creates a theme with the fonts of [`FontConfig::default()`].
     pub fn create(
         ttf: &'a Sdl2TtfContext,
         texture_creator: &'a TextureCreator<WindowContext>,
     ) -> VuiResult<Self> {
-        let font = ttf
-            .load_font("/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf", 14)
-            .map_err(|e| VuiError::new(format!("load_font() error: {e}")))?;
+        Self::create_with_fonts(ttf, texture_creator, &FontConfig::default())
+    }
+
+    pub fn create_with_fonts(
+        ttf: &'a Sdl2TtfContext,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        config: &FontConfig,
+    ) -> VuiResult<Self> {
+        let fonts = FontCollection::load(ttf, config)
+            .map_err(|e| VuiError::new(format!("font loading error: {e}")))?;
 
         Ok(Self {
-            font,
+            fonts,
             texture_creator,
+            ttf,
+            font_config: config.clone(),
         })
     }
 }
@@ -35,8 +62,32 @@ const PRIMARY_FG: Color = Color::RGB(0xbb, 0xbb, 0xbb);
 const PRIMARY_BG: Color = Color::RGB(0x4d, 0x51, 0x53);
 const TEXT_BG: Color = Color::RGB(0x45, 0x49, 0x4a);
 const PRIMARY_EDGE: Color = Color::RGB(0x5f, 0x61, 0x61);
+const THUMB_BG: Color = Color::RGB(0x6b, 0x6e, 0x70);
+const TOOLTIP_BG: Color = Color::RGB(0x5c, 0x5c, 0x42);
+const DISABLED_FG: Color = Color::RGB(0x77, 0x77, 0x77);
+const LINK_FG: Color = Color::RGB(0x58, 0x9d, 0xf6);
+const FOCUS_EDGE: Color = Color::RGB(0x46, 0x8a, 0xc8);
+const BACKDROP: Color = Color::RGBA(0, 0, 0, 0x80);
 const PADDING_H: i32 = 5;
 const PADDING_V: i32 = 3;
+const SLIDER_THICKNESS: i32 = 16;
+const SLIDER_THUMB_LENGTH: i32 = 10;
+const SCROLLBAR_THICKNESS: i32 = 12;
+const SCROLLBAR_MIN_THUMB_LENGTH: i32 = 16;
+const SCROLLBAR_STEP: i32 = 20;
+const COMBO_BOX_ARROW_WIDTH: i32 = 16;
+const COMBO_BOX_VISIBLE_ROWS: i32 = 8;
+const MENU_GLYPH_SIZE: i32 = 9;
+const MENU_GUTTER: i32 = 20;
+const MENU_ACCELERATOR_GAP: i32 = 24;
+const MENU_BAR_PADDING_H: i32 = 8;
+const DIALOG_PADDING: i32 = 12;
+const DIALOG_BUTTON_SPACING: i32 = 8;
+const DIALOG_MIN_BUTTON_WIDTH: i32 = 64;
+const TREE_VIEW_INDENT: i32 = 16;
+const TAB_CLOSE_WIDTH: i32 = 16;
+const TAB_SCROLL_BUTTON_WIDTH: i32 = 16;
+const SPLIT_HANDLE_THICKNESS: i32 = 6;
 
 trait CanvasExt {
     fn draw_border(&mut self, rect: Rect) -> VuiResult<()>;
@@ -69,34 +120,154 @@ where
 }
 
 impl Theme<'_> {
-    pub fn label(&self, text: &str) -> VuiResult<Image> {
-        let surf = self.font.render(text).blended(PRIMARY_FG)?;
+    /// Creates a bordered rectangle of the provided size and background color.
+    fn panel(&self, size: Size, background: Color) -> VuiResult<Image<'_>> {
+        let surface = Self::panel_surface(size, background)?;
+        Ok(Image::new(
+            Rc::new(surface.as_texture(self.texture_creator)?),
+            size,
+        ))
+    }
+
+    fn panel_surface(size: Size, background: Color) -> VuiResult<Surface<'static>> {
+        let surface = Surface::new(
+            size.width.lossy_into(),
+            size.height.lossy_into(),
+            PixelFormatEnum::RGB888,
+        )?;
+
+        let mut canvas = surface.into_canvas()?;
+        canvas.set_draw_color(background);
+        canvas.clear();
+
+        canvas.set_draw_color(PRIMARY_EDGE);
+        canvas.draw_border(Rect::from_size(size).inflate(-1, -1))?;
+
+        Ok(canvas.into_surface())
+    }
+
+    pub fn label(&self, text: &str) -> VuiResult<Image<'_>> {
+        let surf = self.fonts.render(text, PRIMARY_FG)?;
         let size: (i32, i32) = surf.size().lossy_into();
         let texture = Rc::new(surf.as_texture(self.texture_creator)?);
         Ok(Image::new(texture, size.into()))
     }
 
-    pub fn button<'a>(&'a self, content: (Frame, Position, Image<'a>)) -> VuiResult<Button<'a>> {
-        let (content_frame, content_pos, content_img) = content;
-        let content_size = content_frame.size();
-        let button_size = content_size + Size::new(PADDING_H * 2, PADDING_V * 2);
-        let surface = Surface::new(
-            button_size.width.lossy_into(),
-            button_size.height.lossy_into(),
-            PixelFormatEnum::RGB888,
+    /// Measures `text` and arranges it on lines according to `format`, without rendering it.
+    pub fn layout_text(&self, text: &str, format: &TextFormat) -> VuiResult<TextLayout> {
+        TextLayout::new(&self.fonts, text, format)
+    }
+
+    /// Creates a label of text that is arranged on lines according to `format`. The label has the
+    /// size of the [`TextLayout`].
+    pub fn formatted_label(&self, text: &str, format: &TextFormat) -> VuiResult<Image<'_>> {
+        let layout = self.layout_text(text, format)?;
+        let size = layout.size();
+        let mut surface = Surface::new(
+            size.width.max(1).lossy_into(),
+            size.height.max(1).lossy_into(),
+            PixelFormatEnum::ARGB8888,
         )?;
+        surface.fill_rect(None, Color::RGBA(0, 0, 0, 0))?;
+        for run in layout.runs() {
+            let mut glyphs = self.fonts.render(&run.text, PRIMARY_FG)?;
+            // Copies the glyphs including their transparency rather than blending them onto the
+            // transparent surface.
+            glyphs.set_blend_mode(BlendMode::None)?;
+            let (w, h) = glyphs.size();
+            glyphs.blit(
+                None,
+                &mut surface,
+                sdl2::rect::Rect::new(run.position.x, run.position.y, w, h),
+            )?;
+        }
+
+        let texture = surface.as_texture(self.texture_creator)?;
+        Ok(Image::new(Rc::new(texture), size))
+    }
 
-        let mut bg_canvas = surface.into_canvas()?;
-        bg_canvas.set_draw_color(PRIMARY_BG);
-        bg_canvas.clear();
+    /// Creates a label of rich text, which is wrapped at `width` if one is given. Spans without a
+    /// color are rendered in the foreground color, except for links, which are underlined and
+    /// rendered in the link color.
+    pub fn rich_label(&self, text: &RichText, width: Option<i32>) -> VuiResult<RichLabel<'_>> {
+        let styles: Vec<_> = text
+            .spans()
+            .iter()
+            .map(|span| match &span.link {
+                Some(_) => span.style.with_underline(),
+                None => span.style,
+            })
+            .collect();
 
-        bg_canvas.set_draw_color(PRIMARY_EDGE);
-        bg_canvas.draw_border(Rect::from_size(button_size).inflate(-1, -1))?;
+        // Spans in the regular style use the fonts of the theme, and other spans of the same size
+        // and font style share their fonts.
+        let regular = (self.font_config.point_size(), FontStyle::NORMAL);
+        let mut keys = Vec::new();
+        let mut collections = Vec::new();
+        let mut span_fonts = Vec::new();
+        for style in &styles {
+            let point_size = style.size().unwrap_or(self.font_config.point_size());
+            let key = (point_size, style.font_style());
+            let index = match keys.iter().position(|k| *k == key) {
+                _ if key == regular => None,
+                Some(index) => Some(index),
+                None => {
+                    let config = self.font_config.clone().with_point_size(point_size);
+                    let mut fonts = FontCollection::load(self.ttf, &config)?;
+                    fonts.set_style(style.font_style());
+                    keys.push(key);
+                    collections.push(fonts);
+                    Some(collections.len() - 1)
+                }
+            };
+            span_fonts.push(index);
+        }
+        let fonts: Vec<_> = span_fonts
+            .iter()
+            .map(|index| index.map_or(&self.fonts, |index| &collections[index]))
+            .collect();
 
-        let bg_image = Image::new(
-            Rc::new(bg_canvas.into_surface().as_texture(self.texture_creator)?),
-            button_size,
-        );
+        let layout = RichTextLayout::new(text, &fonts, width)?;
+        let size = layout.size();
+        let mut surface = Surface::new(
+            size.width.max(1).lossy_into(),
+            size.height.max(1).lossy_into(),
+            PixelFormatEnum::ARGB8888,
+        )?;
+        surface.fill_rect(None, Color::RGBA(0, 0, 0, 0))?;
+        for run in layout.runs() {
+            let span = &text.spans()[run.span];
+            let color = match (styles[run.span].color(), &span.link) {
+                (Some(color), _) => color,
+                (None, Some(_)) => LINK_FG,
+                (None, None) => PRIMARY_FG,
+            };
+            let mut glyphs = fonts[run.span].render(&run.text, color)?;
+            glyphs.set_blend_mode(BlendMode::None)?;
+            let (w, h) = glyphs.size();
+            glyphs.blit(
+                None,
+                &mut surface,
+                sdl2::rect::Rect::new(run.position.x, run.position.y, w, h),
+            )?;
+        }
+
+        let texture = surface.as_texture(self.texture_creator)?;
+        let image = Image::new(Rc::new(texture), size);
+        let links = layout
+            .link_areas(text)
+            .into_iter()
+            .map(|(area, target)| (area, target.to_string()))
+            .collect();
+        let outer = (Frame::new(size), MouseSensor::new());
+        Ok(RichLabel::new(outer, image, links))
+    }
+
+    pub fn button<'a>(&'a self, content: (Frame, Position, Image<'a>)) -> VuiResult<Button<'a>> {
+        let (content_frame, content_pos, content_img) = content;
+        let content_size = content_frame.size();
+        let button_size = content_size + Size::new(PADDING_H * 2, PADDING_V * 2);
+        let bg_image = self.panel(button_size, PRIMARY_BG)?;
 
         let outer = (Frame::new(button_size), MouseSensor::new());
         let inner = (
@@ -108,36 +279,770 @@ impl Theme<'_> {
         Ok(Button::new(outer, inner, bg_image, content_img))
     }
 
-    pub fn text_input<'a>(&'a self, content: (Frame, Position, DynText<'a>)) -> VuiResult<TextInput<'a>> {
+    pub fn text_input<'a>(
+        &'a self,
+        content: (Frame, Position, DynText<'a>),
+    ) -> VuiResult<TextInput<'a>> {
         let (content_frame, content_pos, content_img) = content;
         let content_size = content_frame.size();
         let button_size = content_size + Size::new(PADDING_H * 2, PADDING_V * 2);
+        let bg_image = self.panel(button_size, TEXT_BG)?;
+
+        let outer = (Frame::new(button_size), MouseSensor::new());
+        let inner = (
+            Position::new((PADDING_H, PADDING_V).into()),
+            content_frame,
+            content_pos,
+        );
+
+        let caret = self.fill(Size::new(1, content_size.height), PRIMARY_FG)?;
+
+        Ok(TextInput::new(outer, inner, bg_image, content_img, caret))
+    }
+
+    /// Creates a slider of the provided length (along `orientation`) for the provided range.
+    pub fn slider(
+        &self,
+        orientation: Orientation,
+        length: i32,
+        range: ValueRange,
+    ) -> VuiResult<Slider<'_>> {
+        let size = orientation.size(length, SLIDER_THICKNESS);
+        let track = self.panel(size, TEXT_BG)?;
+        let thumb = self.panel(
+            orientation.size(SLIDER_THUMB_LENGTH, SLIDER_THICKNESS),
+            THUMB_BG,
+        )?;
+        let outer = (Frame::new(size), MouseSensor::new());
+
+        Ok(Slider::new(outer, orientation, range, track, thumb))
+    }
+
+    /// Creates a scrollbar of the provided length (along `orientation`) for content of
+    /// `content_length` that is shown in a viewport of `viewport_length`.
+    pub fn scrollbar(
+        &self,
+        orientation: Orientation,
+        length: i32,
+        content_length: i32,
+        viewport_length: i32,
+    ) -> VuiResult<Scrollbar<'_>> {
+        self.scrollbar_with_step(
+            orientation,
+            length,
+            content_length,
+            viewport_length,
+            SCROLLBAR_STEP,
+        )
+    }
+
+    fn scrollbar_with_step(
+        &self,
+        orientation: Orientation,
+        length: i32,
+        content_length: i32,
+        viewport_length: i32,
+        step: i32,
+    ) -> VuiResult<Scrollbar<'_>> {
+        let size = orientation.size(length, SCROLLBAR_THICKNESS);
+        let track = self.panel(size, TEXT_BG)?;
+
+        let thumb_length = Scrollbar::thumb_length_for(
+            length,
+            content_length,
+            viewport_length,
+            SCROLLBAR_MIN_THUMB_LENGTH,
+        );
+        let thumb = self.panel(
+            orientation.size(thumb_length, SCROLLBAR_THICKNESS),
+            THUMB_BG,
+        )?;
+
+        let outer = (Frame::new(size), MouseSensor::new());
+        let range = Scrollbar::range_for(content_length, viewport_length).with_step(step);
+
+        Ok(Scrollbar::new(
+            outer,
+            orientation,
+            range,
+            track,
+            thumb,
+            SCROLLBAR_MIN_THUMB_LENGTH,
+        ))
+    }
+
+    /// Creates a tooltip showing `text` while the pointer dwells over an area of `size`.
+    pub fn tooltip(&self, text: &str, size: Size, timers: Timers) -> VuiResult<Tooltip<'_>> {
+        let text_surface = self.fonts.render(text, PRIMARY_FG)?;
+        let text_size: (i32, i32) = text_surface.size().lossy_into();
+        let tooltip_size = Size::from(text_size) + Size::new(PADDING_H * 2, PADDING_V * 2);
+
+        let mut surface = Self::panel_surface(tooltip_size, TOOLTIP_BG)?;
+        text_surface.blit(
+            None,
+            &mut surface,
+            sdl2::rect::Rect::new(
+                PADDING_H,
+                PADDING_V,
+                text_surface.width(),
+                text_surface.height(),
+            ),
+        )?;
+        let content = Image::new(
+            Rc::new(surface.as_texture(self.texture_creator)?),
+            tooltip_size,
+        );
+
+        let outer = (Frame::new(size), MouseSensor::new());
+        Ok(Tooltip::new(outer, content, timers))
+    }
+
+    /// Creates a combo box of the provided width for selecting one of `options`. Its popup list
+    /// shows up to eight options at a time.
+    pub fn combo_box(
+        &self,
+        width: i32,
+        options: &[&str],
+        overlays: Overlays,
+    ) -> VuiResult<ComboBox<'_>> {
+        let row_height = self.fonts.height() + PADDING_V * 2;
+        let options = options
+            .iter()
+            .map(|text| Ok(ComboBoxOption::new(text.to_string(), self.label(text)?)))
+            .collect::<VuiResult<Vec<_>>>()?;
+
+        let size = Size::new(width, row_height);
+        let field = {
+            let mut canvas = Self::panel_surface(size, TEXT_BG)?.into_canvas()?;
+            canvas.set_draw_color(PRIMARY_FG);
+            let center_x = width - COMBO_BOX_ARROW_WIDTH / 2;
+            let top = row_height / 2 - 2;
+            for i in 0..4 {
+                canvas.draw_line(
+                    sdl2::rect::Point::new(center_x - 3 + i, top + i),
+                    sdl2::rect::Point::new(center_x + 3 - i, top + i),
+                )?;
+            }
+            let texture = canvas.into_surface().as_texture(self.texture_creator)?;
+            Image::new(Rc::new(texture), size)
+        };
+
+        let rows = (options.len() as i32).clamp(1, COMBO_BOX_VISIBLE_ROWS);
+        let popup = self.panel(Size::new(width, rows * row_height), PRIMARY_BG)?;
+        let highlight = self.panel(size, THUMB_BG)?;
+        let outer = (Frame::new(size), MouseSensor::new());
+
+        Ok(ComboBox::new(
+            outer,
+            options,
+            field,
+            popup,
+            highlight,
+            Vector::new(PADDING_H, PADDING_V),
+            overlays,
+        ))
+    }
+
+    /// Renders `label` with its mnemonic (see [`MenuEntry`]) underlined.
+    fn mnemonic_label(&self, label: &str, color: Color) -> VuiResult<(Image<'_>, Option<char>)> {
+        let (text, mnemonic) = parse_mnemonic(label);
+        let mut surface = self.fonts.render(&text, color)?;
+        if let Some((start, end)) = mnemonic {
+            let x0 = self.fonts.size_of(&text[..start])?.width;
+            let x1 = self.fonts.size_of(&text[..end])?.width;
+            let y = self.fonts.ascent() + 1;
+            surface.fill_rect(
+                sdl2::rect::Rect::new(x0, y, (x1 - x0).lossy_into(), 1),
+                color,
+            )?;
+        }
+
+        let size: (i32, i32) = surface.size().lossy_into();
+        let image = Image::new(
+            Rc::new(surface.as_texture(self.texture_creator)?),
+            size.into(),
+        );
+        let mnemonic = mnemonic.and_then(|(start, _)| text[start..].chars().next());
+        Ok((image, mnemonic.map(|chr| chr.to_ascii_lowercase())))
+    }
+
+    fn menu_label(&self, label: &str) -> VuiResult<(MenuLabel<'_>, Option<char>)> {
+        let (normal, mnemonic) = self.mnemonic_label(label, PRIMARY_FG)?;
+        let (dimmed, _) = self.mnemonic_label(label, DISABLED_FG)?;
+        Ok((MenuLabel { normal, dimmed }, mnemonic))
+    }
+
+    /// Draws a small glyph from line segments onto a transparent background.
+    fn glyph(&self, lines: &[(i32, i32, i32, i32)]) -> VuiResult<Image<'_>> {
+        let size = Size::new(MENU_GLYPH_SIZE, MENU_GLYPH_SIZE);
+        let surface = Surface::new(
+            size.width.lossy_into(),
+            size.height.lossy_into(),
+            PixelFormatEnum::ARGB8888,
+        )?;
+        let mut canvas = surface.into_canvas()?;
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+        canvas.clear();
+        canvas.set_draw_color(PRIMARY_FG);
+        for &(x0, y0, x1, y1) in lines {
+            canvas.draw_line(
+                sdl2::rect::Point::new(x0, y0),
+                sdl2::rect::Point::new(x1, y1),
+            )?;
+        }
+
+        let texture = canvas.into_surface().as_texture(self.texture_creator)?;
+        Ok(Image::new(Rc::new(texture), size))
+    }
+
+    fn fill(&self, size: Size, color: Color) -> VuiResult<Image<'_>> {
         let surface = Surface::new(
-            button_size.width.lossy_into(),
-            button_size.height.lossy_into(),
+            size.width.max(1).lossy_into(),
+            size.height.max(1).lossy_into(),
             PixelFormatEnum::RGB888,
         )?;
+        let mut canvas = surface.into_canvas()?;
+        canvas.set_draw_color(color);
+        canvas.clear();
+        let texture = canvas.into_surface().as_texture(self.texture_creator)?;
+        Ok(Image::new(Rc::new(texture), size))
+    }
 
-        let mut bg_canvas = surface.into_canvas()?;
-        bg_canvas.set_draw_color(TEXT_BG);
-        bg_canvas.clear();
+    /// Creates a popup menu with the provided entries.
+    pub fn menu(&self, entries: &[MenuEntry], overlays: Overlays) -> VuiResult<Menu<'_>> {
+        let check_mark = self.glyph(&[(1, 4, 3, 6), (1, 5, 3, 7), (3, 6, 7, 2), (3, 7, 7, 3)])?;
+        let submenu_arrow = self.glyph(&[(2, 0, 6, 4), (2, 8, 6, 4), (2, 1, 2, 7)])?;
+        self.build_menu(entries, &check_mark, &submenu_arrow, overlays)
+    }
 
-        bg_canvas.set_draw_color(PRIMARY_EDGE);
-        bg_canvas.draw_border(Rect::from_size(button_size).inflate(-1, -1))?;
+    fn build_menu<'t>(
+        &'t self,
+        entries: &[MenuEntry],
+        check_mark: &Image<'t>,
+        submenu_arrow: &Image<'t>,
+        overlays: Overlays,
+    ) -> VuiResult<Menu<'t>> {
+        let row_height = self.fonts.height() + PADDING_V * 2;
+        let separator_height = PADDING_V * 2 + 1;
 
-        let bg_image = Image::new(
-            Rc::new(bg_canvas.into_surface().as_texture(self.texture_creator)?),
-            button_size,
+        let mut kinds = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let kind = match entry {
+                MenuEntry::Item(item) => {
+                    let (label, mnemonic) = self.menu_label(&item.label)?;
+                    let accelerator = match &item.accelerator {
+                        Some(accelerator) => Some(self.menu_label(accelerator)?.0),
+                        None => None,
+                    };
+                    let kind = MenuRowKind::Item {
+                        action: item.action,
+                        checkable: item.checkable,
+                        label,
+                        accelerator,
+                    };
+                    (kind, mnemonic)
+                }
+                MenuEntry::Submenu(label, entries) => {
+                    let (label, mnemonic) = self.menu_label(label)?;
+                    let menu =
+                        self.build_menu(entries, check_mark, submenu_arrow, overlays.clone())?;
+                    let kind = MenuRowKind::Submenu {
+                        label,
+                        menu: Box::new(menu),
+                    };
+                    (kind, mnemonic)
+                }
+                MenuEntry::Separator => (MenuRowKind::Separator, None),
+            };
+            kinds.push(kind);
+        }
+
+        let (label_width, accelerator_width) = kinds.iter().fold(
+            (0, 0),
+            |(label_width, accelerator_width), (kind, _)| match kind {
+                MenuRowKind::Item {
+                    label, accelerator, ..
+                } => (
+                    label_width.max(label.normal.size().width),
+                    accelerator_width.max(
+                        accelerator
+                            .as_ref()
+                            .map_or(0, |accelerator| accelerator.normal.size().width),
+                    ),
+                ),
+                MenuRowKind::Submenu { label, .. } => (
+                    label_width.max(label.normal.size().width),
+                    accelerator_width,
+                ),
+                MenuRowKind::Separator => (label_width, accelerator_width),
+            },
         );
 
-        let outer = (Frame::new(button_size), MouseSensor::new());
-        let inner = (
-            Position::new((PADDING_H, PADDING_V).into()),
-            content_frame,
-            content_pos,
+        let glyph_y = (row_height - MENU_GLYPH_SIZE) / 2;
+        let accelerator_x = MENU_GUTTER
+            + label_width
+            + if accelerator_width > 0 {
+                MENU_ACCELERATOR_GAP
+            } else {
+                0
+            };
+        let metrics = MenuMetrics {
+            check_mark: Vector::new(PADDING_H, glyph_y),
+            label: Vector::new(MENU_GUTTER, PADDING_V),
+            accelerator: Vector::new(accelerator_x, PADDING_V),
+            submenu_arrow: Vector::new(accelerator_x + accelerator_width + PADDING_H, glyph_y),
+            separator: Vector::new(PADDING_H, PADDING_V),
+        };
+        let row_width = metrics.submenu_arrow.x + MENU_GLYPH_SIZE + PADDING_H;
+
+        // Leave room for the border.
+        let mut y = 1;
+        let mut rows = Vec::with_capacity(kinds.len());
+        for (kind, mnemonic) in kinds {
+            let height = match kind {
+                MenuRowKind::Separator => separator_height,
+                _ => row_height,
+            };
+            rows.push(MenuRow {
+                kind,
+                mnemonic,
+                rect: Rect::from_xywh(1, y, row_width, height),
+            });
+            y += height;
+        }
+
+        let size = Size::new(row_width + 2, y + 1);
+        let images = MenuImages {
+            background: self.panel(size, PRIMARY_BG)?,
+            highlight: self.fill(Size::new(row_width, row_height), THUMB_BG)?,
+            check_mark: check_mark.clone(),
+            submenu_arrow: submenu_arrow.clone(),
+            separator: self.fill(Size::new(row_width - PADDING_H * 2, 1), PRIMARY_EDGE)?,
+        };
+
+        Ok(Menu::new(rows, images, metrics, size, overlays))
+    }
+
+    /// Creates a menu bar of the provided width with a menu for every pair of title and entries.
+    pub fn menu_bar(
+        &self,
+        width: i32,
+        menus: &[(&str, Vec<MenuEntry>)],
+        overlays: Overlays,
+    ) -> VuiResult<MenuBar<'_>> {
+        let height = self.fonts.height() + PADDING_V * 2;
+        let mut x = 0;
+        let mut titles = Vec::with_capacity(menus.len());
+        for (title, entries) in menus {
+            let (label, mnemonic) = self.mnemonic_label(title, PRIMARY_FG)?;
+            let size = Size::new(label.size().width + MENU_BAR_PADDING_H * 2, height);
+            titles.push(MenuTitle {
+                label,
+                highlight: self.fill(size, THUMB_BG)?,
+                mnemonic,
+                rect: Rect::new((x, 0).into(), size),
+                menu: self.menu(entries, overlays.clone())?,
+            });
+            x += size.width;
+        }
+
+        let size = Size::new(width, height);
+        let background = self.fill(size, PRIMARY_BG)?;
+        let outer = (Frame::new(size), MouseSensor::new());
+        let padding = Vector::new(MENU_BAR_PADDING_H, PADDING_V);
+
+        Ok(MenuBar::new(outer, background, titles, padding))
+    }
+
+    /// Creates a context menu for an area of the provided size.
+    pub fn context_menu(
+        &self,
+        size: Size,
+        entries: &[MenuEntry],
+        overlays: Overlays,
+    ) -> VuiResult<ContextMenu<'_>> {
+        let outer = (Frame::new(size), MouseSensor::new());
+        Ok(ContextMenu::new(outer, self.menu(entries, overlays)?))
+    }
+
+    /// Creates a modal dialog with a title, the provided content and a row of buttons labeled
+    /// `buttons`. The first button is the default button and the last one the cancel button.
+    pub fn dialog<'a, C>(
+        &'a self,
+        title: &str,
+        content: C,
+        buttons: &[&str],
+        overlays: Overlays,
+    ) -> VuiResult<Dialog<'a, C>>
+    where
+        C: SizeAttr,
+    {
+        let title = self.label(title)?;
+        let title_bar_height = title.size().height + DIALOG_PADDING;
+        let content_origin = Point::new(DIALOG_PADDING, title_bar_height + DIALOG_PADDING);
+        let buttons_y = content_origin.y + content.size().height + DIALOG_PADDING;
+
+        let labels = buttons
+            .iter()
+            .map(|text| self.label(text))
+            .collect::<VuiResult<Vec<_>>>()?;
+        let label_size = labels
+            .iter()
+            .fold(Size::new(DIALOG_MIN_BUTTON_WIDTH, 0), |size, label| {
+                size.max(label.size())
+            });
+        let mut buttons = Vec::with_capacity(labels.len());
+        for label in labels {
+            let position = Position::new(Point::new(
+                (label_size.width - label.size().width) / 2,
+                (label_size.height - label.size().height) / 2,
+            ));
+            buttons.push(self.button((Frame::new(label_size), position, label))?);
+        }
+
+        let button_size = buttons.first().map_or(Size::zero(), |button| button.size());
+        let count = i32::try_from(buttons.len())?;
+        let row_width = (button_size.width + DIALOG_BUTTON_SPACING) * count - DIALOG_BUTTON_SPACING;
+        let width =
+            title.size().width.max(content.size().width).max(row_width) + DIALOG_PADDING * 2;
+        let height = buttons_y + button_size.height + DIALOG_PADDING;
+        let size = Size::new(width, height);
+
+        // The button row is aligned to the right.
+        let mut x = width - DIALOG_PADDING - row_width;
+        let mut dialog_buttons = Vec::with_capacity(buttons.len());
+        for button in buttons {
+            let origin = Point::new(x, buttons_y);
+            let ring_size = button_size + Size::new(4, 4);
+            dialog_buttons.push(DialogButton::new(
+                (Position::new(origin), button),
+                (
+                    Position::new(origin - Vector::new(2, 2)),
+                    self.focus_ring(ring_size)?,
+                ),
+            ));
+            x += button_size.width + DIALOG_BUTTON_SPACING;
+        }
+
+        let mut canvas = Self::panel_surface(size, PRIMARY_BG)?.into_canvas()?;
+        canvas.set_draw_color(PRIMARY_EDGE);
+        canvas.draw_line(
+            sdl2::rect::Point::new(1, title_bar_height),
+            sdl2::rect::Point::new(width - 2, title_bar_height),
+        )?;
+        let panel = Image::new(
+            Rc::new(canvas.into_surface().as_texture(self.texture_creator)?),
+            size,
         );
 
-        Ok(TextInput::new(outer, inner, bg_image, content_img))
+        let cancel_button = dialog_buttons.len().checked_sub(1);
+        let dialog = Dialog::new(
+            self.backdrop()?,
+            panel,
+            (
+                Position::new(Point::new(DIALOG_PADDING, DIALOG_PADDING / 2)),
+                title,
+            ),
+            (Position::new(content_origin), content),
+            dialog_buttons,
+            overlays,
+        );
+        Ok(match cancel_button {
+            Some(index) => dialog.with_cancel_button(index),
+            None => dialog,
+        })
     }
 
+    /// A border drawn around focused components.
+    fn focus_ring(&self, size: Size) -> VuiResult<Image<'_>> {
+        let surface = Surface::new(
+            size.width.lossy_into(),
+            size.height.lossy_into(),
+            PixelFormatEnum::ARGB8888,
+        )?;
+        let mut canvas = surface.into_canvas()?;
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+        canvas.clear();
+        canvas.set_draw_color(FOCUS_EDGE);
+        canvas.draw_border(Rect::from_size(size).inflate(-1, -1))?;
+
+        let texture = canvas.into_surface().as_texture(self.texture_creator)?;
+        Ok(Image::new(Rc::new(texture), size))
+    }
+
+    /// A translucent image to be stretched over the window behind modal overlays.
+    fn backdrop(&self) -> VuiResult<Image<'_>> {
+        let surface = Surface::new(1, 1, PixelFormatEnum::ARGB8888)?;
+        let mut canvas = surface.into_canvas()?;
+        canvas.set_draw_color(BACKDROP);
+        canvas.clear();
+
+        let texture = canvas.into_surface().as_texture(self.texture_creator)?;
+        Ok(Image::new(Rc::new(texture), Size::new(1, 1)))
+    }
+
+    /// Creates a list view of the provided size with `row_count` rows of `row_height`, whose
+    /// content is drawn by `render_row`.
+    pub fn list_view<'a>(
+        &'a self,
+        size: Size,
+        row_count: usize,
+        row_height: i32,
+        render_row: impl Fn(ListRow, Layout, &mut RenderContext) -> VuiResult<()> + 'a,
+    ) -> VuiResult<ListView<'a>> {
+        // Inside the border, left of the scrollbar.
+        let rows_rect =
+            Rect::from_xywh(1, 1, size.width - SCROLLBAR_THICKNESS - 1, size.height - 2);
+        let visible_rows = (rows_rect.size.height / row_height.max(1)).max(1);
+        let scrollbar = (
+            Position::new(Point::new(size.width - SCROLLBAR_THICKNESS, 0)),
+            self.scrollbar_with_step(
+                Orientation::Vertical,
+                size.height,
+                i32::try_from(row_count)?,
+                visible_rows,
+                1,
+            )?,
+        );
+
+        let row_size = Size::new(rows_rect.size.width, row_height);
+        let images = ListViewImages {
+            background: self.panel(size, TEXT_BG)?,
+            selection: self.fill(row_size, THUMB_BG)?,
+            focus_ring: self.focus_ring(row_size)?,
+        };
+        let padding = Vector::new(PADDING_H, ((row_height - self.fonts.height()) / 2).max(0));
+        let outer = (Frame::new(size), MouseSensor::new());
+
+        Ok(ListView::new(
+            outer,
+            scrollbar,
+            images,
+            rows_rect,
+            padding,
+            row_count,
+            Box::new(render_row),
+        ))
+    }
+
+    /// Creates a table of the provided size with the provided columns and `row_count` rows of
+    /// `row_height`, whose cells are drawn by `render_cell`. The header is as high as a row.
+    pub fn table<'a>(
+        &'a self,
+        size: Size,
+        columns: &[TableColumn],
+        row_count: usize,
+        row_height: i32,
+        render_cell: impl Fn(TableCell, Layout, &mut RenderContext) -> VuiResult<()> + 'a,
+    ) -> VuiResult<Table<'a>> {
+        // Below the header and inside the border, left of the scrollbar.
+        let rows_rect = Rect::from_xywh(
+            1,
+            row_height,
+            size.width - SCROLLBAR_THICKNESS - 1,
+            size.height - row_height - 1,
+        );
+        let visible_rows = (rows_rect.size.height / row_height.max(1)).max(1);
+        let scrollbar = (
+            Position::new(Point::new(size.width - SCROLLBAR_THICKNESS, row_height)),
+            self.scrollbar_with_step(
+                Orientation::Vertical,
+                size.height - row_height,
+                i32::try_from(row_count)?,
+                visible_rows,
+                1,
+            )?,
+        );
+
+        let headers = columns
+            .iter()
+            .map(|column| {
+                Ok(TableHeader {
+                    label: self.label(&column.title)?,
+                    width: column.width,
+                    sortable: column.sortable,
+                    editable: column.editable,
+                })
+            })
+            .collect::<VuiResult<Vec<_>>>()?;
+        let row_size = Size::new(rows_rect.size.width, row_height);
+        let images = TableImages {
+            background: self.panel(size, TEXT_BG)?,
+            header: self.panel(Size::new(size.width, row_height), PRIMARY_BG)?,
+            header_separator: self.fill(Size::new(1, row_height), PRIMARY_EDGE)?,
+            sort_ascending: self.glyph(&[
+                (4, 2, 4, 2),
+                (3, 3, 5, 3),
+                (2, 4, 6, 4),
+                (1, 5, 7, 5),
+            ])?,
+            sort_descending: self.glyph(&[
+                (1, 3, 7, 3),
+                (2, 4, 6, 4),
+                (3, 5, 5, 5),
+                (4, 6, 4, 6),
+            ])?,
+            selection: self.fill(row_size, THUMB_BG)?,
+            cell_edge_h: self.fill(Size::new(row_size.width, 1), FOCUS_EDGE)?,
+            cell_edge_v: self.fill(Size::new(1, row_height), FOCUS_EDGE)?,
+        };
+
+        // The editor covers a whole row, and is clipped to the cell being edited.
+        let editor_content = (
+            Frame::new(row_size - Size::new(PADDING_H * 2, PADDING_V * 2)),
+            Position::new(Point::zero()),
+            DynText::new(self),
+        );
+        let editor = self.text_input(editor_content)?;
+        let metrics = TableMetrics {
+            size,
+            rows_rect,
+            padding: Vector::new(PADDING_H, ((row_height - self.fonts.height()) / 2).max(0)),
+        };
+
+        Ok(Table::new(
+            headers,
+            images,
+            editor,
+            metrics,
+            scrollbar,
+            row_count,
+            Box::new(render_cell),
+        ))
+    }
+
+    /// Creates a tree view of the provided size with rows of `row_height`.
+    pub fn tree_view(&self, size: Size, row_height: i32) -> VuiResult<TreeView<'_>> {
+        // Inside the border, left of the scrollbar.
+        let rows_rect =
+            Rect::from_xywh(1, 1, size.width - SCROLLBAR_THICKNESS - 1, size.height - 2);
+        let visible_rows = (rows_rect.size.height / row_height.max(1)).max(1);
+        // Resized by the tree view as the number of visible nodes changes.
+        let scrollbar = (
+            Position::new(Point::new(size.width - SCROLLBAR_THICKNESS, 0)),
+            self.scrollbar_with_step(Orientation::Vertical, size.height, 0, visible_rows, 1)?,
+        );
+
+        let row_size = Size::new(rows_rect.size.width, row_height);
+        let images = TreeViewImages {
+            background: self.panel(size, TEXT_BG)?,
+            selection: self.fill(row_size, THUMB_BG)?,
+            focus_ring: self.focus_ring(row_size)?,
+            expand_arrow: self.glyph(&[(2, 0, 6, 4), (2, 8, 6, 4), (2, 1, 2, 7)])?,
+            collapse_arrow: self.glyph(&[(0, 2, 4, 6), (8, 2, 4, 6), (1, 2, 7, 2)])?,
+        };
+        let padding = Vector::new(0, ((row_height - self.fonts.height()) / 2).max(0));
+        let outer = (Frame::new(size), MouseSensor::new());
+
+        Ok(TreeView::new(
+            outer,
+            scrollbar,
+            images,
+            DynText::new(self),
+            rows_rect,
+            padding,
+            TREE_VIEW_INDENT,
+        ))
+    }
+
+    /// Creates a tab container of the provided size with a titled tab for each page, which can
+    /// be closed if `closable` is set. The pages are laid out below the tab strip, see
+    /// [`Tabs::page_size()`] for the space available to them.
+    pub fn tabs<'t, P>(
+        &'t self,
+        size: Size,
+        pages: Vec<(&str, P)>,
+        closable: bool,
+    ) -> VuiResult<Tabs<'t, P>> {
+        let strip_height = self.fonts.height() + PADDING_V * 2;
+        let close_width = if closable { TAB_CLOSE_WIDTH } else { 0 };
+        let (titles, pages): (Vec<_>, Vec<_>) = pages.into_iter().unzip();
+        let tabs = titles
+            .into_iter()
+            .map(|title| {
+                let label = self.label(title)?;
+                let tab_size = Size::new(
+                    label.size().width + PADDING_H * 2 + close_width,
+                    strip_height,
+                );
+                Ok(Tab {
+                    label,
+                    normal: self.panel(tab_size, PRIMARY_BG)?,
+                    active: self.panel(tab_size, TEXT_BG)?,
+                })
+            })
+            .collect::<VuiResult<Vec<_>>>()?;
+
+        let images = TabsImages {
+            strip: self.fill(Size::new(size.width, strip_height), PRIMARY_EDGE)?,
+            page: self.panel(Size::new(size.width, size.height - strip_height), TEXT_BG)?,
+            close: self.glyph(&[(1, 1, 7, 7), (1, 7, 7, 1)])?,
+            scroll_button: self
+                .panel(Size::new(TAB_SCROLL_BUTTON_WIDTH, strip_height), PRIMARY_BG)?,
+            scroll_back: self.glyph(&[(6, 0, 2, 4), (6, 8, 2, 4), (6, 1, 6, 7)])?,
+            scroll_forward: self.glyph(&[(2, 0, 6, 4), (2, 8, 6, 4), (2, 1, 2, 7)])?,
+        };
+        let metrics = TabsMetrics {
+            size,
+            strip_height,
+            label: Vector::new(PADDING_H, PADDING_V),
+            close_width,
+            scroll_button_width: TAB_SCROLL_BUTTON_WIDTH,
+            page_padding: Vector::new(PADDING_H, PADDING_H),
+        };
+
+        Ok(Tabs::new(tabs, pages, images, metrics))
+    }
+
+    /// Creates a split of the provided size that places `first` and `second` next to each other
+    /// along `orientation`.
+    pub fn split<A, B>(
+        &self,
+        orientation: Orientation,
+        size: Size,
+        first: A,
+        second: B,
+    ) -> VuiResult<Split<'_, A, B>> {
+        let handle = self.panel(
+            orientation.size(SPLIT_HANDLE_THICKNESS, orientation.breadth(size)),
+            PRIMARY_BG,
+        )?;
+        let outer = (Frame::new(size), MouseSensor::new());
+
+        Ok(Split::new(outer, orientation, handle, first, second))
+    }
+
+    /// Creates a multi-line text area of the provided size, which wraps its text and shows a
+    /// vertical scrollbar.
+    pub fn text_area(&self, size: Size) -> VuiResult<TextArea<'_>> {
+        // Inside the padding, left of the scrollbar.
+        let text_rect = Rect::from_xywh(
+            PADDING_H,
+            PADDING_V,
+            size.width - SCROLLBAR_THICKNESS - PADDING_H * 2,
+            size.height - PADDING_V * 2,
+        );
+        let row_height = self.fonts.height();
+        let visible_rows = (text_rect.size.height / row_height.max(1)).max(1);
+        // Resized by the text area as the number of rows changes.
+        let scrollbar = (
+            Position::new(Point::new(size.width - SCROLLBAR_THICKNESS, 0)),
+            self.scrollbar_with_step(Orientation::Vertical, size.height, 0, visible_rows, 1)?,
+        );
+
+        let images = TextAreaImages {
+            background: self.panel(size, TEXT_BG)?,
+            selection: self.fill(Size::new(1, row_height), THUMB_BG)?,
+            caret: self.fill(Size::new(1, row_height), PRIMARY_FG)?,
+        };
+        let outer = (Frame::new(size), MouseSensor::new());
+
+        Ok(TextArea::new(
+            outer,
+            scrollbar,
+            images,
+            DynText::new(self),
+            text_rect,
+        ))
+    }
 }
@@ -0,0 +1,246 @@
+use amulet_core::geom::Size;
+use amulet_core::text::bidi;
+use amulet_core::{VuiError, VuiResult};
+use amulet_sdl2::lossy::LossyInto;
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::render::BlendMode;
+use sdl2::rwops::RWops;
+use sdl2::surface::Surface;
+use sdl2::ttf::{Font, FontStyle, Sdl2TtfContext};
+use std::ops::Range;
+use std::path::PathBuf;
+
+const DEFAULT_POINT_SIZE: u16 = 14;
+
+/// The font of [`FontConfig::default()`], which is shipped with the crate.
+const DEFAULT_FONT: &[u8] = include_bytes!("../../assets/font/MuseoModerno-Regular.ttf");
+
+#[derive(Debug, Clone)]
+pub enum FontSource {
+    File(PathBuf),
+    /// Font data that is part of the application, e.g. through `include_bytes!()`.
+    Memory(&'static [u8]),
+}
+
+impl FontSource {
+    fn load<'a>(&self, ttf: &'a Sdl2TtfContext, point_size: u16) -> VuiResult<Font<'a, 'static>> {
+        let font = match self {
+            Self::File(path) => ttf.load_font(path, point_size)?,
+            Self::Memory(data) => ttf.load_font_from_rwops(RWops::from_bytes(data)?, point_size)?,
+        };
+        Ok(font)
+    }
+}
+
+/// The fonts a [`FontCollection`] is loaded from.
+#[derive(Debug, Clone)]
+pub struct FontConfig {
+    sources: Vec<FontSource>,
+    point_size: u16,
+}
+
+impl Default for FontConfig {
+    /// MuseoModerno, which covers Latin scripts only. Append fonts with
+    /// [`FontConfig::with_font()`] for other scripts.
+    fn default() -> Self {
+        Self::new(DEFAULT_POINT_SIZE).with_font(FontSource::Memory(DEFAULT_FONT))
+    }
+}
+
+impl FontConfig {
+    /// Creates a configuration without any fonts.
+    pub fn new(point_size: u16) -> Self {
+        Self {
+            sources: Vec::new(),
+            point_size,
+        }
+    }
+
+    /// Appends a font, which is used for the characters that the fonts before it lack.
+    pub fn with_font(mut self, source: FontSource) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    pub fn with_point_size(self, point_size: u16) -> Self {
+        Self { point_size, ..self }
+    }
+
+    pub fn sources(&self) -> &[FontSource] {
+        &self.sources
+    }
+
+    pub fn point_size(&self) -> u16 {
+        self.point_size
+    }
+}
+
+/// A part of a text that is rendered with a single font of a [`FontCollection`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FontRun {
+    /// The index of the font in the collection.
+    pub font: usize,
+    pub range: Range<usize>,
+}
+
+/// An ordered chain of fonts, which renders every character with the first font that has a glyph
+/// for it.
+///
+/// The first font is the primary font, which determines the metrics of the text. Glyphs of other
+/// fonts are placed on its baseline. Characters that no font has are rendered with the primary
+/// font.
+///
+/// SDL_ttf can only look up characters of the Basic Multilingual Plane, so the characters beyond
+/// it, like most emoji, are rendered with the last font, which should be the font of last resort.
+pub struct FontCollection<'a> {
+    /// There's at least one.
+    fonts: Vec<Font<'a, 'static>>,
+}
+
+impl<'a> FontCollection<'a> {
+    /// Fails if `fonts` is empty.
+    pub fn new(fonts: Vec<Font<'a, 'static>>) -> VuiResult<Self> {
+        if fonts.is_empty() {
+            return Err(
+                VuiError::new("a font collection needs at least one font".to_string()).into(),
+            );
+        }
+        Ok(Self { fonts })
+    }
+
+    /// Loads the fonts of `config`. Fonts that can't be loaded are skipped, so that the chain may
+    /// contain fonts that are only installed on some systems, but at least one has to be loaded.
+    pub fn load(ttf: &'a Sdl2TtfContext, config: &FontConfig) -> VuiResult<Self> {
+        let mut errors = Vec::new();
+        let mut fonts = Vec::new();
+        for source in &config.sources {
+            match source.load(ttf, config.point_size) {
+                Ok(font) => fonts.push(font),
+                Err(e) => errors.push(format!("{source:?}: {e}")),
+            }
+        }
+        if fonts.is_empty() {
+            let message = format!("none of the fonts could be loaded ({})", errors.join(", "));
+            return Err(VuiError::new(message).into());
+        }
+        Ok(Self { fonts })
+    }
+
+    pub fn primary(&self) -> &Font<'a, 'static> {
+        &self.fonts[0]
+    }
+
+    pub fn fonts(&self) -> &[Font<'a, 'static>] {
+        &self.fonts
+    }
+
+    /// Applies `style` to all the fonts of the collection.
+    pub fn set_style(&mut self, style: FontStyle) {
+        for font in &mut self.fonts {
+            font.set_style(style);
+        }
+    }
+
+    pub fn height(&self) -> i32 {
+        self.primary().height()
+    }
+
+    pub fn ascent(&self) -> i32 {
+        self.primary().ascent()
+    }
+
+    /// The distance between the baselines of consecutive lines.
+    pub fn line_spacing(&self) -> i32 {
+        self.primary().recommended_line_spacing()
+    }
+
+    /// The index of the font that renders `chr`.
+    pub fn font_for(&self, chr: char) -> usize {
+        if u32::from(chr) > 0xffff {
+            return self.fonts.len() - 1;
+        }
+        self.fonts
+            .iter()
+            .position(|font| font.find_glyph(chr).is_some())
+            .unwrap_or(0)
+    }
+
+    /// Splits `text` into the parts that are rendered with the same font.
+    pub fn runs(&self, text: &str) -> Vec<FontRun> {
+        let mut runs: Vec<FontRun> = Vec::new();
+        for (offset, chr) in text.char_indices() {
+            let end = offset + chr.len_utf8();
+            let font = match runs.last() {
+                // Keeps marks together with the characters they modify.
+                Some(run) if is_attached(chr) => run.font,
+                _ => self.font_for(chr),
+            };
+            match runs.last_mut() {
+                Some(run) if run.font == font => run.range.end = end,
+                _ => runs.push(FontRun {
+                    font,
+                    range: offset..end,
+                }),
+            }
+        }
+        runs
+    }
+
+    /// The size of `text` when rendered on a single line.
+    pub fn size_of(&self, text: &str) -> VuiResult<Size> {
+        let mut width = 0;
+        for run in self.runs(text) {
+            let (w, _) = self.fonts[run.font].size_of(&text[run.range])?;
+            width += LossyInto::<i32>::lossy_into(w);
+        }
+        Ok(Size::new(width, self.height()))
+    }
+
+    /// Renders `text` on a single line, reordering right-to-left runs for display. Fails for
+    /// empty text.
+    ///
+    /// SDL_ttf doesn't shape text, so Arabic letters are rendered in their isolated forms.
+    pub fn render(&self, text: &str, color: Color) -> VuiResult<Surface<'static>> {
+        let text = &bidi::reorder(text, None);
+        let runs = self.runs(text);
+        if runs.iter().all(|run| run.font == 0) {
+            return Ok(self.primary().render(text).blended(color)?);
+        }
+
+        let size = self.size_of(text)?;
+        let mut surface = Surface::new(
+            size.width.lossy_into(),
+            size.height.lossy_into(),
+            PixelFormatEnum::ARGB8888,
+        )?;
+        surface.fill_rect(None, Color::RGBA(0, 0, 0, 0))?;
+        let mut x = 0;
+        for run in runs {
+            let font = &self.fonts[run.font];
+            let mut glyphs = font.render(&text[run.range]).blended(color)?;
+            // Copies the glyphs including their transparency rather than blending them onto the
+            // transparent surface.
+            glyphs.set_blend_mode(BlendMode::None)?;
+            let (w, h) = glyphs.size();
+            let y = self.ascent() - font.ascent();
+            glyphs.blit(None, &mut surface, sdl2::rect::Rect::new(x, y, w, h))?;
+            x += LossyInto::<i32>::lossy_into(w);
+        }
+        Ok(surface)
+    }
+}
+
+/// Whether `chr` modifies the character before it, like combining accents, variation selectors
+/// and joiners.
+fn is_attached(chr: char) -> bool {
+    matches!(
+        u32::from(chr),
+        0x0300..=0x036f
+            | 0x1ab0..=0x1aff
+            | 0x1dc0..=0x1dff
+            | 0x200d
+            | 0x20d0..=0x20ff
+            | 0xfe00..=0xfe0f
+            | 0xfe20..=0xfe2f
+    )
+}
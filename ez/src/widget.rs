@@ -1,9 +1,45 @@
+mod anchor;
 mod button;
-mod text_input;
-mod image;
+mod combo_box;
+mod context_menu;
+mod dialog;
 mod dyn_text;
+mod image;
+mod list_view;
+mod menu;
+mod menu_bar;
+mod rich_label;
+mod row_scroll;
+mod scrollbar;
+mod slider;
+mod split;
+mod table;
+mod tabs;
+mod text_area;
+mod text_input;
+mod tooltip;
+mod tree_view;
 
 pub use button::{Button, ButtonState};
-pub use image::Image;
+pub use combo_box::{ComboBox, ComboBoxOption, ComboBoxState};
+pub use context_menu::{ContextMenu, ContextMenuState};
+pub use dialog::{Dialog, DialogButton, DialogState};
 pub use dyn_text::DynText;
-pub use text_input::{TextInput, TextInputState};
\ No newline at end of file
+pub use image::Image;
+pub use list_view::{ListRow, ListView, ListViewImages, ListViewState, RenderRow, SelectionMode};
+pub(crate) use menu::{parse_mnemonic, MenuImages, MenuLabel, MenuMetrics, MenuRow, MenuRowKind};
+pub use menu::{Menu, MenuEntry, MenuItem, MenuState};
+pub(crate) use menu_bar::MenuTitle;
+pub use menu_bar::{MenuBar, MenuBarState};
+pub use rich_label::{RichLabel, RichLabelState};
+pub use scrollbar::{Scrollbar, ScrollbarState};
+pub use slider::{Slider, SliderState, ValueRange};
+pub use split::{Split, SplitState};
+pub use table::{CellRef, RenderCell, SortOrder, Table, TableCell, TableColumn, TableState};
+pub(crate) use table::{TableHeader, TableImages, TableMetrics};
+pub(crate) use tabs::{Tab, TabsImages, TabsMetrics};
+pub use tabs::{Tabs, TabsState};
+pub use text_area::{TextArea, TextAreaImages, TextAreaState};
+pub use text_input::{TextInput, TextInputState};
+pub use tooltip::{Tooltip, TooltipState, DEFAULT_TOOLTIP_DELAY};
+pub use tree_view::{TreeModel, TreeView, TreeViewImages, TreeViewState};
@@ -0,0 +1,29 @@
+use amulet_core::component::Layout;
+use amulet_core::geom::{Direction, Rect};
+use std::cell::Cell;
+
+/// The area a widget was rendered in last, which the overlays it opens are placed against, along
+/// with the direction of its layout.
+///
+/// Keyboard events don't carry a position, so widgets that open a popup from the keyboard keep
+/// their anchor in their state and update it while rendering.
+#[derive(Debug, Default)]
+pub(crate) struct Anchor {
+    rect: Cell<Rect>,
+    direction: Cell<Direction>,
+}
+
+impl Anchor {
+    pub fn set(&self, layout: &Layout) {
+        self.rect.set(layout.clip_rect());
+        self.direction.set(layout.direction());
+    }
+
+    pub fn rect(&self) -> Rect {
+        self.rect.get()
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction.get()
+    }
+}
@@ -4,6 +4,7 @@ use amulet_core::component::{
     SizeAttr, UpdateLayout,
 };
 use amulet_core::geom::Size;
+use amulet_core::keyboard::Modifiers;
 use amulet_core::mouse::MouseButton;
 use amulet_core::VuiResult;
 use amulet_sdl2::render::{Render, RenderContext};
@@ -19,6 +20,17 @@ impl ButtonState {
             .click_states()
             .has_click_completed(MouseButton::Left)
     }
+
+    pub fn was_double_clicked(&self) -> bool {
+        self.mouse_sensor
+            .click_states()
+            .has_double_click_completed(MouseButton::Left)
+    }
+
+    /// The modifier keys that were held down during the last click.
+    pub fn modifiers(&self) -> Modifiers {
+        self.mouse_sensor.modifiers()
+    }
 }
 
 pub struct Button<'a> {
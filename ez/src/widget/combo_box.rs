@@ -0,0 +1,512 @@
+use crate::widget::anchor::Anchor;
+use crate::widget::{DynText, Image};
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, HandleEvent, Layout, MouseSensor, MouseSensorState, SizeAttr,
+    UpdateLayout,
+};
+use amulet_core::geom::{Size, Vector};
+use amulet_core::keyboard::{Key, Modifiers};
+use amulet_core::mouse::MouseButton;
+use amulet_core::overlay::{OverlayId, Overlays, Placement};
+use amulet_core::time::Timestamp;
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+use std::time::Duration;
+
+/// Keys typed within this interval form a single type-ahead search.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Default)]
+pub struct ComboBoxState {
+    mouse_sensor: MouseSensorState,
+    list_sensor: MouseSensorState,
+    /// The field, which the popup is opened below.
+    anchor: Anchor,
+    popup: Option<OverlayId>,
+    selected: Option<usize>,
+    /// Index into the matching options.
+    highlighted: usize,
+    first_row: usize,
+    text: String,
+    filtering: bool,
+    filter_changed: bool,
+    type_ahead: String,
+    last_type_ahead: Timestamp,
+    focused: bool,
+    changed: bool,
+}
+
+impl ComboBoxState {
+    pub fn new(selected: Option<usize>) -> Self {
+        Self {
+            selected,
+            ..Self::default()
+        }
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Selects the option at `selected` without triggering [`Self::selection_changed()`].
+    pub fn set_selected_index(&mut self, selected: Option<usize>) {
+        self.selected = selected;
+        self.filtering = false;
+    }
+
+    /// Whether the user picked another option during the current loop iteration.
+    pub fn selection_changed(&self) -> bool {
+        self.changed
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.popup.is_some()
+    }
+
+    /// Whether the combo box receives keyboard input. It gains focus when it is clicked and loses
+    /// it when a click happens elsewhere.
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The content of the text field of an editable combo box.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Feeds text input to an editable combo box, which filters the options by it. This has no
+    /// effect unless the combo box is focused.
+    pub fn input_text(&mut self, text: &str) {
+        if self.focused {
+            self.text.push_str(text);
+            self.filtering = true;
+            self.filter_changed = true;
+        }
+    }
+}
+
+pub struct ComboBoxOption<'a> {
+    text: String,
+    label: Image<'a>,
+}
+
+impl<'a> ComboBoxOption<'a> {
+    pub fn new(text: String, label: Image<'a>) -> Self {
+        Self { text, label }
+    }
+}
+
+/// Selects one of a list of options from a popup list.
+///
+/// The popup opens when the combo box is clicked, or with Alt+Down or F4 while it is focused.
+/// While it is closed, the arrow keys change the selection directly. The options can be searched by
+/// typing the start of their text.
+///
+/// An editable combo box (see [`ComboBox::editable()`]) shows a text field instead of the selected
+/// option, which is filled through [`ComboBoxState::input_text()`] and filters the options.
+pub struct ComboBox<'a> {
+    outer: (Frame, MouseSensor),
+    list: (Frame, MouseSensor),
+    options: Vec<ComboBoxOption<'a>>,
+    field: Image<'a>,
+    popup: Image<'a>,
+    highlight: Image<'a>,
+    padding: Vector,
+    editor: Option<DynText<'a>>,
+    overlays: Overlays,
+}
+
+impl SizeAttr for ComboBox<'_> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a> ComboBox<'a> {
+    /// The height of `highlight` determines the height of the rows in the popup, which shows as
+    /// many rows as fit into `popup`. Option labels are drawn at `padding` within their row.
+    pub fn new(
+        outer: (Frame, MouseSensor),
+        options: Vec<ComboBoxOption<'a>>,
+        field: Image<'a>,
+        popup: Image<'a>,
+        highlight: Image<'a>,
+        padding: Vector,
+        overlays: Overlays,
+    ) -> Self {
+        Self {
+            outer,
+            list: (Frame::new(popup.size()), MouseSensor::new()),
+            options,
+            field,
+            popup,
+            highlight,
+            padding,
+            editor: None,
+            overlays,
+        }
+    }
+
+    /// Makes the text of the combo box editable.
+    pub fn editable(self, editor: DynText<'a>) -> Self {
+        Self {
+            editor: Some(editor),
+            ..self
+        }
+    }
+
+    pub fn options(&self) -> impl Iterator<Item = &str> {
+        self.options.iter().map(|option| option.text.as_str())
+    }
+
+    fn row_height(&self) -> i32 {
+        self.highlight.size().height.max(1)
+    }
+
+    fn visible_rows(&self) -> usize {
+        (self.popup.size().height / self.row_height()).max(1) as usize
+    }
+
+    /// The indices of the options that match the text typed into an editable combo box.
+    fn matches(&self, state: &ComboBoxState) -> Vec<usize> {
+        let filter = state.text.to_lowercase();
+        (0..self.options.len())
+            .filter(|&index| {
+                !state.filtering || self.options[index].text.to_lowercase().contains(&filter)
+            })
+            .collect()
+    }
+
+    fn select(&self, state: &mut ComboBoxState, index: usize) {
+        if state.selected != Some(index) {
+            state.selected = Some(index);
+            state.changed = true;
+        }
+        state.filtering = false;
+    }
+
+    fn open(&self, state: &mut ComboBoxState, matches: &[usize]) {
+        if state.popup.is_some() {
+            return;
+        }
+
+        state.popup = Some(self.overlays.open_at(
+            state.anchor.rect(),
+            self.popup.size(),
+            Placement::Below,
+            state.anchor.direction(),
+        ));
+        state.list_sensor = MouseSensorState::default();
+        state.highlighted = state
+            .selected
+            .and_then(|selected| matches.iter().position(|&index| index == selected))
+            .unwrap_or(0);
+        state.first_row = 0;
+        self.scroll_into_view(state, matches.len());
+    }
+
+    fn close(&self, state: &mut ComboBoxState) {
+        if let Some(id) = state.popup.take() {
+            self.overlays.close(id);
+        }
+    }
+
+    fn scroll_into_view(&self, state: &mut ComboBoxState, match_count: usize) {
+        let rows = self.visible_rows();
+        if state.highlighted < state.first_row {
+            state.first_row = state.highlighted;
+        } else if state.highlighted >= state.first_row + rows {
+            state.first_row = state.highlighted + 1 - rows;
+        }
+        state.first_row = state.first_row.min(match_count.saturating_sub(rows));
+    }
+
+    /// Moves the highlight (or the selection, if the popup is closed) to the matching option at
+    /// `position`.
+    fn move_to(&self, state: &mut ComboBoxState, matches: &[usize], position: isize) {
+        if matches.is_empty() {
+            return;
+        }
+
+        let position = position.clamp(0, matches.len() as isize - 1) as usize;
+        if state.popup.is_some() {
+            state.highlighted = position;
+            self.scroll_into_view(state, matches.len());
+        } else {
+            self.select(state, matches[position]);
+        }
+    }
+
+    /// Finds the next matching option whose text starts with the characters typed in quick
+    /// succession, starting from `current`. Typing a single character repeatedly cycles through
+    /// the options starting with it.
+    fn type_ahead(
+        &self,
+        state: &mut ComboBoxState,
+        matches: &[usize],
+        chr: char,
+        current: usize,
+        timestamp: Timestamp,
+    ) -> Option<usize> {
+        if timestamp.duration_since(state.last_type_ahead) > TYPE_AHEAD_TIMEOUT {
+            state.type_ahead.clear();
+        }
+        state.last_type_ahead = timestamp;
+        state.type_ahead.extend(chr.to_lowercase());
+
+        let start = if state.type_ahead.chars().count() == 1 {
+            current + 1
+        } else {
+            current
+        };
+        (0..matches.len())
+            .map(|offset| (start + offset) % matches.len())
+            .find(|&position| {
+                self.options[matches[position]]
+                    .text
+                    .to_lowercase()
+                    .starts_with(&state.type_ahead)
+            })
+    }
+
+    fn handle_key(
+        &self,
+        state: &mut ComboBoxState,
+        matches: &[usize],
+        key: Key,
+        modifiers: Modifiers,
+        timestamp: Timestamp,
+    ) {
+        let current = if state.popup.is_some() {
+            state.highlighted
+        } else {
+            state
+                .selected
+                .and_then(|selected| matches.iter().position(|&index| index == selected))
+                .unwrap_or(0)
+        };
+        let page = self.visible_rows() as isize;
+
+        match key {
+            Key::Down if modifiers.alt() => self.open(state, matches),
+            Key::Function(4) => {
+                if state.popup.is_some() {
+                    self.close(state);
+                } else {
+                    self.open(state, matches);
+                }
+            }
+            Key::Up if modifiers.alt() => self.close(state),
+            Key::Up => self.move_to(state, matches, current as isize - 1),
+            Key::Down => {
+                // Without a selection, Down selects the first option.
+                let next = if state.selected.is_none() && state.popup.is_none() {
+                    0
+                } else {
+                    current as isize + 1
+                };
+                self.move_to(state, matches, next)
+            }
+            Key::PageUp => self.move_to(state, matches, current as isize - page),
+            Key::PageDown => self.move_to(state, matches, current as isize + page),
+            Key::Home if self.editor.is_none() => self.move_to(state, matches, 0),
+            Key::End if self.editor.is_none() => self.move_to(state, matches, isize::MAX),
+            Key::Enter => {
+                if state.popup.is_some() {
+                    if let Some(&index) = matches.get(state.highlighted) {
+                        self.select(state, index);
+                    }
+                    self.close(state);
+                } else {
+                    self.open(state, matches);
+                }
+            }
+            Key::Escape | Key::Tab => {
+                self.close(state);
+                state.filtering = false;
+            }
+            Key::Backspace if self.editor.is_some() => {
+                state.text.pop();
+                state.filtering = true;
+                state.filter_changed = true;
+            }
+            Key::Character(chr) if self.editor.is_none() && !modifiers.ctrl() => {
+                if let Some(position) = self.type_ahead(state, matches, chr, current, timestamp) {
+                    self.move_to(state, matches, position as isize);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_popup_event(
+        &self,
+        state: &mut ComboBoxState,
+        matches: &[usize],
+        id: OverlayId,
+        event: &ComponentEvent,
+    ) -> VuiResult<()> {
+        let Some(rect) = self.overlays.rect(id) else {
+            return Ok(());
+        };
+        let event = event.clone().enter_overlay(id, rect);
+        self.list
+            .as_chain()
+            .handle_event(((), &mut state.list_sensor), event.clone())?;
+
+        match &event {
+            ComponentEvent::MouseWheel(amount, pos, ..) if pos.is_hit() => {
+                let max_first_row = matches.len().saturating_sub(self.visible_rows());
+                let first_row = state.first_row as isize - amount.y as isize;
+                state.first_row = first_row.clamp(0, max_first_row as isize) as usize;
+            }
+            ComponentEvent::MouseMotion(pos, ..) if pos.is_hit() => {
+                let row = pos.relative_position().y / self.row_height();
+                let position = state.first_row + row as usize;
+                if position < matches.len() {
+                    state.highlighted = position;
+                }
+            }
+            _ => {}
+        }
+
+        let clicks = state.list_sensor.click_states();
+        if matches!(event, ComponentEvent::MouseButtonUp(..))
+            && clicks.has_click_completed(MouseButton::Left)
+        {
+            if let Some(&index) = matches.get(state.highlighted) {
+                self.select(state, index);
+                self.close(state);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl HandleEvent for ComboBox<'_> {
+    type State<'a> = &'a mut ComboBoxState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        if let Some(id) = state.popup {
+            if !self.overlays.is_open(id) {
+                // Light dismiss.
+                state.popup = None;
+                state.filtering = false;
+            }
+        }
+
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+
+        if self.editor.is_some() && !state.filtering {
+            let text = state
+                .selected
+                .and_then(|index| self.options.get(index))
+                .map_or("", |option| option.text.as_str());
+            if state.text != text {
+                state.text = text.to_string();
+            }
+        }
+
+        let matches = self.matches(state);
+        if state.filter_changed {
+            state.filter_changed = false;
+            state.highlighted = 0;
+            state.first_row = 0;
+            self.open(state, &matches);
+        }
+
+        if let Some(id) = state.popup {
+            self.handle_popup_event(state, &matches, id, &event)?;
+        }
+
+        match &event {
+            ComponentEvent::LoopStart(..) => {
+                state.changed = false;
+            }
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) => {
+                let in_popup = state
+                    .popup
+                    .and_then(|id| self.overlays.rect(id))
+                    .is_some_and(|rect| rect.contains(pos.absolute_position()));
+                state.focused = pos.is_hit() || in_popup;
+                if pos.is_hit() {
+                    if state.popup.is_some() {
+                        self.close(state);
+                    } else {
+                        self.open(state, &matches);
+                    }
+                }
+            }
+            ComponentEvent::KeyDown(key, modifiers, timestamp) if state.focused => {
+                self.handle_key(state, &matches, *key, *modifiers, *timestamp);
+            }
+            _ => {}
+        }
+
+        Ok(event)
+    }
+}
+
+impl Render for ComboBox<'_> {
+    type State<'a> = &'a ComboBoxState;
+
+    fn render(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
+        state.anchor.set(&layout);
+        self.field.render((), layout.clone(), render_context)?;
+
+        let layout = layout.clip(self.padding);
+        match &self.editor {
+            Some(editor) => editor.render(&state.text, layout, render_context),
+            None => match state.selected.and_then(|index| self.options.get(index)) {
+                Some(option) => option.label.render((), layout, render_context),
+                None => Ok(()),
+            },
+        }
+    }
+
+    fn render_overlay(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let Some(rect) = state.popup.and_then(|id| self.overlays.rect(id)) else {
+            return Ok(());
+        };
+
+        let layout = Layout::new(rect).with_direction(layout.direction());
+        self.popup.render((), layout.clone(), render_context)?;
+
+        let matches = self.matches(state);
+        let row_size = Size::new(rect.size.width, self.row_height());
+        let visible = matches.iter().enumerate().skip(state.first_row);
+        for (row, (position, &index)) in visible.take(self.visible_rows()).enumerate() {
+            let row_layout = layout
+                .clip(Vector::new(0, row as i32 * self.row_height()))
+                .resize_clipped(row_size);
+            if position == state.highlighted {
+                self.highlight
+                    .render((), row_layout.clone(), render_context)?;
+            }
+            self.options[index]
+                .label
+                .render((), row_layout.clip(self.padding), render_context)?;
+        }
+
+        Ok(())
+    }
+}
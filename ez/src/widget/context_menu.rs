@@ -0,0 +1,106 @@
+use crate::widget::{Menu, MenuState};
+use amulet_core::action::ActionId;
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, HandleEvent, Layout, MouseSensor, MouseSensorState, SizeAttr,
+};
+use amulet_core::geom::{Rect, Size};
+use amulet_core::mouse::MouseButton;
+use amulet_core::overlay::Placement;
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+
+#[derive(Debug, Default)]
+pub struct ContextMenuState {
+    mouse_sensor: MouseSensorState,
+    menu: MenuState,
+}
+
+impl ContextMenuState {
+    /// The action of the item that was invoked since the start of the current loop iteration.
+    pub fn invoked(&self) -> Option<ActionId> {
+        self.menu.invoked()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.menu.is_open()
+    }
+
+    /// The state of the menu, which holds the check marks and disabled items.
+    pub fn menu(&self) -> &MenuState {
+        &self.menu
+    }
+
+    pub fn menu_mut(&mut self) -> &mut MenuState {
+        &mut self.menu
+    }
+}
+
+/// Opens a [`Menu`] at the pointer when the right mouse button is pressed within its frame.
+///
+/// Like a tooltip, the context menu has no visual representation in the regular render pass and
+/// is meant to be placed on top of the component it belongs to.
+pub struct ContextMenu<'a> {
+    outer: (Frame, MouseSensor),
+    menu: Menu<'a>,
+}
+
+impl SizeAttr for ContextMenu<'_> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a> ContextMenu<'a> {
+    pub fn new(outer: (Frame, MouseSensor), menu: Menu<'a>) -> Self {
+        Self { outer, menu }
+    }
+}
+
+impl HandleEvent for ContextMenu<'_> {
+    type State<'a> = &'a mut ContextMenuState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+        self.menu.handle_event(&mut state.menu, event.clone())?;
+
+        if let ComponentEvent::MouseButtonDown(MouseButton::Right, pos, ..) = &event {
+            if pos.is_hit() {
+                let anchor = Rect::new(pos.absolute_position(), Size::zero());
+                self.menu
+                    .open(&mut state.menu, anchor, Placement::Below, pos.direction());
+            }
+        }
+
+        Ok(event)
+    }
+}
+
+impl Render for ContextMenu<'_> {
+    type State<'a> = &'a ContextMenuState;
+
+    fn render(
+        &self,
+        _state: Self::State<'_>,
+        _layout: Layout,
+        _render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        Ok(())
+    }
+
+    fn render_overlay(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        self.menu
+            .render_overlay(&state.menu, layout, render_context)
+    }
+}
@@ -0,0 +1,302 @@
+use crate::widget::{Button, ButtonState, Image};
+use amulet_core::component::{
+    AsChain, ComponentEvent, FramedPosition, HandleEvent, Layout, Position, PositionAttr, SizeAttr,
+};
+use amulet_core::geom::{Rect, Size};
+use amulet_core::keyboard::Key;
+use amulet_core::overlay::{OverlayId, Overlays};
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+
+#[derive(Debug, Default)]
+pub struct DialogState {
+    overlay: Option<OverlayId>,
+    buttons: Vec<ButtonState>,
+    focused: usize,
+    /// Whether the content was clicked last, so that it receives Space and Enter.
+    content_focused: bool,
+    response: Option<usize>,
+}
+
+impl DialogState {
+    pub fn is_open(&self) -> bool {
+        self.overlay.is_some()
+    }
+
+    /// The index of the button the dialog was closed with, if that happened during the current
+    /// iteration of the event loop.
+    pub fn response(&self) -> Option<usize> {
+        self.response
+    }
+
+    /// The index of the button that has the keyboard focus while the dialog is open.
+    pub fn focused_button(&self) -> usize {
+        self.focused
+    }
+}
+
+/// A button in the button row of a [`Dialog`], along with the ring drawn around it while it is
+/// focused.
+pub struct DialogButton<'a> {
+    button: (Position, Button<'a>),
+    focus_ring: (Position, Image<'a>),
+}
+
+impl<'a> DialogButton<'a> {
+    pub fn new(button: (Position, Button<'a>), focus_ring: (Position, Image<'a>)) -> Self {
+        Self { button, focus_ring }
+    }
+}
+
+/// A modal dialog with a title, content and a row of buttons.
+///
+/// While open, the dialog is centered in the window above a dimmed backdrop. The backdrop is a
+/// modal overlay, so the components below don't receive pointer input, and the application must
+/// not pass keyboard input to them (see [`Overlays::is_interactive()`]). Everything is drawn
+/// during the overlay pass.
+///
+/// Tab and Shift+Tab move the keyboard focus between the buttons, without ever leaving the
+/// dialog. Space presses the focused button, Enter the default button and Escape the cancel
+/// button. Pressing a button closes the dialog and reports it through
+/// [`DialogState::response()`]. After a click on the content, such as into a text input, Space
+/// and Enter are left to the content until Tab moves the focus back to the buttons.
+///
+/// The content receives events and is rendered like any other component, and may open overlays
+/// of its own on top of the dialog.
+pub struct Dialog<'a, C> {
+    backdrop: Image<'a>,
+    panel: Image<'a>,
+    title: (Position, Image<'a>),
+    content: (Position, C),
+    buttons: Vec<DialogButton<'a>>,
+    default_button: usize,
+    cancel_button: Option<usize>,
+    overlays: Overlays,
+}
+
+impl<C> SizeAttr for Dialog<'_, C> {
+    fn size(&self) -> Size {
+        self.panel.size()
+    }
+}
+
+impl<'a, C> Dialog<'a, C> {
+    pub fn new(
+        backdrop: Image<'a>,
+        panel: Image<'a>,
+        title: (Position, Image<'a>),
+        content: (Position, C),
+        buttons: Vec<DialogButton<'a>>,
+        overlays: Overlays,
+    ) -> Self {
+        Self {
+            backdrop,
+            panel,
+            title,
+            content,
+            buttons,
+            default_button: 0,
+            cancel_button: None,
+            overlays,
+        }
+    }
+
+    /// The button pressed by Enter, which also has the focus when the dialog opens.
+    pub fn with_default_button(self, index: usize) -> Self {
+        Self {
+            default_button: index,
+            ..self
+        }
+    }
+
+    /// The button pressed by Escape. Without one, Escape has no effect.
+    pub fn with_cancel_button(self, index: usize) -> Self {
+        Self {
+            cancel_button: Some(index),
+            ..self
+        }
+    }
+
+    pub fn open(&self, state: &mut DialogState) {
+        if state.is_open() {
+            return;
+        }
+
+        state.overlay = Some(self.overlays.open_modal(self.overlays.bounds()));
+        state.focused = self.default_button;
+        state.content_focused = false;
+    }
+
+    pub fn close(&self, state: &mut DialogState) {
+        if let Some(id) = state.overlay.take() {
+            self.overlays.close(id);
+        }
+        // Discards click states left over from this time.
+        state.buttons.clear();
+    }
+
+    fn respond(&self, state: &mut DialogState, index: usize) {
+        if index < self.buttons.len() {
+            state.response = Some(index);
+            self.close(state);
+        }
+    }
+
+    /// Whether `pos` is on one of the buttons, which are mirrored in right-to-left direction.
+    fn is_on_button(&self, pos: &FramedPosition) -> bool {
+        let panel = Rect::from_size(self.panel.size());
+        let direction = pos.direction();
+        self.buttons.iter().any(|button| {
+            let (position, button) = &button.button;
+            let rect = direction.clip(panel, position.position().as_vector());
+            direction
+                .resize_clipped(rect, button.size())
+                .contains(pos.relative_position())
+        })
+    }
+
+    fn panel_rect(&self, bounds: Rect) -> Rect {
+        let size = self.panel.size();
+        let origin = bounds.origin + ((bounds.size - size) / 2).as_vector();
+        Rect::new(origin, size).constrain(bounds)
+    }
+}
+
+impl<C> HandleEvent for Dialog<'_, C>
+where
+    C: HandleEvent,
+{
+    type State<'a> = (&'a mut DialogState, C::State<'a>);
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        let (state, content_state) = state;
+        if let ComponentEvent::LoopStart(..) = event {
+            state.response = None;
+        }
+
+        let Some(id) = state.overlay.filter(|id| self.overlays.is_open(*id)) else {
+            state.overlay = None;
+            return Ok(event);
+        };
+
+        // Keep covering the window when it is resized.
+        let bounds = self.overlays.bounds();
+        self.overlays.set_rect(id, bounds);
+
+        let inner = event.clone().enter_overlay(id, self.panel_rect(bounds));
+        self.content
+            .as_chain()
+            .handle_event(((), content_state), inner.clone())?;
+
+        state
+            .buttons
+            .resize_with(self.buttons.len(), ButtonState::default);
+        let clicked = self
+            .buttons
+            .iter()
+            .zip(&mut state.buttons)
+            .map(|(button, button_state)| {
+                button
+                    .button
+                    .as_chain()
+                    .handle_event(((), &mut *button_state), inner.clone())?;
+                Ok(button_state.was_clicked())
+            })
+            .collect::<VuiResult<Vec<_>>>()?
+            .into_iter()
+            .position(|clicked| clicked);
+        if let Some(index) = clicked {
+            state.focused = index;
+            self.respond(state, index);
+            return Ok(event);
+        }
+
+        if let ComponentEvent::MouseButtonDown(_, pos, ..) = &inner {
+            state.content_focused = pos.is_hit() && !self.is_on_button(pos);
+        }
+
+        if let ComponentEvent::KeyDown(key, modifiers, _) = &event {
+            let count = self.buttons.len();
+            match key {
+                Key::Tab if count > 0 => {
+                    state.content_focused = false;
+                    state.focused = if modifiers.shift() {
+                        (state.focused + count - 1) % count
+                    } else {
+                        (state.focused + 1) % count
+                    };
+                }
+                Key::Character(' ') if !state.content_focused => self.respond(state, state.focused),
+                Key::Enter if !state.content_focused => self.respond(state, self.default_button),
+                Key::Escape => {
+                    if let Some(cancel) = self.cancel_button {
+                        self.respond(state, cancel);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(event)
+    }
+}
+
+impl<C> Render for Dialog<'_, C>
+where
+    C: Render,
+    for<'s> C::State<'s>: Copy,
+{
+    type State<'a> = (&'a DialogState, C::State<'a>);
+
+    fn render(
+        &self,
+        _state: Self::State<'_>,
+        _layout: Layout,
+        _render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        Ok(())
+    }
+
+    fn render_overlay(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let (state, content_state) = state;
+        if !state.is_open() {
+            return Ok(());
+        }
+
+        let bounds = render_context.window_rect();
+        self.backdrop
+            .render_stretched(bounds, Layout::new(bounds), render_context)?;
+
+        let layout = Layout::new(self.panel_rect(bounds)).with_direction(layout.direction());
+        self.panel.render((), layout.clone(), render_context)?;
+        self.title
+            .render(((), ()), layout.clone(), render_context)?;
+        self.content
+            .render(((), content_state), layout.clone(), render_context)?;
+
+        let unused = ButtonState::default();
+        for (index, button) in self.buttons.iter().enumerate() {
+            let button_state = state.buttons.get(index).unwrap_or(&unused);
+            button
+                .button
+                .render(((), button_state), layout.clone(), render_context)?;
+            if index == state.focused && !state.content_focused {
+                button
+                    .focus_ring
+                    .render(((), ()), layout.clone(), render_context)?;
+            }
+        }
+
+        self.content
+            .render_overlay(((), content_state), layout, render_context)
+    }
+}
@@ -1,16 +1,53 @@
 use crate::theme::Theme;
+use crate::widget::Image;
+use amulet_core::cache::LruCache;
 use amulet_core::component::Layout;
+use amulet_core::geom::Size;
 use amulet_core::VuiResult;
+use amulet_sdl2::lossy::LossyInto;
 use amulet_sdl2::render::{Render, RenderContext};
 use sdl2::pixels::Color;
+use std::cell::RefCell;
+use std::rc::Rc;
 
+/// The number of distinct texts whose rendering a [`DynText`] keeps.
+const CACHE_CAPACITY: usize = 256;
+
+/// Text that may change from one frame to the next, as opposed to a label.
+///
+/// The texts that were rendered recently are cached, so that text which stays the same is only
+/// rendered once. A single `DynText` may render many texts, like the rows of a list.
 pub struct DynText<'a> {
     theme: &'a Theme<'a>,
+    cache: RefCell<LruCache<String, Image<'a>>>,
 }
 
 impl<'a> DynText<'a> {
     pub fn new(theme: &'a Theme<'a>) -> Self {
-        Self { theme }
+        Self {
+            theme,
+            cache: RefCell::new(LruCache::new(CACHE_CAPACITY)),
+        }
+    }
+
+    /// The size of `text` when rendered on a single line.
+    pub fn size_of(&self, text: &str) -> VuiResult<Size> {
+        self.theme.fonts.size_of(text)
+    }
+
+    fn image(&self, text: &str) -> VuiResult<Image<'a>> {
+        if let Some(image) = self.cache.borrow_mut().get(text) {
+            return Ok(image.clone());
+        }
+
+        let surface = self.theme.fonts.render(text, Color::GREEN)?;
+        let size: (i32, i32) = surface.size().lossy_into();
+        let texture = Rc::new(surface.as_texture(self.theme.texture_creator)?);
+        let image = Image::new(texture, size.into());
+        self.cache
+            .borrow_mut()
+            .insert(text.to_string(), image.clone());
+        Ok(image)
     }
 }
 
@@ -28,14 +65,6 @@ impl Render for DynText<'_> {
             return Ok(());
         }
 
-        let surface = self.theme.font.render(text).blended(Color::GREEN)?;
-        let (w, h) = surface.size();
-        let texture = surface.as_texture(self.theme.texture_creator)?;
-        render_context.get_canvas(layout).copy(
-            &texture,
-            None,
-            Some(sdl2::rect::Rect::new(0, 0, w, h)),
-        )?;
-        Ok(())
+        self.image(text)?.render((), layout, render_context)
     }
 }
@@ -1,5 +1,5 @@
-use amulet_core::component::{Layout, SizeAttr};
-use amulet_core::geom::Size;
+use amulet_core::component::{HandleEvent, Layout, SizeAttr};
+use amulet_core::geom::{Rect, Size};
 use amulet_core::VuiResult;
 use amulet_sdl2::lossy::LossyInto;
 use amulet_sdl2::render::{Render, RenderContext};
@@ -16,6 +16,28 @@ impl<'a> Image<'a> {
     pub fn new(texture: Rc<Texture<'a>>, size: Size) -> Self {
         Self { texture, size }
     }
+
+    /// Renders the image scaled to cover `rect`, which is relative to the layout.
+    pub fn render_stretched(
+        &self,
+        rect: Rect,
+        layout: Layout,
+        render_ctx: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let (x, y, w, h) = rect.into();
+        let dst = sdl2::rect::Rect::new(x, y, w.lossy_into(), h.lossy_into());
+
+        let canvas = render_ctx.get_canvas(layout);
+        canvas.copy(&self.texture, None, dst)?;
+
+        Ok(())
+    }
+}
+
+/// Images are static, so they ignore all events. This lets them serve as the content of
+/// containers like [`crate::widget::Dialog`].
+impl HandleEvent for Image<'_> {
+    type State<'a> = ();
 }
 
 impl Render for Image<'_> {
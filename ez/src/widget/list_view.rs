@@ -0,0 +1,361 @@
+use crate::widget::row_scroll::RowScroll;
+use crate::widget::{Image, Scrollbar, ScrollbarState};
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, FramedPosition, HandleEvent, Layout, MouseSensor,
+    MouseSensorState, Position, SizeAttr, UpdateLayout,
+};
+use amulet_core::geom::{Rect, Size, Vector};
+use amulet_core::keyboard::{Key, Modifiers};
+use amulet_core::mouse::MouseButton;
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+use std::collections::BTreeSet;
+
+/// Whether a [`ListView`] allows selecting more than one row.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum SelectionMode {
+    #[default]
+    Single,
+    /// Ctrl toggles single rows and Shift selects ranges, with both the mouse and the keyboard.
+    Multiple,
+}
+
+/// A row as passed to the row-rendering callback of a [`ListView`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ListRow {
+    pub index: usize,
+    pub selected: bool,
+    /// Whether this is the row the keyboard operates on.
+    pub current: bool,
+}
+
+/// Renders the content of a row into the provided layout.
+pub type RenderRow<'a> = Box<dyn Fn(ListRow, Layout, &mut RenderContext) -> VuiResult<()> + 'a>;
+
+/// The images a [`ListView`] is drawn with.
+pub struct ListViewImages<'a> {
+    pub background: Image<'a>,
+    /// Drawn behind selected rows. Its height is the height of the rows.
+    pub selection: Image<'a>,
+    /// Drawn around the current row while the list view is focused.
+    pub focus_ring: Image<'a>,
+}
+
+#[derive(Debug, Default)]
+pub struct ListViewState {
+    mouse_sensor: MouseSensorState,
+    scrollbar: ScrollbarState,
+    selection: BTreeSet<usize>,
+    current: Option<usize>,
+    /// The row that range selections extend from.
+    anchor: Option<usize>,
+    pressed_row: Option<usize>,
+    activated: Option<usize>,
+    focused: bool,
+    changed: bool,
+}
+
+impl ListViewState {
+    /// The indices of the selected rows in ascending order.
+    pub fn selection(&self) -> impl Iterator<Item = usize> + '_ {
+        self.selection.iter().copied()
+    }
+
+    /// The first selected row, which is the only one in [`SelectionMode::Single`].
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selection.first().copied()
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selection.contains(&index)
+    }
+
+    /// Sets the selection programmatically. This does not trigger [`Self::selection_changed()`].
+    pub fn set_selection(&mut self, selection: impl IntoIterator<Item = usize>) {
+        self.selection = selection.into_iter().collect();
+        self.anchor = self.selection.first().copied();
+        self.current = self.anchor;
+    }
+
+    /// Whether the user changed the selection since the start of the current loop iteration.
+    pub fn selection_changed(&self) -> bool {
+        self.changed
+    }
+
+    /// The row the keyboard operates on.
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// The row that was double-clicked or confirmed with Enter during the current loop
+    /// iteration.
+    pub fn activated(&self) -> Option<usize> {
+        self.activated
+    }
+
+    /// Whether the list view receives keyboard input. It gains focus when it is clicked and loses
+    /// it when a click happens elsewhere.
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The index of the topmost visible row.
+    pub fn first_visible_row(&self) -> usize {
+        RowScroll::first_visible_row(&self.scrollbar)
+    }
+
+    /// Scrolls so that the topmost visible row is `index`, within the limits of the scrollbar.
+    pub fn set_first_visible_row(&mut self, index: usize) {
+        self.scrollbar
+            .set_position(i32::try_from(index).unwrap_or(i32::MAX));
+    }
+
+    fn update_selection(&mut self, selection: BTreeSet<usize>) {
+        if selection != self.selection {
+            self.selection = selection;
+            self.changed = true;
+        }
+    }
+}
+
+/// A list of uniformly sized rows that only renders the rows that are currently visible, so that
+/// it can hold a very large number of them.
+///
+/// The rows are drawn by a callback, which receives the index of the row and a layout covering
+/// it, and a vertical scrollbar scrolls the list row by row. The arrow keys, Page Up/Down, Home
+/// and End move the current row and select it, Enter or a double click activate it.
+pub struct ListView<'a> {
+    outer: (Frame, MouseSensor),
+    scrollbar: (Position, Scrollbar<'a>),
+    images: ListViewImages<'a>,
+    rows: RowScroll,
+    padding: Vector,
+    row_count: usize,
+    mode: SelectionMode,
+    render_row: RenderRow<'a>,
+}
+
+impl SizeAttr for ListView<'_> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a> ListView<'a> {
+    /// The rows are laid out within `rows_rect`, and their content is rendered at `padding` within
+    /// the row. The range of `scrollbar` must be in rows.
+    pub fn new(
+        outer: (Frame, MouseSensor),
+        scrollbar: (Position, Scrollbar<'a>),
+        images: ListViewImages<'a>,
+        rows_rect: Rect,
+        padding: Vector,
+        row_count: usize,
+        render_row: RenderRow<'a>,
+    ) -> Self {
+        let rows = RowScroll::new(images.selection.size().height, rows_rect);
+        Self {
+            outer,
+            scrollbar,
+            images,
+            rows,
+            padding,
+            row_count,
+            mode: SelectionMode::Single,
+            render_row,
+        }
+    }
+
+    pub fn with_selection_mode(self, mode: SelectionMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// The row at `pos`, which is framed by the list view.
+    fn row_at(&self, state: &ListViewState, pos: &FramedPosition) -> Option<usize> {
+        self.rows
+            .row_at(&state.scrollbar, pos)
+            .filter(|&index| index < self.row_count)
+    }
+
+    /// Makes `index` the current row and updates the selection according to the modifiers, the
+    /// same way for clicks and keyboard navigation.
+    fn select(&self, state: &mut ListViewState, index: usize, modifiers: Modifiers) {
+        let multiple = self.mode == SelectionMode::Multiple;
+        state.current = Some(index);
+
+        if multiple && modifiers.shift() {
+            let anchor = *state.anchor.get_or_insert(index);
+            let range = anchor.min(index)..=anchor.max(index);
+            let selection = if modifiers.ctrl() {
+                state.selection.iter().copied().chain(range).collect()
+            } else {
+                range.collect()
+            };
+            state.update_selection(selection);
+        } else if multiple && modifiers.ctrl() {
+            state.anchor = Some(index);
+        } else {
+            state.anchor = Some(index);
+            state.update_selection(BTreeSet::from([index]));
+        }
+    }
+
+    fn toggle(&self, state: &mut ListViewState, index: usize) {
+        let mut selection = state.selection.clone();
+        if !selection.remove(&index) {
+            selection.insert(index);
+        }
+        state.anchor = Some(index);
+        state.update_selection(selection);
+    }
+
+    fn handle_key(&self, state: &mut ListViewState, key: Key, modifiers: Modifiers) {
+        if self.row_count == 0 {
+            return;
+        }
+
+        let multiple = self.mode == SelectionMode::Multiple;
+        let last = self.row_count - 1;
+        let page = self.rows.visible_rows().saturating_sub(1).max(1);
+        let current = state.current;
+        let target = match key {
+            Key::Up => current.map_or(0, |i| i.saturating_sub(1)),
+            Key::Down => current.map_or(0, |i| (i + 1).min(last)),
+            Key::PageUp => current.map_or(0, |i| i.saturating_sub(page)),
+            Key::PageDown => current.map_or(0, |i| (i + page).min(last)),
+            Key::Home => 0,
+            Key::End => last,
+            Key::Character(' ') => {
+                let index = current.unwrap_or(0);
+                if multiple && modifiers.ctrl() {
+                    self.toggle(state, index);
+                } else {
+                    self.select(state, index, Modifiers::NONE);
+                }
+                return;
+            }
+            Key::Character('a') if multiple && modifiers.ctrl() => {
+                state.update_selection((0..self.row_count).collect());
+                return;
+            }
+            Key::Enter => {
+                state.activated = current;
+                return;
+            }
+            _ => return,
+        };
+
+        self.select(state, target, modifiers);
+        let range = self.scrollbar.1.range();
+        self.rows
+            .scroll_into_view(&mut state.scrollbar, range, target);
+    }
+}
+
+impl HandleEvent for ListView<'_> {
+    type State<'a> = &'a mut ListViewState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+        self.scrollbar
+            .as_chain()
+            .handle_event(((), &mut state.scrollbar), event.clone())?;
+
+        match &event {
+            ComponentEvent::LoopStart(..) => {
+                state.changed = false;
+                state.activated = None;
+            }
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, modifiers, _) => {
+                state.focused = pos.is_hit();
+                state.pressed_row = None;
+                if pos.is_hit() {
+                    if let Some(index) = self.row_at(state, pos) {
+                        state.pressed_row = Some(index);
+                        if self.mode == SelectionMode::Multiple && modifiers.ctrl() {
+                            state.current = Some(index);
+                            self.toggle(state, index);
+                        } else {
+                            self.select(state, index, *modifiers);
+                        }
+                    }
+                }
+            }
+            ComponentEvent::MouseWheel(amount, pos, ..) if pos.is_hit() => {
+                let range = self.scrollbar.1.range();
+                self.rows
+                    .scroll_by_wheel(&mut state.scrollbar, range, *amount);
+            }
+            ComponentEvent::KeyDown(key, modifiers, _) if state.focused => {
+                self.handle_key(state, *key, *modifiers);
+            }
+            _ => {}
+        }
+
+        if state
+            .mouse_sensor
+            .click_states()
+            .has_double_click_completed(MouseButton::Left)
+        {
+            state.activated = state.pressed_row;
+        }
+
+        Ok(event)
+    }
+}
+
+impl Render for ListView<'_> {
+    type State<'a> = &'a ListViewState;
+
+    fn render(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
+        self.images
+            .background
+            .render((), layout.clone(), render_context)?;
+        self.scrollbar
+            .render(((), &state.scrollbar), layout.clone(), render_context)?;
+
+        let rows_layout = self.rows.area_layout(&layout);
+        let first = state.first_visible_row();
+        // The last row may be partially visible.
+        let end = (first + self.rows.visible_rows() + 1).min(self.row_count);
+        for (offset, index) in (first..end).enumerate() {
+            let row_layout = self.rows.row_layout(&rows_layout, offset);
+            let row = ListRow {
+                index,
+                selected: state.is_selected(index),
+                current: state.current == Some(index),
+            };
+
+            if row.selected {
+                self.images
+                    .selection
+                    .render((), row_layout.clone(), render_context)?;
+            }
+            (self.render_row)(row, row_layout.place(self.padding), render_context)?;
+            if row.current && state.focused {
+                self.images
+                    .focus_ring
+                    .render((), row_layout, render_context)?;
+            }
+        }
+
+        Ok(())
+    }
+}
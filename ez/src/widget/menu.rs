@@ -0,0 +1,640 @@
+use crate::widget::Image;
+use amulet_core::action::ActionId;
+use amulet_core::component::{ComponentEvent, HandleEvent, Layout, SizeAttr};
+use amulet_core::geom::{Direction, Rect, Size, Vector};
+use amulet_core::keyboard::{Key, Modifiers};
+use amulet_core::mouse::MouseButton;
+use amulet_core::overlay::{OverlayId, Overlays, Placement};
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+use std::collections::BTreeSet;
+
+/// Describes an entry of a menu.
+///
+/// In labels, `&` marks the following character as the mnemonic of the entry, which activates it
+/// when typed while the menu is open. Use `&&` for a literal `&`.
+#[derive(Debug, Clone)]
+pub enum MenuEntry {
+    Item(MenuItem),
+    Submenu(String, Vec<MenuEntry>),
+    Separator,
+}
+
+#[derive(Debug, Clone)]
+pub struct MenuItem {
+    pub(crate) label: String,
+    pub(crate) action: ActionId,
+    pub(crate) accelerator: Option<String>,
+    pub(crate) checkable: bool,
+}
+
+impl MenuEntry {
+    pub fn item(label: &str, action: ActionId) -> Self {
+        MenuEntry::Item(MenuItem {
+            label: label.to_string(),
+            action,
+            accelerator: None,
+            checkable: false,
+        })
+    }
+
+    /// An item that toggles its check mark when invoked. See [`MenuState::is_checked()`].
+    pub fn check_item(label: &str, action: ActionId) -> Self {
+        MenuEntry::Item(MenuItem {
+            label: label.to_string(),
+            action,
+            accelerator: None,
+            checkable: true,
+        })
+    }
+
+    pub fn submenu(label: &str, entries: Vec<MenuEntry>) -> Self {
+        MenuEntry::Submenu(label.to_string(), entries)
+    }
+
+    /// Shows `accelerator` (e.g. "Ctrl+S") next to the label of an item. This is only a hint for
+    /// the user, the shortcut itself has to be registered separately.
+    pub fn with_accelerator(self, accelerator: &str) -> Self {
+        match self {
+            MenuEntry::Item(item) => MenuEntry::Item(MenuItem {
+                accelerator: Some(accelerator.to_string()),
+                ..item
+            }),
+            other => other,
+        }
+    }
+}
+
+/// Splits the mnemonic marker off `label`, returning the text to display and the byte range of
+/// the mnemonic character within it.
+pub(crate) fn parse_mnemonic(label: &str) -> (String, Option<(usize, usize)>) {
+    let mut text = String::with_capacity(label.len());
+    let mut mnemonic = None;
+    let mut chars = label.chars();
+    while let Some(chr) = chars.next() {
+        if chr != '&' {
+            text.push(chr);
+            continue;
+        }
+
+        match chars.next() {
+            Some('&') => text.push('&'),
+            Some(chr) => {
+                if mnemonic.is_none() {
+                    mnemonic = Some((text.len(), text.len() + chr.len_utf8()));
+                }
+                text.push(chr);
+            }
+            None => {}
+        }
+    }
+    (text, mnemonic)
+}
+
+/// A label in its regular and its disabled appearance.
+pub(crate) struct MenuLabel<'a> {
+    pub(crate) normal: Image<'a>,
+    pub(crate) dimmed: Image<'a>,
+}
+
+impl<'a> MenuLabel<'a> {
+    fn image(&self, enabled: bool) -> &Image<'a> {
+        if enabled {
+            &self.normal
+        } else {
+            &self.dimmed
+        }
+    }
+}
+
+pub(crate) enum MenuRowKind<'a> {
+    Item {
+        action: ActionId,
+        checkable: bool,
+        label: MenuLabel<'a>,
+        accelerator: Option<MenuLabel<'a>>,
+    },
+    Submenu {
+        label: MenuLabel<'a>,
+        menu: Box<Menu<'a>>,
+    },
+    Separator,
+}
+
+pub(crate) struct MenuRow<'a> {
+    pub(crate) kind: MenuRowKind<'a>,
+    pub(crate) mnemonic: Option<char>,
+    /// Relative to the menu origin.
+    pub(crate) rect: Rect,
+}
+
+/// The images shared by all rows of a menu and its submenus.
+#[derive(Clone)]
+pub(crate) struct MenuImages<'a> {
+    pub(crate) background: Image<'a>,
+    pub(crate) highlight: Image<'a>,
+    pub(crate) check_mark: Image<'a>,
+    pub(crate) submenu_arrow: Image<'a>,
+    pub(crate) separator: Image<'a>,
+}
+
+/// Offsets of the parts of a row, relative to the row origin.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct MenuMetrics {
+    pub(crate) check_mark: Vector,
+    pub(crate) label: Vector,
+    pub(crate) accelerator: Vector,
+    pub(crate) submenu_arrow: Vector,
+    pub(crate) separator: Vector,
+}
+
+#[derive(Debug)]
+struct MenuLevel {
+    overlay: OverlayId,
+    /// The row of the parent menu that opened this submenu.
+    parent_row: Option<usize>,
+    highlighted: Option<usize>,
+}
+
+/// Where the keyboard navigation left the root menu. A menu bar moves on to the adjacent menu.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum MenuExit {
+    Previous,
+    Next,
+}
+
+#[derive(Debug, Default)]
+pub struct MenuState {
+    /// The open menu, followed by its open submenus.
+    levels: Vec<MenuLevel>,
+    checked: BTreeSet<ActionId>,
+    disabled: BTreeSet<ActionId>,
+    invoked: Option<ActionId>,
+    exit: Option<MenuExit>,
+    /// The direction the menu was opened in, which its submenus are opened in as well.
+    direction: Direction,
+}
+
+impl MenuState {
+    pub fn is_open(&self) -> bool {
+        !self.levels.is_empty()
+    }
+
+    /// The action of the item that was invoked since the start of the current loop iteration.
+    pub fn invoked(&self) -> Option<ActionId> {
+        self.invoked
+    }
+
+    pub fn is_checked(&self, action: ActionId) -> bool {
+        self.checked.contains(&action)
+    }
+
+    pub fn set_checked(&mut self, action: ActionId, checked: bool) {
+        if checked {
+            self.checked.insert(action);
+        } else {
+            self.checked.remove(&action);
+        }
+    }
+
+    pub fn is_enabled(&self, action: ActionId) -> bool {
+        !self.disabled.contains(&action)
+    }
+
+    /// Disabled items are dimmed and can neither be highlighted nor invoked.
+    pub fn set_enabled(&mut self, action: ActionId, enabled: bool) {
+        if enabled {
+            self.disabled.remove(&action);
+        } else {
+            self.disabled.insert(action);
+        }
+    }
+
+    pub(crate) fn clear_event_state(&mut self) {
+        self.invoked = None;
+        self.exit = None;
+    }
+
+    pub(crate) fn take_exit(&mut self) -> Option<MenuExit> {
+        self.exit.take()
+    }
+}
+
+/// A popup menu with items, separators and submenus.
+///
+/// The menu is shown on the overlay layer. It is usually part of a [`crate::widget::MenuBar`] or
+/// a [`crate::widget::ContextMenu`], which open it. Items are invoked by clicking them, or using
+/// the arrow keys and Enter, or by typing their mnemonic. The action of the invoked item is
+/// reported through [`MenuState::invoked()`].
+pub struct Menu<'a> {
+    rows: Vec<MenuRow<'a>>,
+    images: MenuImages<'a>,
+    metrics: MenuMetrics,
+    size: Size,
+    overlays: Overlays,
+}
+
+impl SizeAttr for Menu<'_> {
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<'a> Menu<'a> {
+    pub(crate) fn new(
+        rows: Vec<MenuRow<'a>>,
+        images: MenuImages<'a>,
+        metrics: MenuMetrics,
+        size: Size,
+        overlays: Overlays,
+    ) -> Self {
+        Self {
+            rows,
+            images,
+            metrics,
+            size,
+            overlays,
+        }
+    }
+
+    /// Opens the menu next to `anchor`, closing it first if it is already open. See
+    /// [`Placement::place()`].
+    pub fn open(
+        &self,
+        state: &mut MenuState,
+        anchor: Rect,
+        placement: Placement,
+        direction: Direction,
+    ) {
+        self.close(state);
+        state.direction = direction;
+        let overlay = self
+            .overlays
+            .open_at(anchor, self.size, placement, direction);
+        state.levels.push(MenuLevel {
+            overlay,
+            parent_row: None,
+            highlighted: None,
+        });
+    }
+
+    /// Highlights the first item of the innermost open menu, as is customary when a menu is
+    /// opened with the keyboard.
+    pub fn highlight_first(&self, state: &mut MenuState) {
+        if let Some(level) = state.levels.len().checked_sub(1) {
+            let first = self
+                .menu_at(state, level)
+                .next_selectable(state, None, true);
+            state.levels[level].highlighted = first;
+        }
+    }
+
+    pub fn close(&self, state: &mut MenuState) {
+        if let Some(level) = state.levels.first() {
+            self.overlays.close(level.overlay);
+        }
+        state.levels.clear();
+    }
+
+    /// The (sub)menu shown at `level`.
+    fn menu_at(&self, state: &MenuState, level: usize) -> &Menu<'a> {
+        let mut menu = self;
+        for level in &state.levels[1..=level] {
+            match level.parent_row.map(|row| &menu.rows[row].kind) {
+                Some(MenuRowKind::Submenu { menu: submenu, .. }) => menu = submenu,
+                _ => break,
+            }
+        }
+        menu
+    }
+
+    fn row_at(&self, y: i32) -> Option<usize> {
+        self.rows
+            .iter()
+            .position(|row| y >= row.rect.origin.y && y < row.rect.limit().y)
+    }
+
+    fn is_selectable(&self, state: &MenuState, row: usize) -> bool {
+        match &self.rows[row].kind {
+            MenuRowKind::Item { action, .. } => state.is_enabled(*action),
+            MenuRowKind::Submenu { .. } => true,
+            MenuRowKind::Separator => false,
+        }
+    }
+
+    /// The next selectable row after `from` in the provided direction, wrapping around.
+    fn next_selectable(
+        &self,
+        state: &MenuState,
+        from: Option<usize>,
+        forward: bool,
+    ) -> Option<usize> {
+        let count = self.rows.len();
+        let start = match (from, forward) {
+            (Some(row), true) => row + 1,
+            (Some(row), false) => row + count - 1,
+            (None, true) => 0,
+            (None, false) => count.saturating_sub(1),
+        };
+        (0..count)
+            .map(|offset| {
+                if forward {
+                    (start + offset) % count
+                } else {
+                    (start + count - offset) % count
+                }
+            })
+            .find(|&row| self.is_selectable(state, row))
+    }
+
+    fn close_levels_above(&self, state: &mut MenuState, level: usize) {
+        if let Some(above) = state.levels.get(level + 1) {
+            self.overlays.close(above.overlay);
+        }
+        state.levels.truncate(level + 1);
+    }
+
+    fn open_submenu(&self, state: &mut MenuState, level: usize, row: usize) {
+        self.close_levels_above(state, level);
+
+        let menu = self.menu_at(state, level);
+        let MenuRowKind::Submenu { menu: submenu, .. } = &menu.rows[row].kind else {
+            return;
+        };
+        let Some(rect) = self.overlays.rect(state.levels[level].overlay) else {
+            return;
+        };
+
+        let anchor = menu.rows[row].rect.translate(rect.origin.as_vector());
+        let overlay =
+            self.overlays
+                .open_at(anchor, submenu.size, Placement::Right, state.direction);
+        state.levels.push(MenuLevel {
+            overlay,
+            parent_row: Some(row),
+            highlighted: None,
+        });
+    }
+
+    /// Invokes the item or opens the submenu in `row`.
+    fn activate(&self, state: &mut MenuState, level: usize, row: usize) {
+        let menu = self.menu_at(state, level);
+        if !menu.is_selectable(state, row) {
+            return;
+        }
+
+        match &menu.rows[row].kind {
+            MenuRowKind::Item {
+                action, checkable, ..
+            } => {
+                if *checkable {
+                    let checked = state.is_checked(*action);
+                    state.set_checked(*action, !checked);
+                }
+                state.invoked = Some(*action);
+                self.close(state);
+            }
+            MenuRowKind::Submenu { .. } => {
+                state.levels[level].highlighted = Some(row);
+                if state.levels.get(level + 1).and_then(|l| l.parent_row) != Some(row) {
+                    self.open_submenu(state, level, row);
+                }
+                self.highlight_first(state);
+            }
+            MenuRowKind::Separator => {}
+        }
+    }
+
+    fn handle_level_event(&self, state: &mut MenuState, level: usize, event: &ComponentEvent) {
+        let overlay = state.levels[level].overlay;
+        let Some(rect) = self.overlays.rect(overlay) else {
+            return;
+        };
+        let menu = self.menu_at(state, level);
+        let is_innermost = level + 1 == state.levels.len();
+
+        match event.clone().enter_overlay(overlay, rect) {
+            ComponentEvent::MouseMotion(pos, ..) if pos.is_hit() => {
+                let row = menu
+                    .row_at(pos.relative_position().y)
+                    .filter(|&row| menu.is_selectable(state, row));
+                if row != state.levels[level].highlighted {
+                    state.levels[level].highlighted = row;
+                    self.close_levels_above(state, level);
+                    if let Some(row) = row {
+                        if let MenuRowKind::Submenu { .. } = &menu.rows[row].kind {
+                            self.open_submenu(state, level, row);
+                        }
+                    }
+                }
+            }
+            ComponentEvent::MouseMotion(..) if is_innermost => {
+                state.levels[level].highlighted = None;
+            }
+            // Invoking on release also supports pressing the button on a menu bar title and
+            // releasing it over an item.
+            ComponentEvent::MouseButtonUp(MouseButton::Left, pos, ..) if pos.is_hit() => {
+                if let Some(row) = menu.row_at(pos.relative_position().y) {
+                    self.activate(state, level, row);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_key(&self, state: &mut MenuState, key: Key, modifiers: Modifiers) {
+        let Some(level) = state.levels.len().checked_sub(1) else {
+            return;
+        };
+        let menu = self.menu_at(state, level);
+        let current = state.levels[level].highlighted;
+
+        match key {
+            Key::Down | Key::Up => {
+                state.levels[level].highlighted =
+                    menu.next_selectable(state, current, key == Key::Down);
+            }
+            Key::Right => match current {
+                Some(row) if matches!(menu.rows[row].kind, MenuRowKind::Submenu { .. }) => {
+                    self.activate(state, level, row);
+                }
+                _ => state.exit = Some(MenuExit::Next),
+            },
+            Key::Left => {
+                if level > 0 {
+                    self.close_levels_above(state, level - 1);
+                } else {
+                    state.exit = Some(MenuExit::Previous);
+                }
+            }
+            Key::Enter | Key::Character(' ') => {
+                if let Some(row) = current {
+                    self.activate(state, level, row);
+                }
+            }
+            Key::Escape => {
+                if level > 0 {
+                    self.close_levels_above(state, level - 1);
+                } else {
+                    self.close(state);
+                }
+            }
+            // Chords are left to the menu bar and to shortcuts.
+            Key::Character(chr)
+                if !(modifiers.ctrl() || modifiers.alt() || modifiers.super_key()) =>
+            {
+                let chr = chr.to_ascii_lowercase();
+                let row = (0..menu.rows.len()).find(|&row| {
+                    menu.rows[row].mnemonic == Some(chr) && menu.is_selectable(state, row)
+                });
+                if let Some(row) = row {
+                    state.levels[level].highlighted = Some(row);
+                    self.activate(state, level, row);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn render_menu(
+        &self,
+        state: &MenuState,
+        highlighted: Option<usize>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let images = &self.images;
+        let metrics = &self.metrics;
+        images
+            .background
+            .render((), layout.clone(), render_context)?;
+
+        for (index, row) in self.rows.iter().enumerate() {
+            let row_layout = layout
+                .clip(row.rect.origin.as_vector())
+                .resize_clipped(row.rect.size);
+            let highlighted = highlighted == Some(index);
+            if highlighted {
+                images
+                    .highlight
+                    .render((), row_layout.clone(), render_context)?;
+            }
+
+            match &row.kind {
+                MenuRowKind::Item {
+                    action,
+                    label,
+                    accelerator,
+                    ..
+                } => {
+                    let enabled = state.is_enabled(*action);
+                    if state.is_checked(*action) {
+                        images.check_mark.render(
+                            (),
+                            row_layout.clip(metrics.check_mark),
+                            render_context,
+                        )?;
+                    }
+                    label.image(enabled).render(
+                        (),
+                        row_layout.clip(metrics.label),
+                        render_context,
+                    )?;
+                    if let Some(accelerator) = accelerator {
+                        accelerator.image(enabled).render(
+                            (),
+                            row_layout.clip(metrics.accelerator),
+                            render_context,
+                        )?;
+                    }
+                }
+                MenuRowKind::Submenu { label, .. } => {
+                    label
+                        .normal
+                        .render((), row_layout.clip(metrics.label), render_context)?;
+                    images.submenu_arrow.render(
+                        (),
+                        row_layout.clip(metrics.submenu_arrow),
+                        render_context,
+                    )?;
+                }
+                MenuRowKind::Separator => {
+                    images.separator.render(
+                        (),
+                        row_layout.clip(metrics.separator),
+                        render_context,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl HandleEvent for Menu<'_> {
+    type State<'a> = &'a mut MenuState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        // Menus closed by light dismiss.
+        if let Some(closed) = state
+            .levels
+            .iter()
+            .position(|level| !self.overlays.is_open(level.overlay))
+        {
+            state.levels.truncate(closed);
+        }
+
+        if let ComponentEvent::LoopStart(..) = &event {
+            state.clear_event_state();
+        }
+
+        let mut level = 0;
+        while level < state.levels.len() {
+            self.handle_level_event(state, level, &event);
+            level += 1;
+        }
+
+        if let ComponentEvent::KeyDown(key, modifiers, _) = &event {
+            self.handle_key(state, *key, *modifiers);
+        }
+
+        Ok(event)
+    }
+}
+
+impl Render for Menu<'_> {
+    type State<'a> = &'a MenuState;
+
+    fn render(
+        &self,
+        _state: Self::State<'_>,
+        _layout: Layout,
+        _render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        Ok(())
+    }
+
+    fn render_overlay(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        for (index, level) in state.levels.iter().enumerate() {
+            if let Some(rect) = self.overlays.rect(level.overlay) {
+                self.menu_at(state, index).render_menu(
+                    state,
+                    level.highlighted,
+                    Layout::new(rect).with_direction(layout.direction()),
+                    render_context,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+use crate::widget::anchor::Anchor;
+use crate::widget::menu::MenuExit;
+use crate::widget::{Image, Menu, MenuState};
+use amulet_core::action::ActionId;
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, HandleEvent, Layout, MouseSensor, MouseSensorState, SizeAttr,
+    UpdateLayout,
+};
+use amulet_core::geom::{Rect, Size, Vector};
+use amulet_core::keyboard::Key;
+use amulet_core::mouse::MouseButton;
+use amulet_core::overlay::Placement;
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+
+#[derive(Debug, Default)]
+pub struct MenuBarState {
+    mouse_sensor: MouseSensorState,
+    menu: MenuState,
+    open: Option<usize>,
+    /// The bar, relative to which the titles are positioned.
+    anchor: Anchor,
+}
+
+impl MenuBarState {
+    /// The action of the item that was invoked since the start of the current loop iteration.
+    pub fn invoked(&self) -> Option<ActionId> {
+        self.menu.invoked()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open.is_some()
+    }
+
+    /// The state of the menus, which holds the check marks and disabled items.
+    pub fn menu(&self) -> &MenuState {
+        &self.menu
+    }
+
+    pub fn menu_mut(&mut self) -> &mut MenuState {
+        &mut self.menu
+    }
+}
+
+pub(crate) struct MenuTitle<'a> {
+    pub(crate) label: Image<'a>,
+    pub(crate) highlight: Image<'a>,
+    pub(crate) mnemonic: Option<char>,
+    /// Relative to the menu bar origin.
+    pub(crate) rect: Rect,
+    pub(crate) menu: Menu<'a>,
+}
+
+/// A horizontal bar of menu titles, each opening a [`Menu`] below it.
+///
+/// While a menu is open, hovering another title opens that one instead, as does moving past the
+/// first or last item with Left or Right. A menu can also be opened with Alt and the mnemonic of
+/// its title.
+pub struct MenuBar<'a> {
+    outer: (Frame, MouseSensor),
+    background: Image<'a>,
+    titles: Vec<MenuTitle<'a>>,
+    padding: Vector,
+}
+
+impl SizeAttr for MenuBar<'_> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a> MenuBar<'a> {
+    pub(crate) fn new(
+        outer: (Frame, MouseSensor),
+        background: Image<'a>,
+        titles: Vec<MenuTitle<'a>>,
+        padding: Vector,
+    ) -> Self {
+        Self {
+            outer,
+            background,
+            titles,
+            padding,
+        }
+    }
+
+    fn title_at(&self, x: i32) -> Option<usize> {
+        self.titles
+            .iter()
+            .position(|title| x >= title.rect.origin.x && x < title.rect.limit().x)
+    }
+
+    fn open(&self, state: &mut MenuBarState, index: usize) {
+        let title = &self.titles[index];
+        let anchor = title.rect.translate(state.anchor.rect().origin.as_vector());
+        title.menu.open(
+            &mut state.menu,
+            anchor,
+            Placement::Below,
+            state.anchor.direction(),
+        );
+        state.open = Some(index);
+    }
+
+    fn close(&self, state: &mut MenuBarState) {
+        if let Some(open) = state.open.take() {
+            self.titles[open].menu.close(&mut state.menu);
+        }
+    }
+}
+
+impl HandleEvent for MenuBar<'_> {
+    type State<'a> = &'a mut MenuBarState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+
+        if let ComponentEvent::LoopStart(..) = &event {
+            state.menu.clear_event_state();
+        }
+
+        if let Some(open) = state.open {
+            self.titles[open]
+                .menu
+                .handle_event(&mut state.menu, event.clone())?;
+
+            if !state.menu.is_open() {
+                state.open = None;
+            } else if let Some(exit) = state.menu.take_exit() {
+                let count = self.titles.len();
+                let next = match exit {
+                    MenuExit::Previous => (open + count - 1) % count,
+                    MenuExit::Next => (open + 1) % count,
+                };
+                self.open(state, next);
+                self.titles[next].menu.highlight_first(&mut state.menu);
+            }
+        }
+
+        match &event {
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) if pos.is_hit() => {
+                match self.title_at(pos.relative_position().x) {
+                    Some(index) if state.open == Some(index) => self.close(state),
+                    Some(index) => self.open(state, index),
+                    None => self.close(state),
+                }
+            }
+            ComponentEvent::MouseMotion(pos, ..) if pos.is_hit() && state.open.is_some() => {
+                if let Some(index) = self.title_at(pos.relative_position().x) {
+                    if state.open != Some(index) {
+                        self.open(state, index);
+                    }
+                }
+            }
+            ComponentEvent::KeyDown(Key::Character(chr), modifiers, ..) if modifiers.alt() => {
+                let chr = chr.to_ascii_lowercase();
+                if let Some(index) = self.titles.iter().position(|t| t.mnemonic == Some(chr)) {
+                    self.open(state, index);
+                    self.titles[index].menu.highlight_first(&mut state.menu);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(event)
+    }
+}
+
+impl Render for MenuBar<'_> {
+    type State<'a> = &'a MenuBarState;
+
+    fn render(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
+        state.anchor.set(&layout);
+        self.background.render((), layout.clone(), render_context)?;
+
+        let hovered = state
+            .mouse_sensor
+            .pointer_position()
+            .filter(|_| state.mouse_sensor.hover_state().is_hovering())
+            .and_then(|pos| self.title_at(pos.x));
+        for (index, title) in self.titles.iter().enumerate() {
+            let title_layout = layout
+                .clip(title.rect.origin.as_vector())
+                .resize_clipped(title.rect.size);
+            if state.open == Some(index) || hovered == Some(index) {
+                title
+                    .highlight
+                    .render((), title_layout.clone(), render_context)?;
+            }
+            title
+                .label
+                .render((), title_layout.clip(self.padding), render_context)?;
+        }
+
+        Ok(())
+    }
+
+    fn render_overlay(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        match state.open {
+            Some(open) => {
+                self.titles[open]
+                    .menu
+                    .render_overlay(&state.menu, layout, render_context)
+            }
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::widget::Image;
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, FramedPosition, HandleEvent, Layout, MouseSensor,
+    MouseSensorState, SizeAttr, UpdateLayout,
+};
+use amulet_core::geom::{Point, Rect, Size};
+use amulet_core::mouse::{CursorShape, MouseButton};
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+
+#[derive(Debug, Default)]
+pub struct RichLabelState {
+    mouse_sensor: MouseSensorState,
+    clicked_link: Option<String>,
+    /// The target of the link the left button was pressed on.
+    pressed_link: Option<String>,
+    hovering_link: bool,
+}
+
+impl RichLabelState {
+    /// The target of the link that was clicked since the loop started.
+    pub fn clicked_link(&self) -> Option<&str> {
+        self.clicked_link.as_deref()
+    }
+}
+
+/// Text in mixed styles, whose links can be clicked.
+pub struct RichLabel<'a> {
+    outer: (Frame, MouseSensor),
+    image: Image<'a>,
+    /// The areas of the links relative to the label, with their targets.
+    links: Vec<(Rect, String)>,
+}
+
+impl SizeAttr for RichLabel<'_> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a> RichLabel<'a> {
+    pub fn new(outer: (Frame, MouseSensor), image: Image<'a>, links: Vec<(Rect, String)>) -> Self {
+        Self {
+            outer,
+            image,
+            links,
+        }
+    }
+
+    /// The target of the link at `point`, which is relative to the label.
+    pub fn link_at(&self, point: Point) -> Option<&str> {
+        self.links
+            .iter()
+            .find(|(area, _)| area.contains(point))
+            .map(|(_, target)| target.as_str())
+    }
+
+    /// The cursor to show while the pointer is over a link.
+    pub fn cursor(&self, state: &RichLabelState) -> Option<CursorShape> {
+        state.hovering_link.then_some(CursorShape::Hand)
+    }
+
+    fn hit_link(&self, pos: &FramedPosition) -> Option<&str> {
+        pos.is_hit()
+            .then(|| self.link_at(pos.relative_position()))
+            .flatten()
+    }
+}
+
+impl HandleEvent for RichLabel<'_> {
+    type State<'a> = &'a mut RichLabelState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        if let ComponentEvent::LoopStart(..) = event {
+            state.clicked_link = None;
+        }
+
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+
+        match &event {
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) => {
+                state.pressed_link = self.hit_link(pos).map(str::to_string);
+            }
+            ComponentEvent::MouseButtonUp(MouseButton::Left, pos, ..) => {
+                let clicked = state
+                    .mouse_sensor
+                    .click_states()
+                    .has_click_completed(MouseButton::Left);
+                // Only a release on the link that was pressed counts as a click on it.
+                let pressed_link = state.pressed_link.take();
+                if clicked && self.hit_link(pos) == pressed_link.as_deref() {
+                    state.clicked_link = pressed_link;
+                }
+            }
+            ComponentEvent::MouseMotion(pos, ..) => {
+                state.hovering_link = self.hit_link(pos).is_some();
+            }
+            _ => {}
+        }
+        Ok(event)
+    }
+}
+
+impl Render for RichLabel<'_> {
+    type State<'a> = &'a RichLabelState;
+
+    fn render(
+        &self,
+        _state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
+        self.image.render((), layout, render_context)
+    }
+}
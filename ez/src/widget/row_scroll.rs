@@ -0,0 +1,120 @@
+use crate::widget::{ScrollbarState, ValueRange};
+use amulet_core::component::{FramedPosition, Layout};
+use amulet_core::geom::{Point, Rect, Size, Vector};
+
+/// How many rows a notch of the mouse wheel scrolls.
+const WHEEL_ROWS: i32 = 3;
+
+/// Scrolls rows of uniform height through a scrollbar whose range is in rows, so that its
+/// position is the index of the topmost visible row.
+///
+/// The rows fill an area of the widget, which is mirrored in right-to-left layouts along with the
+/// scrollbar next to it. Within the area, content is placed from the leading edge of the rows.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) struct RowScroll {
+    row_height: i32,
+    area: Rect,
+}
+
+impl RowScroll {
+    /// Rows of `row_height` shown in `area`, which is relative to the widget.
+    pub fn new(row_height: i32, area: Rect) -> Self {
+        Self {
+            row_height: row_height.max(1),
+            area,
+        }
+    }
+
+    pub fn first_visible_row(scrollbar: &ScrollbarState) -> usize {
+        scrollbar.position().max(0) as usize
+    }
+
+    pub fn row_height(&self) -> i32 {
+        self.row_height
+    }
+
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// The number of rows that fit into the area completely.
+    pub fn visible_rows(&self) -> usize {
+        (self.area.size.height / self.row_height).max(1) as usize
+    }
+
+    /// The layout of the area within the layout of the widget.
+    pub fn area_layout(&self, layout: &Layout) -> Layout {
+        layout
+            .place(self.area.origin.as_vector())
+            .fit(self.area.size)
+    }
+
+    /// The layout of the row `offset` rows below the topmost visible one, within the layout of the
+    /// area.
+    pub fn row_layout(&self, area_layout: &Layout, offset: usize) -> Layout {
+        area_layout
+            .place(Vector::new(0, offset as i32 * self.row_height))
+            .fit(Size::new(self.area.size.width, self.row_height))
+    }
+
+    /// Moves `pos`, which is framed by the widget, into the area.
+    pub fn enter_area(&self, pos: &FramedPosition) -> FramedPosition {
+        pos.clone()
+            .place(self.area.origin.as_vector())
+            .fit(self.area.size)
+    }
+
+    /// The position relative to the frame of `pos`, measuring x from the leading edge of the
+    /// frame, which is its right edge in right-to-left layouts.
+    pub fn leading_point(pos: &FramedPosition) -> Point {
+        let point = pos.relative_position();
+        if pos.direction().is_rtl() {
+            Point::new(pos.frame_rect().size.width - 1 - point.x, point.y)
+        } else {
+            point
+        }
+    }
+
+    /// The row at `pos`, which is framed by the widget, if the position lies within the area.
+    pub fn row_at(&self, scrollbar: &ScrollbarState, pos: &FramedPosition) -> Option<usize> {
+        let point = self.enter_area(pos).relative_position();
+        Rect::from_size(self.area.size)
+            .contains(point)
+            .then(|| Self::first_visible_row(scrollbar) + (point.y / self.row_height) as usize)
+    }
+
+    pub fn scroll_to(&self, scrollbar: &mut ScrollbarState, range: &ValueRange, first_row: i64) {
+        let first_row = first_row.clamp(0, i64::from(i32::MAX)) as i32;
+        scrollbar.set_position(range.constrain(first_row));
+    }
+
+    /// Scrolls as little as possible for the row `index` to be visible completely.
+    pub fn scroll_into_view(
+        &self,
+        scrollbar: &mut ScrollbarState,
+        range: &ValueRange,
+        index: usize,
+    ) {
+        let first = Self::first_visible_row(scrollbar);
+        let rows = self.visible_rows();
+        if index < first {
+            self.scroll_to(scrollbar, range, index as i64);
+        } else if index >= first + rows {
+            self.scroll_to(scrollbar, range, (index + 1 - rows) as i64);
+        }
+    }
+
+    pub fn scroll_by_wheel(
+        &self,
+        scrollbar: &mut ScrollbarState,
+        range: &ValueRange,
+        amount: Vector,
+    ) {
+        let first_row = Self::first_visible_row(scrollbar) as i64;
+        self.scroll_to(
+            scrollbar,
+            range,
+            first_row - i64::from(amount.y * WHEEL_ROWS),
+        );
+    }
+}
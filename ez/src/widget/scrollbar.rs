@@ -0,0 +1,140 @@
+use crate::widget::{Image, Slider, SliderState, ValueRange};
+use amulet_core::component::{ComponentEvent, Frame, HandleEvent, Layout, MouseSensor, SizeAttr};
+use amulet_core::geom::{Orientation, Size};
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+
+#[derive(Debug, Default)]
+pub struct ScrollbarState {
+    slider: SliderState,
+}
+
+impl ScrollbarState {
+    /// The scroll position, i.e. the offset of the viewport into the content.
+    pub fn position(&self) -> i32 {
+        self.slider.value()
+    }
+
+    /// Sets the scroll position programmatically. This does not trigger
+    /// [`Self::position_changed()`].
+    pub fn set_position(&mut self, position: i32) {
+        self.slider.set_value(position)
+    }
+
+    pub fn position_changed(&self) -> bool {
+        self.slider.value_changed()
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.slider.is_focused()
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.slider.is_dragging()
+    }
+}
+
+/// A [`Slider`] whose range covers the scrollable part of some content and whose thumb length
+/// reflects the visible portion of it.
+#[derive(Clone)]
+pub struct Scrollbar<'a> {
+    slider: Slider<'a>,
+    min_thumb_length: i32,
+}
+
+impl SizeAttr for Scrollbar<'_> {
+    fn size(&self) -> Size {
+        self.slider.size()
+    }
+}
+
+impl<'a> Scrollbar<'a> {
+    pub fn new(
+        outer: (Frame, MouseSensor),
+        orientation: Orientation,
+        range: ValueRange,
+        track: Image<'a>,
+        thumb: Image<'a>,
+        min_thumb_length: i32,
+    ) -> Self {
+        Self {
+            slider: Slider::new(outer, orientation, range, track, thumb),
+            min_thumb_length,
+        }
+    }
+
+    /// The range of scroll positions for content of `content_length` that is shown in a viewport
+    /// of `viewport_length`. Paging scrolls by a full viewport.
+    pub fn range_for(content_length: i32, viewport_length: i32) -> ValueRange {
+        ValueRange::new(0, content_length - viewport_length).with_page(viewport_length)
+    }
+
+    /// The length of a thumb that reflects the visible portion of content of `content_length`
+    /// shown in a viewport of `viewport_length` on a track of `track_length`.
+    pub fn thumb_length_for(
+        track_length: i32,
+        content_length: i32,
+        viewport_length: i32,
+        min_thumb_length: i32,
+    ) -> i32 {
+        let thumb_length = if content_length > viewport_length {
+            (i64::from(track_length) * i64::from(viewport_length) / i64::from(content_length))
+                as i32
+        } else {
+            track_length
+        };
+        thumb_length.clamp(min_thumb_length.min(track_length), track_length)
+    }
+
+    /// A copy of the scrollbar for content of a different length, with the same step and a thumb
+    /// resized accordingly. This lets components whose content grows and shrinks keep using the
+    /// scrollbar they were created with.
+    pub fn with_content(&self, content_length: i32, viewport_length: i32) -> Self {
+        let orientation = self.orientation();
+        let size = self.size();
+        let range = Self::range_for(content_length, viewport_length).with_step(self.range().step());
+        let thumb_length = Self::thumb_length_for(
+            orientation.length(size),
+            content_length,
+            viewport_length,
+            self.min_thumb_length,
+        );
+        Self {
+            slider: self.slider.with_range(range, thumb_length),
+            min_thumb_length: self.min_thumb_length,
+        }
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.slider.orientation()
+    }
+
+    pub fn range(&self) -> &ValueRange {
+        self.slider.range()
+    }
+}
+
+impl HandleEvent for Scrollbar<'_> {
+    type State<'a> = &'a mut ScrollbarState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        self.slider.handle_event(&mut state.slider, event)
+    }
+}
+
+impl Render for Scrollbar<'_> {
+    type State<'a> = &'a ScrollbarState;
+
+    fn render(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        self.slider.render(&state.slider, layout, render_context)
+    }
+}
@@ -0,0 +1,297 @@
+use crate::widget::Image;
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, HandleEvent, Layout, MouseSensor, MouseSensorState, SizeAttr,
+    UpdateLayout,
+};
+use amulet_core::geom::{Orientation, Rect, Size};
+use amulet_core::keyboard::Key;
+use amulet_core::mouse::MouseButton;
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+
+/// The range of values a [`Slider`] can take on.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ValueRange {
+    min: i32,
+    max: i32,
+    step: i32,
+    page: i32,
+}
+
+impl ValueRange {
+    /// Creates a new range with a step of 1 and a page of a tenth of the range.
+    pub fn new(min: i32, max: i32) -> Self {
+        let max = max.max(min);
+        Self {
+            min,
+            max,
+            step: 1,
+            page: ((max - min) / 10).max(1),
+        }
+    }
+
+    /// Sets the increment used for keyboard adjustment. All values are snapped to this increment,
+    /// counting from `min`.
+    pub fn with_step(self, step: i32) -> Self {
+        Self {
+            step: step.max(1),
+            ..self
+        }
+    }
+
+    /// Sets the increment used when clicking the track or using Page Up/Page Down.
+    pub fn with_page(self, page: i32) -> Self {
+        Self {
+            page: page.max(1),
+            ..self
+        }
+    }
+
+    pub fn min(&self) -> i32 {
+        self.min
+    }
+
+    pub fn max(&self) -> i32 {
+        self.max
+    }
+
+    pub fn step(&self) -> i32 {
+        self.step
+    }
+
+    pub fn page(&self) -> i32 {
+        self.page
+    }
+
+    /// Clamps `value` to the range and snaps it to the nearest step.
+    pub fn constrain(&self, value: i32) -> i32 {
+        let value = value.clamp(self.min, self.max);
+        let steps = (value - self.min + self.step / 2) / self.step;
+        (self.min + steps * self.step).min(self.max)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SliderState {
+    mouse_sensor: MouseSensorState,
+    value: i32,
+    grab_offset: Option<i32>,
+    focused: bool,
+    changed: bool,
+}
+
+impl SliderState {
+    pub fn new(value: i32) -> Self {
+        Self {
+            value,
+            ..Self::default()
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    /// Sets the value programmatically. This does not trigger [`Self::value_changed()`].
+    pub fn set_value(&mut self, value: i32) {
+        self.value = value;
+    }
+
+    /// Whether the user changed the value since the start of the current loop iteration.
+    pub fn value_changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Whether the slider receives keyboard input. The slider gains focus when it is clicked and
+    /// loses it when a click happens elsewhere.
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Whether the thumb is currently being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.grab_offset.is_some()
+    }
+
+    fn update(&mut self, range: &ValueRange, value: i32) {
+        let value = range.constrain(value);
+        if value != self.value {
+            self.value = value;
+            self.changed = true;
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Slider<'a> {
+    outer: (Frame, MouseSensor),
+    orientation: Orientation,
+    range: ValueRange,
+    track: Image<'a>,
+    /// Stretched along the track to `thumb_length`.
+    thumb: Image<'a>,
+    thumb_length: i32,
+}
+
+impl SizeAttr for Slider<'_> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a> Slider<'a> {
+    pub fn new(
+        outer: (Frame, MouseSensor),
+        orientation: Orientation,
+        range: ValueRange,
+        track: Image<'a>,
+        thumb: Image<'a>,
+    ) -> Self {
+        let thumb_length = orientation.length(thumb.size());
+        Self {
+            outer,
+            orientation,
+            range,
+            track,
+            thumb,
+            thumb_length,
+        }
+    }
+
+    /// A copy of the slider with a different range and thumb length.
+    pub(crate) fn with_range(&self, range: ValueRange, thumb_length: i32) -> Self {
+        Self {
+            range,
+            thumb_length,
+            ..self.clone()
+        }
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn range(&self) -> &ValueRange {
+        &self.range
+    }
+
+    fn thumb_length(&self) -> i32 {
+        self.thumb_length
+    }
+
+    /// The distance the thumb can travel along the track.
+    fn travel(&self) -> i32 {
+        (self.orientation.length(self.size()) - self.thumb_length()).max(0)
+    }
+
+    fn thumb_offset(&self, value: i32) -> i32 {
+        let span = self.range.max - self.range.min;
+        if span == 0 {
+            return 0;
+        }
+
+        let value = self.range.constrain(value) - self.range.min;
+        (i64::from(value) * i64::from(self.travel()) / i64::from(span)) as i32
+    }
+
+    fn value_at(&self, thumb_offset: i32) -> i32 {
+        let travel = self.travel();
+        if travel == 0 {
+            return self.range.min;
+        }
+
+        let span = i64::from(self.range.max - self.range.min);
+        let offset = i64::from(thumb_offset.clamp(0, travel));
+        let travel = i64::from(travel);
+        self.range.min + ((offset * span + travel / 2) / travel) as i32
+    }
+
+    fn handle_key(&self, state: &mut SliderState, key: Key) {
+        let range = &self.range;
+        let value = match key {
+            Key::Left | Key::Up => state.value - range.step,
+            Key::Right | Key::Down => state.value + range.step,
+            Key::PageUp => state.value - range.page,
+            Key::PageDown => state.value + range.page,
+            Key::Home => range.min,
+            Key::End => range.max,
+            _ => return,
+        };
+        state.update(range, value);
+    }
+}
+
+impl HandleEvent for Slider<'_> {
+    type State<'a> = &'a mut SliderState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+
+        match &event {
+            ComponentEvent::LoopStart(..) => {
+                state.changed = false;
+            }
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) => {
+                state.focused = pos.is_hit();
+                if pos.is_hit() {
+                    let at = self.orientation.along(pos.relative_position());
+                    let thumb_start = self.thumb_offset(state.value);
+                    if at < thumb_start {
+                        state.update(&self.range, state.value - self.range.page);
+                    } else if at >= thumb_start + self.thumb_length() {
+                        state.update(&self.range, state.value + self.range.page);
+                    } else {
+                        state.grab_offset = Some(at - thumb_start);
+                    }
+                }
+            }
+            ComponentEvent::MouseMotion(pos, ..) => {
+                if let Some(grab_offset) = state.grab_offset {
+                    let at = self.orientation.along(pos.relative_position());
+                    state.update(&self.range, self.value_at(at - grab_offset));
+                }
+            }
+            ComponentEvent::MouseButtonUp(MouseButton::Left, ..) => {
+                state.grab_offset = None;
+            }
+            ComponentEvent::KeyDown(key, ..) if state.focused => {
+                self.handle_key(state, *key);
+            }
+            _ => {}
+        }
+
+        Ok(event)
+    }
+}
+
+impl Render for Slider<'_> {
+    type State<'a> = &'a SliderState;
+
+    fn render(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
+        self.track.render((), layout.clone(), render_context)?;
+        let thumb_rect = Rect::new(
+            self.orientation
+                .vector(self.thumb_offset(state.value), 0)
+                .as_point(),
+            self.orientation.size(
+                self.thumb_length,
+                self.orientation.breadth(self.thumb.size()),
+            ),
+        );
+        self.thumb
+            .render_stretched(thumb_rect, layout, render_context)
+    }
+}
@@ -0,0 +1,300 @@
+use crate::widget::Image;
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, HandleEvent, Layout, MouseSensor, MouseSensorState, SizeAttr,
+    UpdateLayout,
+};
+use amulet_core::geom::{Orientation, Rect, Size};
+use amulet_core::mouse::{CursorShape, MouseButton};
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+
+#[derive(Debug)]
+pub struct SplitState {
+    mouse_sensor: MouseSensorState,
+    ratio: f32,
+    /// The offset of the pointer into the handle while it is being dragged.
+    grab_offset: Option<i32>,
+    hovering: bool,
+    changed: bool,
+}
+
+impl Default for SplitState {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+impl SplitState {
+    /// Creates a state that gives `ratio` of the available space to the first pane.
+    pub fn new(ratio: f32) -> Self {
+        Self {
+            mouse_sensor: MouseSensorState::default(),
+            ratio: ratio.clamp(0.0, 1.0),
+            grab_offset: None,
+            hovering: false,
+            changed: false,
+        }
+    }
+
+    /// The share of the available space that goes to the first pane, before the pane sizes are
+    /// limited. Store this to restore the layout later.
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Sets the ratio programmatically. This does not trigger [`Self::ratio_changed()`].
+    pub fn set_ratio(&mut self, ratio: f32) {
+        self.ratio = ratio.clamp(0.0, 1.0);
+    }
+
+    /// Whether the user moved the handle since the start of the current loop iteration.
+    pub fn ratio_changed(&self) -> bool {
+        self.changed
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.grab_offset.is_some()
+    }
+}
+
+/// Two panes next to each other along `orientation`, separated by a handle that can be dragged
+/// to distribute the space between them.
+///
+/// Each pane is laid out in its own rectangle, so that its layout is clipped to it and the events
+/// it receives are relative to it. The sizes of the panes can be limited, in which case the limits
+/// take precedence over the ratio kept in [`SplitState`].
+pub struct Split<'a, A, B> {
+    outer: (Frame, MouseSensor),
+    orientation: Orientation,
+    handle: Image<'a>,
+    first: A,
+    second: B,
+    min_sizes: (i32, i32),
+    max_sizes: (Option<i32>, Option<i32>),
+}
+
+impl<A, B> SizeAttr for Split<'_, A, B> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a, A, B> Split<'a, A, B> {
+    /// The thickness of the handle is its length along `orientation`, and it is stretched across
+    /// the split.
+    pub fn new(
+        outer: (Frame, MouseSensor),
+        orientation: Orientation,
+        handle: Image<'a>,
+        first: A,
+        second: B,
+    ) -> Self {
+        Self {
+            outer,
+            orientation,
+            handle,
+            first,
+            second,
+            min_sizes: (0, 0),
+            max_sizes: (None, None),
+        }
+    }
+
+    /// Sets the minimum lengths of the panes along the orientation.
+    pub fn with_min_sizes(self, first: i32, second: i32) -> Self {
+        Self {
+            min_sizes: (first.max(0), second.max(0)),
+            ..self
+        }
+    }
+
+    /// Sets the maximum lengths of the panes along the orientation.
+    pub fn with_max_sizes(self, first: Option<i32>, second: Option<i32>) -> Self {
+        Self {
+            max_sizes: (first, second),
+            ..self
+        }
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// The cursor to show while the pointer is over the handle or dragging it.
+    pub fn cursor(&self, state: &SplitState) -> Option<CursorShape> {
+        (state.hovering || state.is_dragging()).then_some(match self.orientation {
+            Orientation::Horizontal => CursorShape::ResizeHorizontal,
+            Orientation::Vertical => CursorShape::ResizeVertical,
+        })
+    }
+
+    fn thickness(&self) -> i32 {
+        self.orientation.length(self.handle.size())
+    }
+
+    /// The length that is distributed between the panes.
+    fn available(&self) -> i32 {
+        (self.orientation.length(self.size()) - self.thickness()).max(0)
+    }
+
+    /// Limits the length of the first pane, so that both panes respect their limits as far as
+    /// possible. The minimum sizes win over the maximum sizes.
+    fn constrain(&self, first: i32) -> i32 {
+        let available = self.available();
+        let (min_first, min_second) = self.min_sizes;
+        let (max_first, max_second) = self.max_sizes;
+        let lower = max_second.map_or(0, |max| available - max).max(min_first);
+        let upper = max_first.unwrap_or(available).min(available - min_second);
+        first.min(upper).max(lower).clamp(0, available)
+    }
+
+    fn first_length(&self, state: &SplitState) -> i32 {
+        self.constrain((state.ratio * self.available() as f32).round() as i32)
+    }
+
+    /// The rectangles of the first pane, the handle and the second pane.
+    fn rects(&self, state: &SplitState) -> (Rect, Rect, Rect) {
+        let size = self.size();
+        let breadth = self.orientation.breadth(size);
+        let first = self.first_length(state);
+        let second = self.available() - first;
+        let thickness = self.thickness();
+        let o = self.orientation;
+        (
+            Rect::from_size(o.size(first, breadth)),
+            Rect::new(o.vector(first, 0).as_point(), o.size(thickness, breadth)),
+            Rect::new(
+                o.vector(first + thickness, 0).as_point(),
+                o.size(second, breadth),
+            ),
+        )
+    }
+}
+
+impl<A, B> HandleEvent for Split<'_, A, B>
+where
+    A: HandleEvent,
+    B: HandleEvent,
+{
+    type State<'a> = (&'a mut SplitState, A::State<'a>, B::State<'a>);
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        let (state, first_state, second_state) = state;
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+
+        let (_, handle_rect, _) = self.rects(state);
+        match &event {
+            ComponentEvent::LoopStart(..) => {
+                state.changed = false;
+            }
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) => {
+                let point = pos.relative_position();
+                if pos.is_hit() && handle_rect.contains(point) {
+                    let along = self.orientation.along(point);
+                    state.grab_offset = Some(along - self.first_length(state));
+                }
+            }
+            ComponentEvent::MouseMotion(pos, ..) => {
+                let point = pos.relative_position();
+                state.hovering = pos.is_hit() && handle_rect.contains(point);
+                if let Some(grab_offset) = state.grab_offset {
+                    let available = self.available();
+                    let first = self.constrain(self.orientation.along(point) - grab_offset);
+                    let ratio = if available > 0 {
+                        first as f32 / available as f32
+                    } else {
+                        state.ratio
+                    };
+                    if ratio != state.ratio {
+                        state.ratio = ratio;
+                        state.changed = true;
+                    }
+                }
+            }
+            ComponentEvent::MouseButtonUp(MouseButton::Left, ..) => {
+                state.grab_offset = None;
+            }
+            _ => {}
+        }
+
+        let (first_rect, _, second_rect) = self.rects(state);
+        self.first
+            .handle_event(first_state, event.clone().resize(first_rect.size))?;
+        self.second.handle_event(
+            second_state,
+            event
+                .clone()
+                .clip(second_rect.origin.as_vector())
+                .resize(second_rect.size),
+        )?;
+
+        Ok(event)
+    }
+}
+
+impl<A, B> Render for Split<'_, A, B>
+where
+    A: Render,
+    B: Render,
+    for<'s> A::State<'s>: Copy,
+    for<'s> B::State<'s>: Copy,
+{
+    type State<'a> = (&'a SplitState, A::State<'a>, B::State<'a>);
+
+    fn render(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let (state, first_state, second_state) = state;
+        let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
+        let (first_rect, handle_rect, second_rect) = self.rects(state);
+
+        self.first.render(
+            first_state,
+            layout.resize_clipped(first_rect.size),
+            render_context,
+        )?;
+        self.handle
+            .render_stretched(handle_rect, layout.clone(), render_context)?;
+        self.second.render(
+            second_state,
+            layout
+                .clip(second_rect.origin.as_vector())
+                .resize_clipped(second_rect.size),
+            render_context,
+        )
+    }
+
+    fn render_overlay(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let (state, first_state, second_state) = state;
+        let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
+        let (first_rect, _, second_rect) = self.rects(state);
+
+        self.first.render_overlay(
+            first_state,
+            layout.resize_clipped(first_rect.size),
+            render_context,
+        )?;
+        self.second.render_overlay(
+            second_state,
+            layout
+                .clip(second_rect.origin.as_vector())
+                .resize_clipped(second_rect.size),
+            render_context,
+        )
+    }
+}
@@ -0,0 +1,669 @@
+use crate::widget::row_scroll::RowScroll;
+use crate::widget::{Image, Scrollbar, ScrollbarState, TextInput, TextInputState};
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, FramedPosition, HandleEvent, Layout, MouseSensor,
+    MouseSensorState, Position, SizeAttr, UpdateLayout,
+};
+use amulet_core::geom::{Rect, Size, Vector};
+use amulet_core::keyboard::Key;
+use amulet_core::mouse::MouseButton;
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+
+/// How far from the border between two column headers a press starts resizing the column.
+const RESIZE_MARGIN: i32 = 3;
+
+const MIN_COLUMN_WIDTH: i32 = 16;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    pub fn reversed(self) -> Self {
+        match self {
+            SortOrder::Ascending => SortOrder::Descending,
+            SortOrder::Descending => SortOrder::Ascending,
+        }
+    }
+}
+
+/// Identifies a cell of a [`Table`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct CellRef {
+    pub row: usize,
+    pub column: usize,
+}
+
+/// A cell as passed to the cell-rendering callback of a [`Table`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TableCell {
+    pub row: usize,
+    pub column: usize,
+    /// Whether the row of the cell is selected.
+    pub selected: bool,
+}
+
+/// Renders the content of a cell into the provided layout.
+pub type RenderCell<'a> = Box<dyn Fn(TableCell, Layout, &mut RenderContext) -> VuiResult<()> + 'a>;
+
+/// Describes a column when creating a table.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TableColumn {
+    pub title: String,
+    /// The initial width, which the user may change by dragging the border of the header.
+    pub width: i32,
+    pub sortable: bool,
+    pub editable: bool,
+}
+
+impl TableColumn {
+    pub fn new(title: &str, width: i32) -> Self {
+        Self {
+            title: title.to_string(),
+            width,
+            sortable: false,
+            editable: false,
+        }
+    }
+
+    pub fn sortable(self) -> Self {
+        Self {
+            sortable: true,
+            ..self
+        }
+    }
+
+    pub fn editable(self) -> Self {
+        Self {
+            editable: true,
+            ..self
+        }
+    }
+}
+
+pub(crate) struct TableHeader<'a> {
+    pub(crate) label: Image<'a>,
+    pub(crate) width: i32,
+    pub(crate) sortable: bool,
+    pub(crate) editable: bool,
+}
+
+/// The geometry of a [`Table`], relative to the table.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct TableMetrics {
+    pub(crate) size: Size,
+    /// The area the rows are laid out in. The header covers the table above it.
+    pub(crate) rows_rect: Rect,
+    /// The offset of cell and header content within the cell.
+    pub(crate) padding: Vector,
+}
+
+pub(crate) struct TableImages<'a> {
+    pub(crate) background: Image<'a>,
+    pub(crate) header: Image<'a>,
+    /// A vertical line as high as the header, drawn between columns.
+    pub(crate) header_separator: Image<'a>,
+    pub(crate) sort_ascending: Image<'a>,
+    pub(crate) sort_descending: Image<'a>,
+    pub(crate) selection: Image<'a>,
+    /// Lines as wide as the body and as high as a row, from which the outline of the current cell
+    /// is drawn.
+    pub(crate) cell_edge_h: Image<'a>,
+    pub(crate) cell_edge_v: Image<'a>,
+}
+
+#[derive(Debug, Default)]
+pub struct TableState {
+    mouse_sensor: MouseSensorState,
+    header_sensor: MouseSensorState,
+    scrollbar: ScrollbarState,
+    widths: Vec<i32>,
+    /// The column being resized along with its width when the resizing started.
+    resizing: Option<(usize, i32)>,
+    pressed_header: Option<usize>,
+    sort: Option<(usize, SortOrder)>,
+    sort_changed: bool,
+    selected: Option<usize>,
+    current_column: usize,
+    selection_changed: bool,
+    /// The cell the last press landed on, which a double click edits.
+    pressed_cell: Option<CellRef>,
+    editing: Option<CellRef>,
+    editor: TextInputState,
+    edit_started: Option<CellRef>,
+    committed: Option<CellRef>,
+    focused: bool,
+}
+
+impl TableState {
+    pub fn selected_row(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Selects `row` without triggering [`Self::selection_changed()`].
+    pub fn set_selected_row(&mut self, row: Option<usize>) {
+        self.selected = row;
+    }
+
+    /// Whether the user selected another row during the current loop iteration.
+    pub fn selection_changed(&self) -> bool {
+        self.selection_changed
+    }
+
+    /// The column the keyboard operates on, e.g. when starting to edit.
+    pub fn current_column(&self) -> usize {
+        self.current_column
+    }
+
+    /// The column the rows are sorted by and the order. Sorting is up to the application, which
+    /// is expected to map the rows of the table to its data accordingly.
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+
+    /// Sets the sort indicator programmatically. This does not trigger [`Self::sort_changed()`].
+    pub fn set_sort(&mut self, sort: Option<(usize, SortOrder)>) {
+        self.sort = sort;
+    }
+
+    /// Whether the user clicked a header to change the sorting during the current loop iteration.
+    pub fn sort_changed(&self) -> bool {
+        self.sort_changed
+    }
+
+    /// The current widths of the columns, or `None` if the user hasn't resized any.
+    pub fn column_widths(&self) -> Option<&[i32]> {
+        (!self.widths.is_empty()).then_some(self.widths.as_slice())
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The topmost row that is scrolled into view.
+    pub fn first_visible_row(&self) -> usize {
+        RowScroll::first_visible_row(&self.scrollbar)
+    }
+
+    /// The cell being edited, if any.
+    pub fn editing(&self) -> Option<CellRef> {
+        self.editing
+    }
+
+    /// The cell the user started to edit during the current loop iteration. The editor starts out
+    /// empty, the application is expected to fill in the value of the cell through
+    /// [`Self::set_edit_text()`].
+    pub fn edit_started(&self) -> Option<CellRef> {
+        self.edit_started
+    }
+
+    pub fn edit_text(&self) -> &str {
+        self.editor.text()
+    }
+
+    pub fn set_edit_text(&mut self, text: &str) {
+        self.editor.set_text(text);
+    }
+
+    /// Passes text typed by the user to the editor. Has no effect unless a cell is being edited.
+    pub fn input_text(&mut self, text: &str) {
+        if self.editing.is_some() {
+            self.editor.update(text);
+        }
+    }
+
+    /// The cell whose editing the user completed during the current loop iteration. Its new value
+    /// is [`Self::edit_text()`].
+    pub fn committed(&self) -> Option<CellRef> {
+        self.committed
+    }
+
+    fn commit(&mut self) {
+        if let Some(cell) = self.editing.take() {
+            self.committed = Some(cell);
+        }
+    }
+}
+
+/// A table with column headers and uniformly sized rows, of which only the visible ones are
+/// rendered.
+///
+/// Cells are drawn by a callback, which receives a layout clipped to the cell. Dragging the border
+/// between two headers resizes the column left of it, and clicking the header of a sortable column
+/// sorts by it, or reverses the order if it is sorted by already.
+///
+/// A row is selected by clicking it or with the arrow keys, Page Up/Down, Home and End, while
+/// Left and Right choose the current column. Cells of editable columns are edited in a
+/// [`TextInput`] after a double click, Enter or F2. Enter commits the edit, as does clicking
+/// elsewhere, and Escape cancels it.
+///
+/// Columns that don't fit into the table are clipped, there is no horizontal scrolling.
+pub struct Table<'a> {
+    outer: (Frame, MouseSensor),
+    header: (Frame, MouseSensor),
+    scrollbar: (Position, Scrollbar<'a>),
+    columns: Vec<TableHeader<'a>>,
+    images: TableImages<'a>,
+    editor: TextInput<'a>,
+    rows: RowScroll,
+    padding: Vector,
+    row_count: usize,
+    render_cell: RenderCell<'a>,
+}
+
+impl SizeAttr for Table<'_> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a> Table<'a> {
+    /// The height of the rows is the height of the selection image. The range of `scrollbar` must
+    /// be in rows.
+    pub(crate) fn new(
+        columns: Vec<TableHeader<'a>>,
+        images: TableImages<'a>,
+        editor: TextInput<'a>,
+        metrics: TableMetrics,
+        scrollbar: (Position, Scrollbar<'a>),
+        row_count: usize,
+        render_cell: RenderCell<'a>,
+    ) -> Self {
+        let rows_rect = metrics.rows_rect;
+        let header_size = Size::new(rows_rect.limit().x, rows_rect.origin.y);
+        let rows = RowScroll::new(images.selection.size().height, rows_rect);
+        Self {
+            outer: (Frame::new(metrics.size), MouseSensor::new()),
+            header: (Frame::new(header_size), MouseSensor::new()),
+            scrollbar,
+            columns,
+            images,
+            editor,
+            rows,
+            padding: metrics.padding,
+            row_count,
+            render_cell,
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    fn column_width(&self, state: &TableState, column: usize) -> i32 {
+        state
+            .widths
+            .get(column)
+            .copied()
+            .unwrap_or(self.columns[column].width)
+    }
+
+    /// The horizontal extent of every column, relative to the leading edge of the table.
+    fn column_spans(&self, state: &TableState) -> Vec<(i32, i32)> {
+        let mut x = self.rows.area().origin.x;
+        (0..self.columns.len())
+            .map(|column| {
+                let width = self.column_width(state, column);
+                x += width;
+                (x - width, width)
+            })
+            .collect()
+    }
+
+    fn column_at(&self, state: &TableState, x: i32) -> Option<usize> {
+        self.column_spans(state)
+            .iter()
+            .position(|&(start, width)| x >= start && x < start + width)
+    }
+
+    /// The column whose right border is near `x`.
+    fn border_at(&self, state: &TableState, x: i32) -> Option<usize> {
+        self.column_spans(state)
+            .iter()
+            .rposition(|&(start, width)| (x - (start + width)).abs() <= RESIZE_MARGIN)
+    }
+
+    fn row_at(&self, state: &TableState, pos: &FramedPosition) -> Option<usize> {
+        self.rows
+            .row_at(&state.scrollbar, pos)
+            .filter(|&index| index < self.row_count)
+    }
+
+    /// The rectangle of the cell relative to the leading edge of the table, if its row is visible.
+    fn cell_rect(&self, state: &TableState, cell: CellRef) -> Option<Rect> {
+        let first = state.first_visible_row();
+        let offset = cell.row.checked_sub(first)?;
+        if offset > self.rows.visible_rows() {
+            return None;
+        }
+
+        let (x, width) = *self.column_spans(state).get(cell.column)?;
+        let y = self.rows.area().origin.y + offset as i32 * self.rows.row_height();
+        Some(Rect::from_xywh(x, y, width, self.rows.row_height()))
+    }
+
+    fn select(&self, state: &mut TableState, row: usize) {
+        if state.selected != Some(row) {
+            state.selected = Some(row);
+            state.selection_changed = true;
+        }
+        let range = self.scrollbar.1.range();
+        self.rows.scroll_into_view(&mut state.scrollbar, range, row);
+    }
+
+    fn start_edit(&self, state: &mut TableState, cell: CellRef) {
+        if !self.columns[cell.column].editable {
+            return;
+        }
+
+        state.commit();
+        state.editing = Some(cell);
+        state.edit_started = Some(cell);
+        state.editor = TextInputState::default();
+        state.editor.set_focused(true);
+    }
+
+    fn handle_header(&self, state: &mut TableState, event: &ComponentEvent) -> VuiResult<()> {
+        let event = self
+            .header
+            .as_chain()
+            .handle_event(((), &mut state.header_sensor), event.clone())?;
+
+        match &event {
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) if pos.is_hit() => {
+                let x = RowScroll::leading_point(pos).x;
+                if let Some(column) = self.border_at(state, x) {
+                    state.resizing = Some((column, self.column_width(state, column)));
+                    state.pressed_header = None;
+                } else {
+                    state.pressed_header = self.column_at(state, x);
+                }
+            }
+            ComponentEvent::MouseMotion(pos, ..) => {
+                if let Some((column, start_width)) = state.resizing {
+                    let mut offset = state.header_sensor.drag_states().offset(MouseButton::Left);
+                    // The borders trail the columns, so they are dragged leftwards to widen them.
+                    if pos.direction().is_rtl() {
+                        offset.x = -offset.x;
+                    }
+                    if state.widths.len() != self.columns.len() {
+                        state.widths = (0..self.columns.len())
+                            .map(|column| self.column_width(state, column))
+                            .collect();
+                    }
+                    state.widths[column] = (start_width + offset.x).max(MIN_COLUMN_WIDTH);
+                }
+            }
+            ComponentEvent::MouseButtonUp(MouseButton::Left, pos, ..) => {
+                let resized = state.resizing.take().is_some();
+                let clicked = state
+                    .header_sensor
+                    .click_states()
+                    .has_click_completed(MouseButton::Left)
+                    && !state
+                        .header_sensor
+                        .drag_states()
+                        .has_drag_ended(MouseButton::Left);
+                let column = self.column_at(state, RowScroll::leading_point(pos).x);
+                if clicked && !resized && column.is_some() && column == state.pressed_header {
+                    let column = column.unwrap_or_default();
+                    if self.columns[column].sortable {
+                        state.sort = match state.sort {
+                            Some((sorted, order)) if sorted == column => {
+                                Some((column, order.reversed()))
+                            }
+                            _ => Some((column, SortOrder::Ascending)),
+                        };
+                        state.sort_changed = true;
+                    }
+                }
+                state.pressed_header = None;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn handle_key(&self, state: &mut TableState, key: Key) {
+        if state.editing.is_some() {
+            match key {
+                Key::Enter => state.commit(),
+                Key::Escape => state.editing = None,
+                _ => {}
+            }
+            return;
+        }
+
+        if self.row_count == 0 {
+            return;
+        }
+
+        let last = self.row_count - 1;
+        let page = self.rows.visible_rows().saturating_sub(1).max(1);
+        let selected = state.selected;
+        let row = match key {
+            Key::Up => selected.map_or(0, |row| row.saturating_sub(1)),
+            Key::Down => selected.map_or(0, |row| (row + 1).min(last)),
+            Key::PageUp => selected.map_or(0, |row| row.saturating_sub(page)),
+            Key::PageDown => selected.map_or(0, |row| (row + page).min(last)),
+            Key::Home => 0,
+            Key::End => last,
+            Key::Left => {
+                state.current_column = state.current_column.saturating_sub(1);
+                return;
+            }
+            Key::Right => {
+                state.current_column =
+                    (state.current_column + 1).min(self.columns.len().saturating_sub(1));
+                return;
+            }
+            Key::Enter | Key::Function(2) => {
+                if let Some(row) = selected {
+                    let column = state.current_column;
+                    self.start_edit(state, CellRef { row, column });
+                }
+                return;
+            }
+            _ => return,
+        };
+        self.select(state, row);
+    }
+}
+
+impl HandleEvent for Table<'_> {
+    type State<'a> = &'a mut TableState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+        self.scrollbar
+            .as_chain()
+            .handle_event(((), &mut state.scrollbar), event.clone())?;
+        self.handle_header(state, &event)?;
+
+        let editor_rect = state.editing.and_then(|cell| self.cell_rect(state, cell));
+        if let Some(rect) = editor_rect {
+            self.editor.handle_event(
+                &mut state.editor,
+                event.clone().place(rect.origin.as_vector()).fit(rect.size),
+            )?;
+        }
+
+        match &event {
+            ComponentEvent::LoopStart(..) => {
+                state.selection_changed = false;
+                state.sort_changed = false;
+                state.edit_started = None;
+                state.committed = None;
+            }
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) => {
+                state.focused = pos.is_hit();
+                let point = RowScroll::leading_point(pos);
+                let in_editor = editor_rect.is_some_and(|rect| rect.contains(point));
+                if !in_editor {
+                    state.commit();
+                }
+
+                state.pressed_cell = None;
+                let row = pos.is_hit().then(|| self.row_at(state, pos)).flatten();
+                if let (Some(row), false) = (row, in_editor) {
+                    if let Some(column) = self.column_at(state, point.x) {
+                        state.current_column = column;
+                        state.pressed_cell = Some(CellRef { row, column });
+                    }
+                    self.select(state, row);
+                }
+            }
+            ComponentEvent::MouseWheel(amount, pos, ..) if pos.is_hit() => {
+                let range = self.scrollbar.1.range();
+                self.rows
+                    .scroll_by_wheel(&mut state.scrollbar, range, *amount);
+            }
+            ComponentEvent::KeyDown(key, ..) if state.focused => {
+                self.handle_key(state, *key);
+            }
+            _ => {}
+        }
+
+        if state
+            .mouse_sensor
+            .click_states()
+            .has_double_click_completed(MouseButton::Left)
+        {
+            if let Some(cell) = state.pressed_cell {
+                self.start_edit(state, cell);
+            }
+        }
+
+        Ok(event)
+    }
+}
+
+impl Render for Table<'_> {
+    type State<'a> = &'a TableState;
+
+    fn render(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
+        let images = &self.images;
+        images
+            .background
+            .render((), layout.clone(), render_context)?;
+        images.header.render((), layout.clone(), render_context)?;
+        self.scrollbar
+            .render(((), &state.scrollbar), layout.clone(), render_context)?;
+
+        let spans = self.column_spans(state);
+        let header_height = self.rows.area().origin.y;
+        let header_layout = layout.fit(self.header.0.size());
+        for (column, (header, &(x, width))) in self.columns.iter().zip(&spans).enumerate() {
+            let column_layout = header_layout
+                .place(Vector::new(x, 0))
+                .fit(Size::new(width, header_height));
+            header
+                .label
+                .render((), column_layout.place(self.padding), render_context)?;
+
+            let indicator = match state.sort {
+                Some((sorted, SortOrder::Ascending)) if sorted == column => {
+                    Some(&images.sort_ascending)
+                }
+                Some((sorted, SortOrder::Descending)) if sorted == column => {
+                    Some(&images.sort_descending)
+                }
+                _ => None,
+            };
+            if let Some(indicator) = indicator {
+                let size = indicator.size();
+                let offset = Vector::new(
+                    (width - size.width - self.padding.x).max(0),
+                    (header_height - size.height) / 2,
+                );
+                indicator.render((), column_layout.place(offset).fit(size), render_context)?;
+            }
+
+            images.header_separator.render(
+                (),
+                header_layout
+                    .place(Vector::new(x + width - 1, 0))
+                    .fit(images.header_separator.size()),
+                render_context,
+            )?;
+        }
+
+        let rows_layout = self.rows.area_layout(&layout);
+        let area_x = self.rows.area().origin.x;
+        let row_height = self.rows.row_height();
+        let first = state.first_visible_row();
+        // The last row may be partially visible.
+        let end = (first + self.rows.visible_rows() + 1).min(self.row_count);
+        for (offset, row) in (first..end).enumerate() {
+            let row_layout = self.rows.row_layout(&rows_layout, offset);
+            let selected = state.selected == Some(row);
+            if selected {
+                images
+                    .selection
+                    .render((), row_layout.clone(), render_context)?;
+            }
+
+            for (column, &(x, width)) in spans.iter().enumerate() {
+                let cell_layout = row_layout
+                    .place(Vector::new(x - area_x, 0))
+                    .fit(Size::new(width, row_height));
+                let cell = CellRef { row, column };
+
+                if state.editing == Some(cell) {
+                    self.editor
+                        .render(&state.editor, cell_layout, render_context)?;
+                    continue;
+                }
+
+                let table_cell = TableCell {
+                    row,
+                    column,
+                    selected,
+                };
+                (self.render_cell)(table_cell, cell_layout.place(self.padding), render_context)?;
+
+                if selected && state.focused && column == state.current_column {
+                    images
+                        .cell_edge_h
+                        .render((), cell_layout.clone(), render_context)?;
+                    images.cell_edge_h.render(
+                        (),
+                        cell_layout.clip(Vector::new(0, row_height - 1)),
+                        render_context,
+                    )?;
+                    images
+                        .cell_edge_v
+                        .render((), cell_layout.clone(), render_context)?;
+                    images.cell_edge_v.render(
+                        (),
+                        cell_layout.clip(Vector::new(width - 1, 0)),
+                        render_context,
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,528 @@
+use crate::widget::Image;
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, HandleEvent, Layout, MouseSensor, MouseSensorState, Position,
+    SizeAttr, UpdateLayout,
+};
+use amulet_core::geom::{Size, Vector};
+use amulet_core::keyboard::Key;
+use amulet_core::mouse::MouseButton;
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+
+/// A tab in the strip of a [`Tabs`] container. Its backgrounds determine its size.
+pub(crate) struct Tab<'a> {
+    pub(crate) label: Image<'a>,
+    pub(crate) normal: Image<'a>,
+    pub(crate) active: Image<'a>,
+}
+
+pub(crate) struct TabsImages<'a> {
+    pub(crate) strip: Image<'a>,
+    pub(crate) page: Image<'a>,
+    pub(crate) close: Image<'a>,
+    /// As wide as a scroll button and as high as the strip.
+    pub(crate) scroll_button: Image<'a>,
+    pub(crate) scroll_back: Image<'a>,
+    pub(crate) scroll_forward: Image<'a>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct TabsMetrics {
+    pub(crate) size: Size,
+    pub(crate) strip_height: i32,
+    /// The offset of the label within the tab.
+    pub(crate) label: Vector,
+    /// The width of the area at the right end of a tab that closes it, or 0 if tabs can't be
+    /// closed.
+    pub(crate) close_width: i32,
+    pub(crate) scroll_button_width: i32,
+    /// The inset of the pages from the edges of the area below the strip.
+    pub(crate) page_padding: Vector,
+}
+
+/// What a press on the tab strip started.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum StripTarget {
+    Tab(usize),
+    Close(usize),
+    ScrollBack,
+    ScrollForward,
+}
+
+#[derive(Debug, Default)]
+pub struct TabsState {
+    strip_sensor: MouseSensorState,
+    /// The pages in the order of their tabs, without the closed ones. `None` until the first
+    /// event.
+    order: Option<Vec<usize>>,
+    active: Option<usize>,
+    /// The position in `order` of the leftmost visible tab.
+    first_tab: usize,
+    pressed: Option<StripTarget>,
+    active_changed: bool,
+    closed: Option<usize>,
+}
+
+impl TabsState {
+    /// The index of the page that is shown, or `None` if all tabs were closed.
+    pub fn active_page(&self) -> Option<usize> {
+        self.active
+    }
+
+    /// Shows another page programmatically. This does not trigger [`Self::active_changed()`].
+    pub fn set_active_page(&mut self, page: usize) {
+        self.active = Some(page);
+    }
+
+    /// Whether the user switched to another page during the current loop iteration.
+    pub fn active_changed(&self) -> bool {
+        self.active_changed
+    }
+
+    /// The page whose tab the user closed during the current loop iteration. Closed pages keep
+    /// their state and can be brought back with [`Self::reopen_page()`].
+    pub fn closed_page(&self) -> Option<usize> {
+        self.closed
+    }
+
+    /// The indices of the pages whose tabs are open, in the order they are shown.
+    pub fn tab_order(&self) -> &[usize] {
+        self.order.as_deref().unwrap_or_default()
+    }
+
+    /// Adds the tab of a closed page back at the end of the strip and shows the page.
+    pub fn reopen_page(&mut self, page: usize) {
+        if let Some(order) = &mut self.order {
+            if !order.contains(&page) {
+                order.push(page);
+            }
+        }
+        self.active = Some(page);
+    }
+
+    fn activate(&mut self, page: usize) {
+        if self.active != Some(page) {
+            self.active = Some(page);
+            self.active_changed = true;
+        }
+    }
+}
+
+/// A container that shows one of several pages, chosen by a strip of tabs along its top.
+///
+/// Only the active page receives events and is rendered, but every page keeps its own state,
+/// which is passed as a slice holding the state of every page. The pages share a type, so pages
+/// with different content are best represented by an enum.
+///
+/// Tabs can be reordered by dragging them and, if the theme created them so, closed with the
+/// button at their right end. Ctrl+Tab and Ctrl+Shift+Tab switch to the next and previous tab.
+/// When the tabs don't fit, buttons at the end of the strip scroll it, as does the mouse wheel.
+pub struct Tabs<'a, P> {
+    outer: Frame,
+    strip: (Frame, MouseSensor),
+    page_area: (Position, Frame),
+    tabs: Vec<Tab<'a>>,
+    pages: Vec<P>,
+    images: TabsImages<'a>,
+    metrics: TabsMetrics,
+}
+
+impl<P> SizeAttr for Tabs<'_, P> {
+    fn size(&self) -> Size {
+        self.metrics.size
+    }
+}
+
+impl<'a, P> Tabs<'a, P> {
+    /// The pages are laid out below the strip, and `tabs` must hold a tab for each of them.
+    pub(crate) fn new(
+        tabs: Vec<Tab<'a>>,
+        pages: Vec<P>,
+        images: TabsImages<'a>,
+        metrics: TabsMetrics,
+    ) -> Self {
+        let size = metrics.size;
+        let strip_size = Size::new(size.width, metrics.strip_height);
+        let padding = metrics.page_padding;
+        let page_size = Size::new(
+            size.width - padding.x * 2,
+            size.height - metrics.strip_height - padding.y * 2,
+        );
+        Self {
+            outer: Frame::new(size),
+            strip: (Frame::new(strip_size), MouseSensor::new()),
+            page_area: (
+                Position::new((padding.x, metrics.strip_height + padding.y).into()),
+                Frame::new(page_size),
+            ),
+            tabs,
+            pages,
+            images,
+            metrics,
+        }
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The size available to the pages.
+    pub fn page_size(&self) -> Size {
+        self.page_area.1.size()
+    }
+
+    fn active_page(&self, state: &TabsState) -> Option<usize> {
+        state.active.filter(|&page| page < self.pages.len())
+    }
+
+    fn tab_width(&self, page: usize) -> i32 {
+        self.tabs[page].normal.size().width
+    }
+
+    fn overflows(&self, state: &TabsState) -> bool {
+        let total: i32 = state.tab_order().iter().map(|&p| self.tab_width(p)).sum();
+        total > self.metrics.size.width
+    }
+
+    /// The width of the strip that shows tabs, excluding the scroll buttons.
+    fn visible_width(&self, state: &TabsState) -> i32 {
+        if self.overflows(state) {
+            self.metrics.size.width - 2 * self.metrics.scroll_button_width
+        } else {
+            self.metrics.size.width
+        }
+    }
+
+    /// The page, offset and width of the tabs from the leftmost visible one on.
+    fn tab_spans(&self, state: &TabsState) -> Vec<(usize, i32, i32)> {
+        let mut x = 0;
+        state
+            .tab_order()
+            .iter()
+            .skip(state.first_tab)
+            .map(|&page| {
+                let width = self.tab_width(page);
+                x += width;
+                (page, x - width, width)
+            })
+            .collect()
+    }
+
+    fn target_at(&self, state: &TabsState, x: i32) -> Option<StripTarget> {
+        let visible_width = self.visible_width(state);
+        if x >= visible_width {
+            return if x < visible_width + self.metrics.scroll_button_width {
+                Some(StripTarget::ScrollBack)
+            } else {
+                Some(StripTarget::ScrollForward)
+            };
+        }
+
+        let (page, start, width) = self
+            .tab_spans(state)
+            .into_iter()
+            .find(|&(_, start, width)| x >= start && x < start + width)?;
+        if x >= start + width - self.metrics.close_width {
+            Some(StripTarget::Close(page))
+        } else {
+            Some(StripTarget::Tab(page))
+        }
+    }
+
+    /// Scrolls the strip by `delta` tabs, not further than needed to show the last one.
+    fn scroll(&self, state: &mut TabsState, delta: i32) {
+        let count = state.tab_order().len();
+        let first_tab = (state.first_tab as i64 + i64::from(delta)).max(0) as usize;
+        state.first_tab = first_tab.min(count.saturating_sub(1));
+        while state.first_tab > 0 {
+            let previous = state.tab_order()[state.first_tab - 1];
+            let end = self.tab_spans(state).last().map_or(0, |&(_, x, w)| x + w);
+            if end + self.tab_width(previous) > self.visible_width(state) {
+                break;
+            }
+            state.first_tab -= 1;
+        }
+    }
+
+    fn scroll_into_view(&self, state: &mut TabsState, page: usize) {
+        let Some(position) = state.tab_order().iter().position(|&p| p == page) else {
+            return;
+        };
+        if position < state.first_tab {
+            state.first_tab = position;
+        }
+        while state.first_tab < position {
+            let spans = self.tab_spans(state);
+            let (_, x, width) = spans[position - state.first_tab];
+            if x + width <= self.visible_width(state) {
+                break;
+            }
+            state.first_tab += 1;
+        }
+    }
+
+    fn activate(&self, state: &mut TabsState, page: usize) {
+        state.activate(page);
+        self.scroll_into_view(state, page);
+    }
+
+    fn close(&self, state: &mut TabsState, page: usize) {
+        let Some(order) = &mut state.order else {
+            return;
+        };
+        let Some(position) = order.iter().position(|&p| p == page) else {
+            return;
+        };
+
+        order.remove(position);
+        let neighbor = order.get(position).or(order.last()).copied();
+        state.closed = Some(page);
+        if state.active == Some(page) {
+            state.active = neighbor;
+            state.active_changed = true;
+        }
+        self.scroll(state, 0);
+    }
+
+    /// The page whose tab the tab of `page` takes the place of when dragged to `x`. The pointer
+    /// has to cross the middle of that tab, so that tabs of different widths don't swap back and
+    /// forth.
+    fn reorder_target(&self, state: &TabsState, page: usize, x: i32) -> Option<usize> {
+        if x >= self.visible_width(state) {
+            return None;
+        }
+
+        let (target, start, width) = self
+            .tab_spans(state)
+            .into_iter()
+            .find(|&(_, start, width)| x >= start && x < start + width)?;
+        let order = state.tab_order();
+        let from = order.iter().position(|&p| p == page)?;
+        let to = order.iter().position(|&p| p == target)?;
+        let middle = start + width / 2;
+        let crossed = (to > from && x >= middle) || (to < from && x < middle);
+        crossed.then_some(target)
+    }
+
+    /// Moves the tab of `page` to where the tab of `target` is.
+    fn reorder(&self, state: &mut TabsState, page: usize, target: usize) {
+        let Some(order) = &mut state.order else {
+            return;
+        };
+        let from = order.iter().position(|&p| p == page);
+        let to = order.iter().position(|&p| p == target);
+        if let (Some(from), Some(to)) = (from, to) {
+            let page = order.remove(from);
+            order.insert(to, page);
+        }
+    }
+
+    fn cycle(&self, state: &mut TabsState, forward: bool) {
+        let order = state.tab_order();
+        let count = order.len();
+        if count == 0 {
+            return;
+        }
+
+        let position = state
+            .active
+            .and_then(|active| order.iter().position(|&p| p == active))
+            .unwrap_or(0);
+        let position = if forward {
+            (position + 1) % count
+        } else {
+            (position + count - 1) % count
+        };
+        let page = order[position];
+        self.activate(state, page);
+    }
+
+    fn handle_strip(&self, state: &mut TabsState, event: &ComponentEvent) -> VuiResult<()> {
+        let event = self
+            .strip
+            .as_chain()
+            .handle_event(((), &mut state.strip_sensor), event.clone())?;
+
+        match &event {
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) => {
+                state.pressed = None;
+                if pos.is_hit() {
+                    let target = self.target_at(state, pos.relative_position().x);
+                    match target {
+                        Some(StripTarget::Tab(page)) => self.activate(state, page),
+                        Some(StripTarget::ScrollBack) => self.scroll(state, -1),
+                        Some(StripTarget::ScrollForward) => self.scroll(state, 1),
+                        _ => {}
+                    }
+                    state.pressed = target;
+                }
+            }
+            ComponentEvent::MouseMotion(pos, ..) => {
+                let dragged = match state.pressed {
+                    Some(StripTarget::Tab(page)) => Some(page),
+                    _ => None,
+                };
+                let dragging = state
+                    .strip_sensor
+                    .drag_states()
+                    .is_dragging(MouseButton::Left);
+                if let (Some(page), true) = (dragged, dragging) {
+                    let x = pos.relative_position().x;
+                    if let Some(target) = self.reorder_target(state, page, x) {
+                        self.reorder(state, page, target);
+                    }
+                }
+            }
+            ComponentEvent::MouseButtonUp(MouseButton::Left, pos, ..) => {
+                if let Some(StripTarget::Close(page)) = state.pressed.take() {
+                    let target = self.target_at(state, pos.relative_position().x);
+                    if pos.is_hit() && target == Some(StripTarget::Close(page)) {
+                        self.close(state, page);
+                    }
+                }
+            }
+            ComponentEvent::MouseWheel(amount, pos, ..) if pos.is_hit() => {
+                self.scroll(state, -amount.y.signum());
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl<P, S> HandleEvent for Tabs<'_, P>
+where
+    P: for<'s> HandleEvent<State<'s> = &'s mut S>,
+    S: 'static,
+{
+    type State<'a> = (&'a mut TabsState, &'a mut [S]);
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        let (state, page_states) = state;
+        if state.order.is_none() {
+            state.order = Some((0..self.pages.len()).collect());
+            state.active = state.active.or(state.tab_order().first().copied());
+        }
+
+        let event = self.outer.handle_event((), event)?;
+        match &event {
+            ComponentEvent::LoopStart(..) => {
+                state.active_changed = false;
+                state.closed = None;
+            }
+            ComponentEvent::KeyDown(Key::Tab, modifiers, _) if modifiers.ctrl() => {
+                self.cycle(state, !modifiers.shift());
+                return Ok(event);
+            }
+            _ => {}
+        }
+        self.handle_strip(state, &event)?;
+
+        if let Some(page) = self.active_page(state) {
+            let page_event = self
+                .page_area
+                .as_chain()
+                .handle_event(((), ()), event.clone())?;
+            self.pages[page].handle_event(&mut page_states[page], page_event)?;
+        }
+
+        Ok(event)
+    }
+}
+
+impl<P, S> Render for Tabs<'_, P>
+where
+    P: for<'s> Render<State<'s> = &'s S>,
+    S: 'static,
+{
+    type State<'a> = (&'a TabsState, &'a [S]);
+
+    fn render(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let (state, page_states) = state;
+        let layout = self.outer.update_layout((), layout)?;
+        let images = &self.images;
+        images.strip.render((), layout.clone(), render_context)?;
+
+        let visible_width = self.visible_width(state);
+        let strip_layout =
+            layout.resize_clipped(Size::new(visible_width, self.metrics.strip_height));
+        for (page, x, width) in self.tab_spans(state) {
+            if x >= visible_width {
+                break;
+            }
+
+            let tab = &self.tabs[page];
+            let tab_layout = strip_layout.clip(Vector::new(x, 0));
+            let background = if state.active == Some(page) {
+                &tab.active
+            } else {
+                &tab.normal
+            };
+            background.render((), tab_layout.clone(), render_context)?;
+            tab.label
+                .render((), tab_layout.clip(self.metrics.label), render_context)?;
+
+            if self.metrics.close_width > 0 {
+                let area = Size::new(self.metrics.close_width, self.metrics.strip_height);
+                let offset = Vector::new(width - self.metrics.close_width, 0)
+                    + ((area - images.close.size()) / 2).as_vector();
+                images
+                    .close
+                    .render((), tab_layout.clip(offset), render_context)?;
+            }
+        }
+
+        if self.overflows(state) {
+            let button_width = self.metrics.scroll_button_width;
+            let arrows = [&images.scroll_back, &images.scroll_forward];
+            for (index, arrow) in arrows.into_iter().enumerate() {
+                let button_layout =
+                    layout.clip(Vector::new(visible_width + index as i32 * button_width, 0));
+                images
+                    .scroll_button
+                    .render((), button_layout.clone(), render_context)?;
+                let area = images.scroll_button.size();
+                let offset = ((area - arrow.size()) / 2).as_vector();
+                arrow.render((), button_layout.clip(offset), render_context)?;
+            }
+        }
+
+        images.page.render(
+            (),
+            layout.clip(Vector::new(0, self.metrics.strip_height)),
+            render_context,
+        )?;
+        let page_layout = self.page_area.as_chain().update_layout(((), ()), layout)?;
+        if let Some(page) = self.active_page(state) {
+            self.pages[page].render(&page_states[page], page_layout, render_context)?;
+        }
+
+        Ok(())
+    }
+
+    fn render_overlay(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let (state, page_states) = state;
+        let layout = self.outer.update_layout((), layout)?;
+        let page_layout = self.page_area.as_chain().update_layout(((), ()), layout)?;
+        if let Some(page) = self.active_page(state) {
+            self.pages[page].render_overlay(&page_states[page], page_layout, render_context)?;
+        }
+
+        Ok(())
+    }
+}
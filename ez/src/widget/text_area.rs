@@ -0,0 +1,601 @@
+use crate::text;
+use crate::widget::row_scroll::RowScroll;
+use crate::widget::{DynText, Image, Scrollbar, ScrollbarState};
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, HandleEvent, Layout, MouseSensor, MouseSensorState, Position,
+    SizeAttr, UpdateLayout,
+};
+use amulet_core::geom::{Point, Rect, Size, Vector};
+use amulet_core::keyboard::{Key, Modifiers};
+use amulet_core::mouse::MouseButton;
+use amulet_core::text::bidi::VisualLine;
+use amulet_core::text::PieceTable;
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// The ranges of the rows a line is wrapped into, relative to the start of the line.
+type LineRows = Vec<Range<usize>>;
+
+/// The images a [`TextArea`] is drawn with.
+pub struct TextAreaImages<'a> {
+    pub background: Image<'a>,
+    /// Stretched behind the selected text.
+    pub selection: Image<'a>,
+    /// Drawn at the caret while the text area is focused. Its height is the height of the rows.
+    pub caret: Image<'a>,
+}
+
+#[derive(Debug)]
+pub struct TextAreaState {
+    mouse_sensor: MouseSensorState,
+    scrollbar: ScrollbarState,
+    buffer: PieceTable,
+    /// The rows of each line, or `None` for lines that changed since they were last wrapped.
+    rows: Vec<Option<LineRows>>,
+    /// The width the rows were wrapped at.
+    wrap_width: i32,
+    caret: usize,
+    /// The end of the selection opposite to the caret.
+    anchor: Option<usize>,
+    /// The horizontal position the caret keeps while moving up and down.
+    preferred_x: Option<i32>,
+    reveal_caret: bool,
+    selecting: bool,
+    focused: bool,
+    changed: bool,
+}
+
+impl Default for TextAreaState {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl TextAreaState {
+    pub fn new(text: &str) -> Self {
+        let buffer = PieceTable::new(text);
+        Self {
+            mouse_sensor: MouseSensorState::default(),
+            scrollbar: ScrollbarState::default(),
+            rows: vec![None; buffer.line_count()],
+            buffer,
+            wrap_width: 0,
+            caret: 0,
+            anchor: None,
+            preferred_x: None,
+            reveal_caret: false,
+            selecting: false,
+            focused: false,
+            changed: false,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.buffer.to_string()
+    }
+
+    /// The buffer holding the text, to read parts of it without copying all of it.
+    pub fn buffer(&self) -> &PieceTable {
+        &self.buffer
+    }
+
+    /// Replaces the text programmatically. This does not trigger [`Self::text_changed()`].
+    pub fn set_text(&mut self, text: &str) {
+        self.buffer.set_text(text);
+        self.rows = vec![None; self.buffer.line_count()];
+        self.caret = 0;
+        self.anchor = None;
+        self.preferred_x = None;
+        self.scrollbar.set_position(0);
+    }
+
+    /// Whether the user edited the text since the start of the current loop iteration.
+    pub fn text_changed(&self) -> bool {
+        self.changed
+    }
+
+    /// The byte offset of the caret into the text.
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    /// The selected part of the text. It is empty and located at the caret if nothing is
+    /// selected.
+    pub fn selection(&self) -> Range<usize> {
+        match self.anchor {
+            Some(anchor) => anchor.min(self.caret)..anchor.max(self.caret),
+            None => self.caret..self.caret,
+        }
+    }
+
+    pub fn selected_text(&self) -> String {
+        self.buffer.slice(self.selection())
+    }
+
+    /// Selects `range` programmatically, placing the caret at its end. The range must lie on
+    /// character boundaries.
+    pub fn select(&mut self, range: Range<usize>) {
+        let end = range.end.min(self.buffer.len());
+        self.anchor = Some(range.start.min(end));
+        self.caret = end;
+        self.preferred_x = None;
+        self.reveal_caret = true;
+    }
+
+    /// Whether the text area receives keyboard input. It gains focus when it is clicked and loses
+    /// it when a click happens elsewhere.
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The topmost visible row, counting the rows that long lines are wrapped into.
+    pub fn first_visible_row(&self) -> usize {
+        RowScroll::first_visible_row(&self.scrollbar)
+    }
+
+    /// Inserts text typed by the user at the caret, replacing the selection. Has no effect unless
+    /// the text area is focused.
+    pub fn input_text(&mut self, text: &str) {
+        if self.focused {
+            self.replace_selection(text);
+        }
+    }
+
+    fn replace_selection(&mut self, text: &str) {
+        let selection = self.selection();
+        if selection.is_empty() && text.is_empty() {
+            return;
+        }
+
+        let first_line = self.buffer.line_of(selection.start);
+        let last_line = self.buffer.line_of(selection.end);
+        self.buffer.delete(selection.clone());
+        self.buffer.insert(selection.start, text);
+        let line_count = text.matches('\n').count() + 1;
+        self.rows
+            .splice(first_line..=last_line, vec![None; line_count]);
+
+        self.caret = selection.start + text.len();
+        self.anchor = None;
+        self.preferred_x = None;
+        self.reveal_caret = true;
+        self.changed = true;
+    }
+
+    /// Moves the caret to `offset`, extending the selection or dropping it.
+    fn move_caret(&mut self, offset: usize, extend: bool) {
+        if extend {
+            self.anchor.get_or_insert(self.caret);
+        } else {
+            self.anchor = None;
+        }
+        self.caret = offset;
+        self.preferred_x = None;
+        self.reveal_caret = true;
+    }
+}
+
+/// An editor for multi-line text, which is wrapped at the width of the text area and scrolls
+/// vertically.
+///
+/// Lines are broken after spaces where possible, and between characters if a single word doesn't
+/// fit. Only the lines that were edited are wrapped again, and only the visible rows are rendered,
+/// so the text may be large.
+///
+/// The arrow keys, Home, End and Page Up/Down move the caret, with Ctrl+Home and Ctrl+End going to
+/// the start and end of the text. Holding Shift while moving the caret or clicking selects text,
+/// as does dragging, and Ctrl+A selects all of it. Typed text has to be passed to
+/// [`TextAreaState::input_text()`].
+pub struct TextArea<'a> {
+    outer: (Frame, MouseSensor),
+    scrollbar: (Position, Scrollbar<'a>),
+    images: TextAreaImages<'a>,
+    text: DynText<'a>,
+    rows: RowScroll,
+}
+
+impl SizeAttr for TextArea<'_> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a> TextArea<'a> {
+    /// The text is laid out within `text_rect` and wrapped at its width. The scrollbar is resized
+    /// to the number of rows as the text changes.
+    pub fn new(
+        outer: (Frame, MouseSensor),
+        scrollbar: (Position, Scrollbar<'a>),
+        images: TextAreaImages<'a>,
+        text: DynText<'a>,
+        text_rect: Rect,
+    ) -> Self {
+        let rows = RowScroll::new(images.caret.size().height, text_rect);
+        Self {
+            outer,
+            scrollbar,
+            images,
+            text,
+            rows,
+        }
+    }
+
+    fn width_of(&self, text: &str) -> VuiResult<i32> {
+        Ok(self.text.size_of(text)?.width)
+    }
+
+    /// Splits `line` into rows that fit the width of the text.
+    fn wrap(&self, line: &str) -> VuiResult<LineRows> {
+        text::break_line(line, self.rows.area().size.width, |text| {
+            self.width_of(text)
+        })
+    }
+
+    /// Wraps the lines that changed since they were last wrapped, or all of them if the width of
+    /// the text changed.
+    fn update_rows(&self, state: &mut TextAreaState) -> VuiResult<()> {
+        let width = self.rows.area().size.width;
+        if state.wrap_width != width {
+            state.rows = vec![None; state.buffer.line_count()];
+            state.wrap_width = width;
+        }
+        for (line, rows) in state.rows.iter_mut().enumerate() {
+            if rows.is_none() {
+                *rows = Some(self.wrap(&state.buffer.line(line))?);
+            }
+        }
+        Ok(())
+    }
+
+    /// The rows of `line`, which is wrapped on the fly if it changed since
+    /// [`Self::update_rows()`].
+    fn line_rows<'s>(
+        &self,
+        state: &'s TextAreaState,
+        line: usize,
+    ) -> VuiResult<Cow<'s, [Range<usize>]>> {
+        match state.rows.get(line) {
+            Some(Some(rows)) if state.wrap_width == self.rows.area().size.width => {
+                Ok(Cow::Borrowed(rows))
+            }
+            _ => Ok(Cow::Owned(self.wrap(&state.buffer.line(line))?)),
+        }
+    }
+
+    fn row_count(&self, state: &TextAreaState) -> VuiResult<usize> {
+        (0..state.buffer.line_count()).try_fold(0, |count, line| {
+            Ok(count + self.line_rows(state, line)?.len())
+        })
+    }
+
+    /// The index of the row containing `offset` and its range in the text.
+    fn row_of(&self, state: &TextAreaState, offset: usize) -> VuiResult<(usize, Range<usize>)> {
+        let line = state.buffer.line_of(offset);
+        let mut index = 0;
+        for previous in 0..line {
+            index += self.line_rows(state, previous)?.len();
+        }
+
+        let line_start = state.buffer.line_range(line).start;
+        let rows = self.line_rows(state, line)?;
+        let within = offset - line_start;
+        let row = rows
+            .iter()
+            .rposition(|row| row.start <= within)
+            .unwrap_or(0);
+        let range = &rows[row];
+        Ok((
+            index + row,
+            line_start + range.start..line_start + range.end,
+        ))
+    }
+
+    /// The range of the row at `index` in the text, or of the last row if there are fewer rows.
+    fn row_range(&self, state: &TextAreaState, index: usize) -> VuiResult<Range<usize>> {
+        let line_count = state.buffer.line_count();
+        let mut first = 0;
+        for line in 0..line_count {
+            let rows = self.line_rows(state, line)?;
+            if index < first + rows.len() || line + 1 == line_count {
+                let range = &rows[(index - first).min(rows.len() - 1)];
+                let line_start = state.buffer.line_range(line).start;
+                return Ok(line_start + range.start..line_start + range.end);
+            }
+            first += rows.len();
+        }
+        Ok(0..0)
+    }
+
+    /// The last offset in `row` the caret can be placed at. A row that was wrapped ends where the
+    /// next one starts, which is where the caret is shown then.
+    fn caret_end(&self, state: &TextAreaState, row: &Range<usize>) -> usize {
+        let line_end = state.buffer.line_range(state.buffer.line_of(row.start)).end;
+        if row.end < line_end {
+            state.buffer.prev_boundary(row.end)
+        } else {
+            row.end
+        }
+    }
+
+    /// The offset in `row` that is closest to `x`.
+    fn offset_at_x(&self, state: &TextAreaState, row: Range<usize>, x: i32) -> VuiResult<usize> {
+        let text = state.buffer.slice(row.clone());
+        let visual = VisualLine::new(&text, None);
+        let edges = visual.edges(&text, |text| self.width_of(text))?;
+        let caret = row.start + visual.caret_at(VisualLine::index_at_x(&edges, x));
+        Ok(caret.min(self.caret_end(state, &row)))
+    }
+
+    /// The offset closest to `point`, relative to the area of the text.
+    fn offset_at(&self, state: &TextAreaState, point: Point) -> VuiResult<usize> {
+        let index = state.first_visible_row() as i64
+            + i64::from(point.y.div_euclid(self.rows.row_height()));
+        if index < 0 {
+            return Ok(0);
+        }
+
+        let row = self.row_range(state, index as usize)?;
+        self.offset_at_x(state, row, point.x)
+    }
+
+    /// The index of the row containing the caret and the horizontal position of the caret in it.
+    fn caret_position(&self, state: &TextAreaState) -> VuiResult<(usize, i32)> {
+        let (index, row) = self.row_of(state, state.caret)?;
+        let text = state.buffer.slice(row.clone());
+        let visual = VisualLine::new(&text, None);
+        let edges = visual.edges(&text, |text| self.width_of(text))?;
+        Ok((index, edges[visual.caret_index(state.caret - row.start)]))
+    }
+
+    /// The scrollbar, sized for the rows of the current text.
+    fn scrollbar(&self, state: &TextAreaState) -> VuiResult<(Position, Scrollbar<'a>)> {
+        let row_count = i32::try_from(self.row_count(state)?).unwrap_or(i32::MAX);
+        let visible_rows = self.rows.visible_rows() as i32;
+        let (position, scrollbar) = &self.scrollbar;
+        Ok((
+            position.clone(),
+            scrollbar.with_content(row_count, visible_rows),
+        ))
+    }
+
+    /// Moves the caret by `rows` rows, keeping its horizontal position. Moving beyond the first or
+    /// last row goes to the start or end of the text.
+    fn move_vertically(&self, state: &mut TextAreaState, rows: i64, extend: bool) -> VuiResult<()> {
+        let (index, x) = self.caret_position(state)?;
+        let x = state.preferred_x.unwrap_or(x);
+        let target = index as i64 + rows;
+        let offset = if target < 0 {
+            0
+        } else if target as usize >= self.row_count(state)? {
+            state.buffer.len()
+        } else {
+            let row = self.row_range(state, target as usize)?;
+            self.offset_at_x(state, row, x)?
+        };
+        state.move_caret(offset, extend);
+        state.preferred_x = Some(x);
+        Ok(())
+    }
+
+    fn handle_key(
+        &self,
+        state: &mut TextAreaState,
+        key: Key,
+        modifiers: Modifiers,
+    ) -> VuiResult<()> {
+        let extend = modifiers.shift();
+        let caret = state.caret;
+        let selection = state.selection();
+        match key {
+            Key::Left if !extend && !selection.is_empty() => {
+                state.move_caret(selection.start, false);
+            }
+            Key::Right if !extend && !selection.is_empty() => {
+                state.move_caret(selection.end, false);
+            }
+            Key::Left => state.move_caret(state.buffer.prev_boundary(caret), extend),
+            Key::Right => state.move_caret(state.buffer.next_boundary(caret), extend),
+            Key::Up | Key::Down | Key::PageUp | Key::PageDown => {
+                let page = self.rows.visible_rows().saturating_sub(1).max(1) as i64;
+                let rows = match key {
+                    Key::Up => -1,
+                    Key::Down => 1,
+                    Key::PageUp => -page,
+                    _ => page,
+                };
+                self.move_vertically(state, rows, extend)?;
+            }
+            Key::Home if modifiers.ctrl() => state.move_caret(0, extend),
+            Key::End if modifiers.ctrl() => state.move_caret(state.buffer.len(), extend),
+            Key::Home => {
+                let (_, row) = self.row_of(state, caret)?;
+                state.move_caret(row.start, extend);
+            }
+            Key::End => {
+                let (_, row) = self.row_of(state, caret)?;
+                state.move_caret(self.caret_end(state, &row), extend);
+            }
+            Key::Backspace => {
+                if selection.is_empty() {
+                    state.anchor = Some(state.buffer.prev_boundary(caret));
+                }
+                state.replace_selection("");
+            }
+            Key::Delete => {
+                if selection.is_empty() {
+                    state.anchor = Some(state.buffer.next_boundary(caret));
+                }
+                state.replace_selection("");
+            }
+            Key::Enter => state.replace_selection("\n"),
+            Key::Character('a') if modifiers.ctrl() => state.select(0..state.buffer.len()),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn render_row(
+        &self,
+        state: &TextAreaState,
+        row: Range<usize>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let text = state.buffer.slice(row.clone());
+        let visual = VisualLine::new(&text, None);
+        let edges = visual.edges(&text, |text| self.width_of(text))?;
+
+        // In bidi text, the selection may be displayed in several pieces.
+        let selection = state.selection();
+        let mut highlights: Vec<Range<i32>> = Vec::new();
+        for index in 0..visual.len() {
+            let cluster = visual.cluster_range(index);
+            if selection.start <= row.start + cluster.start
+                && row.start + cluster.end <= selection.end
+            {
+                match highlights.last_mut() {
+                    Some(last) if last.end == edges[index] => last.end = edges[index + 1],
+                    _ => highlights.push(edges[index]..edges[index + 1]),
+                }
+            }
+        }
+        if selection.start <= row.end && selection.end > row.end {
+            // Shows that the line break is selected as well.
+            let x = edges[visual.len()];
+            highlights.push(x..x + self.width_of(" ")?);
+        }
+        for highlight in highlights {
+            self.images.selection.render_stretched(
+                Rect::from_xywh(
+                    highlight.start,
+                    0,
+                    highlight.end - highlight.start,
+                    self.rows.row_height(),
+                ),
+                layout.clone(),
+                render_context,
+            )?;
+        }
+
+        self.text.render(&text, layout.clone(), render_context)?;
+
+        if state.focused && (row.start..=self.caret_end(state, &row)).contains(&state.caret) {
+            let x = edges[visual.caret_index(state.caret - row.start)];
+            self.images
+                .caret
+                .render((), layout.clip(Vector::new(x, 0)), render_context)?;
+        }
+        Ok(())
+    }
+}
+
+impl HandleEvent for TextArea<'_> {
+    type State<'a> = &'a mut TextAreaState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        self.update_rows(state)?;
+
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+        self.scrollbar(state)?
+            .as_chain()
+            .handle_event(((), &mut state.scrollbar), event.clone())?;
+
+        match &event {
+            ComponentEvent::LoopStart(..) => {
+                state.changed = false;
+            }
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, modifiers, _) => {
+                state.focused = pos.is_hit();
+                let point = self.rows.enter_area(pos).relative_position();
+                if pos.is_hit() && Rect::from_size(self.rows.area().size).contains(point) {
+                    let offset = self.offset_at(state, point)?;
+                    state.move_caret(offset, modifiers.shift());
+                    state.selecting = true;
+                }
+            }
+            ComponentEvent::MouseMotion(pos, ..) if state.selecting => {
+                let offset =
+                    self.offset_at(state, self.rows.enter_area(pos).relative_position())?;
+                state.move_caret(offset, true);
+            }
+            ComponentEvent::MouseButtonUp(MouseButton::Left, ..) => {
+                state.selecting = false;
+            }
+            ComponentEvent::MouseWheel(amount, pos, ..) if pos.is_hit() => {
+                let range = *self.scrollbar(state)?.1.range();
+                self.rows
+                    .scroll_by_wheel(&mut state.scrollbar, &range, *amount);
+            }
+            ComponentEvent::KeyDown(key, modifiers, _) if state.focused => {
+                self.handle_key(state, *key, *modifiers)?;
+            }
+            _ => {}
+        }
+
+        if state.reveal_caret {
+            state.reveal_caret = false;
+            self.update_rows(state)?;
+            let (index, _) = self.row_of(state, state.caret)?;
+            let range = *self.scrollbar(state)?.1.range();
+            self.rows
+                .scroll_into_view(&mut state.scrollbar, &range, index);
+        }
+
+        Ok(event)
+    }
+}
+
+impl Render for TextArea<'_> {
+    type State<'a> = &'a TextAreaState;
+
+    fn render(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
+        self.images
+            .background
+            .render((), layout.clone(), render_context)?;
+        self.scrollbar(state)?
+            .render(((), &state.scrollbar), layout.clone(), render_context)?;
+
+        let text_layout = self.rows.area_layout(&layout);
+        let first = state.first_visible_row();
+        // The last row may be partially visible.
+        let end = first + self.rows.visible_rows() + 1;
+        let mut index = 0;
+        for line in 0..state.buffer.line_count() {
+            if index >= end {
+                break;
+            }
+            let rows = self.line_rows(state, line)?;
+            if index + rows.len() <= first {
+                index += rows.len();
+                continue;
+            }
+
+            let line_start = state.buffer.line_range(line).start;
+            for row in rows.iter() {
+                if (first..end).contains(&index) {
+                    let row_layout = self.rows.row_layout(&text_layout, index - first);
+                    let row = line_start + row.start..line_start + row.end;
+                    self.render_row(state, row, row_layout, render_context)?;
+                }
+                index += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,29 +1,95 @@
+use crate::widget::{DynText, Image};
 use amulet_core::component::{
     AsChain, ComponentEvent, Frame, HandleEvent, Layout, MouseSensor, MouseSensorState, Position,
     SizeAttr, UpdateLayout,
 };
-use amulet_core::geom::Size;
+use amulet_core::geom::{Direction, Size, Vector};
+use amulet_core::keyboard::Key;
+use amulet_core::mouse::MouseButton;
+use amulet_core::text::bidi::{self, VisualLine};
 use amulet_core::VuiResult;
 use amulet_sdl2::render::{Render, RenderContext};
-use crate::widget::{DynText, Image};
 
 #[derive(Debug, Default)]
 pub struct TextInputState {
     mouse_sensor: MouseSensorState,
     text: String,
+    /// The byte offset of the caret in `text`.
+    caret: usize,
+    focused: bool,
 }
 
 impl TextInputState {
+    /// Inserts `text` at the caret.
     pub fn update(&mut self, text: &str) {
-        self.text.push_str(text)
+        self.text.insert_str(self.caret, text);
+        self.caret += text.len();
+    }
+
+    /// Passes text typed by the user to the input. Has no effect unless the input is focused.
+    pub fn input_text(&mut self, text: &str) {
+        if self.focused {
+            self.update(text);
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text, moving the caret to its end.
+    pub fn set_text(&mut self, text: &str) {
+        self.text.clear();
+        self.text.push_str(text);
+        self.caret = self.text.len();
+    }
+
+    /// Removes the character before the caret, as done by Backspace.
+    pub fn delete_backward(&mut self) {
+        if let Some(chr) = self.text[..self.caret].chars().next_back() {
+            self.caret -= chr.len_utf8();
+            self.text.remove(self.caret);
+        }
+    }
+
+    /// Removes the character after the caret, as done by Delete.
+    pub fn delete_forward(&mut self) {
+        if self.caret < self.text.len() {
+            self.text.remove(self.caret);
+        }
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Lets the input handle keys. Clicking focuses the input, and clicking elsewhere unfocuses it.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Moves the caret `delta` positions to the right as displayed, which in right-to-left text is
+    /// towards the start.
+    fn move_visually(&mut self, delta: isize) {
+        self.caret = VisualLine::new(&self.text, None).move_visually(self.caret, delta);
     }
 }
 
+/// A single line of editable text.
+///
+/// Text is displayed in bidi order, and aligned right if its first strong character runs right to
+/// left. When focused, the arrow keys move the caret as
+/// displayed, so Left moves towards the end of right-to-left text.
 pub struct TextInput<'a> {
     outer: (Frame, MouseSensor),
     inner: (Position, Frame, Position),
     background: Image<'a>,
     content: DynText<'a>,
+    caret: Image<'a>,
 }
 
 impl SizeAttr for TextInput<'_> {
@@ -38,12 +104,42 @@ impl<'a> TextInput<'a> {
         inner: (Position, Frame, Position),
         background: Image<'a>,
         content: DynText<'a>,
+        caret: Image<'a>,
     ) -> Self {
         Self {
             outer,
             inner,
             background,
             content,
+            caret,
+        }
+    }
+
+    /// The horizontal offset of the text within the content frame.
+    fn text_offset(&self, state: &TextInputState) -> VuiResult<i32> {
+        if bidi::base_direction(&state.text) != Some(Direction::RightToLeft) {
+            return Ok(0);
+        }
+        let width = self.content.size_of(&state.text)?.width;
+        Ok((self.inner.1.size().width - width).max(0))
+    }
+
+    /// The byte offset of the caret that is displayed closest to `x`, relative to the text.
+    fn caret_at_x(&self, state: &TextInputState, x: i32) -> VuiResult<usize> {
+        let visual = VisualLine::new(&state.text, None);
+        let edges = visual.edges(&state.text, |text| Ok(self.content.size_of(text)?.width))?;
+        Ok(visual.caret_at(VisualLine::index_at_x(&edges, x)))
+    }
+
+    fn handle_key(&self, state: &mut TextInputState, key: Key) {
+        match key {
+            Key::Left => state.move_visually(-1),
+            Key::Right => state.move_visually(1),
+            Key::Home => state.caret = 0,
+            Key::End => state.caret = state.text.len(),
+            Key::Backspace => state.delete_backward(),
+            Key::Delete => state.delete_forward(),
+            _ => {}
         }
     }
 }
@@ -56,9 +152,24 @@ impl HandleEvent for TextInput<'_> {
         state: Self::State<'_>,
         event: ComponentEvent,
     ) -> VuiResult<ComponentEvent> {
-        (self.outer.as_chain(), self.inner.as_chain())
+        let event = self
+            .outer
             .as_chain()
-            .handle_event((((), &mut state.mouse_sensor), ((), (), ())), event)
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+        if let ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) = &event {
+            state.focused = pos.is_hit();
+        }
+
+        let event = self.inner.as_chain().handle_event(((), (), ()), event)?;
+        match &event {
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) if state.focused => {
+                let x = pos.relative_position().x - self.text_offset(state)?;
+                state.caret = self.caret_at_x(state, x)?;
+            }
+            ComponentEvent::KeyDown(key, ..) if state.focused => self.handle_key(state, *key),
+            _ => {}
+        }
+        Ok(event)
     }
 }
 
@@ -74,6 +185,20 @@ impl Render for TextInput<'_> {
         let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
         self.background.render((), layout.clone(), render_context)?;
         let layout = self.inner.as_chain().update_layout(((), (), ()), layout)?;
-        self.content.render(&state.text, layout, render_context)
+        let offset = self.text_offset(state)?;
+        self.content.render(
+            &state.text,
+            layout.clip(Vector::new(offset, 0)),
+            render_context,
+        )?;
+
+        if state.focused {
+            let visual = VisualLine::new(&state.text, None);
+            let prefix = visual.prefix(&state.text, visual.caret_index(state.caret));
+            let x = offset + self.content.size_of(&prefix)?.width;
+            self.caret
+                .render((), layout.clip(Vector::new(x, 0)), render_context)?;
+        }
+        Ok(())
     }
 }
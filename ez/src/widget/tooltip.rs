@@ -0,0 +1,138 @@
+use crate::widget::Image;
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, HandleEvent, Layout, MouseSensor, MouseSensorState, SizeAttr,
+};
+use amulet_core::geom::{Point, Rect, Size, Vector};
+use amulet_core::timer::{TimerId, Timers};
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+use std::time::Duration;
+
+/// How long the pointer has to rest over a component before its tooltip appears.
+pub const DEFAULT_TOOLTIP_DELAY: Duration = Duration::from_millis(600);
+
+/// Where the tooltip appears, relative to the pointer.
+const POINTER_OFFSET: Vector = Vector { x: 0, y: 20 };
+
+#[derive(Debug, Default)]
+pub struct TooltipState {
+    mouse_sensor: MouseSensorState,
+    timer: Option<TimerId>,
+    pointer: Point,
+    shown_at: Option<Point>,
+    dismissed: bool,
+}
+
+impl TooltipState {
+    pub fn is_visible(&self) -> bool {
+        self.shown_at.is_some()
+    }
+}
+
+/// Shows a tooltip after the pointer dwelled over the area of the tooltip frame.
+///
+/// The tooltip has no visual representation of its own in the regular render pass, it is meant to
+/// be placed on top of the component it describes. The tooltip itself is drawn during the overlay
+/// pass, below the pointer and kept within the window. It disappears when the pointer leaves the
+/// frame or a button is pressed, and only reappears after the pointer left and entered again.
+pub struct Tooltip<'a> {
+    outer: (Frame, MouseSensor),
+    content: Image<'a>,
+    delay: Duration,
+    timers: Timers,
+}
+
+impl SizeAttr for Tooltip<'_> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a> Tooltip<'a> {
+    pub fn new(outer: (Frame, MouseSensor), content: Image<'a>, timers: Timers) -> Self {
+        Self {
+            outer,
+            content,
+            delay: DEFAULT_TOOLTIP_DELAY,
+            timers,
+        }
+    }
+
+    pub fn with_delay(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    fn cancel_timer(&self, state: &mut TooltipState) {
+        if let Some(timer) = state.timer.take() {
+            self.timers.cancel(timer);
+        }
+    }
+}
+
+impl HandleEvent for Tooltip<'_> {
+    type State<'a> = &'a mut TooltipState;
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        if let ComponentEvent::MouseMotion(pos, ..) = &event {
+            state.pointer = pos.absolute_position();
+        }
+
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+
+        if !state.mouse_sensor.hover_state().is_hovering() {
+            self.cancel_timer(state);
+            state.shown_at = None;
+            state.dismissed = false;
+        } else if matches!(&event, ComponentEvent::MouseButtonDown(..)) {
+            self.cancel_timer(state);
+            state.shown_at = None;
+            state.dismissed = true;
+        } else if !state.dismissed && state.shown_at.is_none() {
+            if state.mouse_sensor.has_dwelled(self.delay) {
+                self.cancel_timer(state);
+                state.shown_at = Some(state.pointer);
+            } else if state.timer.is_none() {
+                // Only needed to wake up the event loop, the dwell time is checked on every event.
+                state.timer = Some(self.timers.start_once(event.timestamp(), self.delay));
+            }
+        }
+
+        Ok(event)
+    }
+}
+
+impl Render for Tooltip<'_> {
+    type State<'a> = &'a TooltipState;
+
+    fn render(
+        &self,
+        _state: Self::State<'_>,
+        _layout: Layout,
+        _render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        Ok(())
+    }
+
+    fn render_overlay(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let Some(pointer) = state.shown_at else {
+            return Ok(());
+        };
+
+        let rect = Rect::new(pointer + POINTER_OFFSET, self.content.size())
+            .constrain(render_context.window_rect());
+        let layout = Layout::new(rect).with_direction(layout.direction());
+        self.content.render((), layout, render_context)
+    }
+}
@@ -0,0 +1,475 @@
+use crate::widget::row_scroll::RowScroll;
+use crate::widget::{DynText, Image, Scrollbar, ScrollbarState};
+use amulet_core::component::{
+    AsChain, ComponentEvent, Frame, FramedPosition, HandleEvent, Layout, MouseSensor,
+    MouseSensorState, Position, SizeAttr, UpdateLayout,
+};
+use amulet_core::geom::{Point, Rect, Size, Vector};
+use amulet_core::keyboard::Key;
+use amulet_core::mouse::MouseButton;
+use amulet_core::VuiResult;
+use amulet_sdl2::render::{Render, RenderContext};
+use std::collections::HashSet;
+
+/// The hierarchical data shown by a [`TreeView`].
+///
+/// Nodes are identified by their path, the indices of the node and its ancestors among their
+/// siblings, starting at the top level. The root is the empty path and isn't shown.
+///
+/// The tree view only asks for the children of expanded nodes, so a model may load them lazily
+/// (see [`TreeViewState::expanded_node()`]).
+pub trait TreeModel {
+    fn child_count(&self, path: &[usize]) -> usize;
+
+    fn label(&self, path: &[usize]) -> String;
+
+    /// Whether the node can be expanded. This is queried for every visible node, and should be
+    /// answered without loading the children.
+    fn has_children(&self, path: &[usize]) -> bool;
+}
+
+/// A visible node, in the order they are shown.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct TreeRow {
+    path: Vec<usize>,
+    has_children: bool,
+}
+
+impl TreeRow {
+    fn depth(&self) -> usize {
+        self.path.len().saturating_sub(1)
+    }
+}
+
+/// The images a [`TreeView`] is drawn with.
+pub struct TreeViewImages<'a> {
+    pub background: Image<'a>,
+    /// Drawn behind the selected row. Its height is the height of the rows.
+    pub selection: Image<'a>,
+    /// Drawn around the selected row while the tree view is focused.
+    pub focus_ring: Image<'a>,
+    /// Shown in front of collapsed nodes with children.
+    pub expand_arrow: Image<'a>,
+    /// Shown in front of expanded nodes.
+    pub collapse_arrow: Image<'a>,
+}
+
+#[derive(Debug, Default)]
+pub struct TreeViewState {
+    mouse_sensor: MouseSensorState,
+    scrollbar: ScrollbarState,
+    expanded: HashSet<Vec<usize>>,
+    /// `None` until the model was first consulted.
+    rows: Option<Vec<TreeRow>>,
+    selected: Option<Vec<usize>>,
+    selection_changed: bool,
+    expanded_node: Option<Vec<usize>>,
+    pressed_row: Option<usize>,
+    activated: Option<Vec<usize>>,
+    focused: bool,
+}
+
+impl TreeViewState {
+    /// The path of the selected node.
+    pub fn selected(&self) -> Option<&[usize]> {
+        self.selected.as_deref()
+    }
+
+    /// Selects the node at `path` without triggering [`Self::selection_changed()`]. The node is
+    /// only visible if all of its ancestors are expanded.
+    pub fn set_selected(&mut self, path: Option<Vec<usize>>) {
+        self.selected = path;
+    }
+
+    /// Whether the user selected another node during the current loop iteration.
+    pub fn selection_changed(&self) -> bool {
+        self.selection_changed
+    }
+
+    /// The node that was double-clicked or confirmed with Enter during the current loop
+    /// iteration.
+    pub fn activated(&self) -> Option<&[usize]> {
+        self.activated.as_deref()
+    }
+
+    pub fn is_expanded(&self, path: &[usize]) -> bool {
+        self.expanded.contains(path)
+    }
+
+    /// Expands or collapses a node programmatically. This does not trigger
+    /// [`Self::expanded_node()`].
+    pub fn set_expanded(&mut self, model: &dyn TreeModel, path: &[usize], expanded: bool) {
+        if expanded {
+            self.expanded.insert(path.to_vec());
+        } else {
+            self.expanded.remove(path);
+        }
+        self.refresh(model);
+    }
+
+    /// The node the user expanded during the current loop iteration. A model that loads children
+    /// in the background can start doing so now and call [`Self::refresh()`] once they arrived.
+    pub fn expanded_node(&self) -> Option<&[usize]> {
+        self.expanded_node.as_deref()
+    }
+
+    /// Whether the tree view receives keyboard input. It gains focus when it is clicked and loses
+    /// it when a click happens elsewhere.
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The index of the topmost visible row, counting only the nodes whose ancestors are all
+    /// expanded.
+    pub fn first_visible_row(&self) -> usize {
+        RowScroll::first_visible_row(&self.scrollbar)
+    }
+
+    /// Queries the visible nodes from the model again. Must be called when the model changed.
+    pub fn refresh(&mut self, model: &dyn TreeModel) {
+        let mut rows = Vec::new();
+        let mut path = Vec::new();
+        self.collect_rows(model, &mut path, &mut rows);
+        self.rows = Some(rows);
+    }
+
+    fn collect_rows(&self, model: &dyn TreeModel, path: &mut Vec<usize>, rows: &mut Vec<TreeRow>) {
+        for index in 0..model.child_count(path) {
+            path.push(index);
+            let has_children = model.has_children(path);
+            rows.push(TreeRow {
+                path: path.clone(),
+                has_children,
+            });
+            if has_children && self.expanded.contains(path.as_slice()) {
+                self.collect_rows(model, path, rows);
+            }
+            path.pop();
+        }
+    }
+
+    fn rows(&self) -> &[TreeRow] {
+        self.rows.as_deref().unwrap_or_default()
+    }
+
+    fn selected_row(&self) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        self.rows().iter().position(|row| &row.path == selected)
+    }
+
+    fn select(&mut self, path: Vec<usize>) {
+        if self.selected.as_ref() != Some(&path) {
+            self.selected = Some(path);
+            self.selection_changed = true;
+        }
+    }
+}
+
+/// A tree of nodes provided by a [`TreeModel`], shown as indented rows of uniform height of
+/// which only the visible ones are rendered.
+///
+/// Clicking the arrow in front of a node or double-clicking it expands or collapses it. The
+/// arrow keys, Page Up/Down, Home and End move the selection, Right expands the selected node or
+/// moves to its first child, and Left collapses it or moves to its parent. Enter or a double
+/// click activate a node.
+///
+/// The model is passed along with the state, and [`TreeViewState::refresh()`] must be called
+/// when it changed.
+pub struct TreeView<'a> {
+    outer: (Frame, MouseSensor),
+    scrollbar: (Position, Scrollbar<'a>),
+    images: TreeViewImages<'a>,
+    label: DynText<'a>,
+    rows: RowScroll,
+    padding: Vector,
+    indent: i32,
+}
+
+impl SizeAttr for TreeView<'_> {
+    fn size(&self) -> Size {
+        self.outer.0.size()
+    }
+}
+
+impl<'a> TreeView<'a> {
+    /// The rows are laid out within `rows_rect`. Each level of the tree is indented by `indent`,
+    /// within which the arrow is centered, and the labels are rendered at `padding` after it.
+    /// The scrollbar is resized to the number of visible rows as nodes are expanded and
+    /// collapsed.
+    pub fn new(
+        outer: (Frame, MouseSensor),
+        scrollbar: (Position, Scrollbar<'a>),
+        images: TreeViewImages<'a>,
+        label: DynText<'a>,
+        rows_rect: Rect,
+        padding: Vector,
+        indent: i32,
+    ) -> Self {
+        let rows = RowScroll::new(images.selection.size().height, rows_rect);
+        Self {
+            outer,
+            scrollbar,
+            images,
+            label,
+            rows,
+            padding,
+            indent,
+        }
+    }
+
+    /// The scrollbar, sized for the rows that are currently visible.
+    fn scrollbar(&self, state: &TreeViewState) -> (Position, Scrollbar<'a>) {
+        let row_count = i32::try_from(state.rows().len()).unwrap_or(i32::MAX);
+        let visible_rows = self.rows.visible_rows() as i32;
+        let (position, scrollbar) = &self.scrollbar;
+        (
+            position.clone(),
+            scrollbar.with_content(row_count, visible_rows),
+        )
+    }
+
+    /// The row at `pos`, which is framed by the tree view.
+    fn row_at(&self, state: &TreeViewState, pos: &FramedPosition) -> Option<usize> {
+        self.rows
+            .row_at(&state.scrollbar, pos)
+            .filter(|&index| index < state.rows().len())
+    }
+
+    /// Whether `point`, relative to the leading edge of the rows, is on the arrow of the node in
+    /// `row`.
+    fn is_on_arrow(&self, state: &TreeViewState, row: usize, point: Point) -> bool {
+        let row = &state.rows()[row];
+        let x = point.x - row.depth() as i32 * self.indent;
+        row.has_children && (0..self.indent).contains(&x)
+    }
+
+    fn select_row(&self, state: &mut TreeViewState, index: usize) {
+        state.select(state.rows()[index].path.clone());
+        let range = *self.scrollbar(state).1.range();
+        self.rows
+            .scroll_into_view(&mut state.scrollbar, &range, index);
+    }
+
+    fn set_expanded(
+        &self,
+        state: &mut TreeViewState,
+        model: &dyn TreeModel,
+        path: &[usize],
+        expanded: bool,
+    ) {
+        if expanded == state.is_expanded(path) {
+            return;
+        }
+
+        if expanded {
+            state.expanded_node = Some(path.to_vec());
+        } else if state.selected.as_ref().is_some_and(|s| s.starts_with(path)) {
+            // Don't let the selection disappear into the collapsed subtree.
+            state.select(path.to_vec());
+        }
+        state.set_expanded(model, path, expanded);
+
+        // Keep the scroll position within the new range.
+        let first_row = state.first_visible_row() as i64;
+        let range = *self.scrollbar(state).1.range();
+        self.rows.scroll_to(&mut state.scrollbar, &range, first_row);
+    }
+
+    fn toggle(&self, state: &mut TreeViewState, model: &dyn TreeModel, index: usize) {
+        let row = state.rows()[index].clone();
+        if row.has_children {
+            let expanded = state.is_expanded(&row.path);
+            self.set_expanded(state, model, &row.path, !expanded);
+        }
+    }
+
+    fn handle_key(&self, state: &mut TreeViewState, model: &dyn TreeModel, key: Key) {
+        let row_count = state.rows().len();
+        if row_count == 0 {
+            return;
+        }
+
+        let last = row_count - 1;
+        let page = self.rows.visible_rows().saturating_sub(1).max(1);
+        let current = state.selected_row();
+        let target = match key {
+            Key::Up => current.map_or(0, |i| i.saturating_sub(1)),
+            Key::Down => current.map_or(0, |i| (i + 1).min(last)),
+            Key::PageUp => current.map_or(0, |i| i.saturating_sub(page)),
+            Key::PageDown => current.map_or(0, |i| (i + page).min(last)),
+            Key::Home => 0,
+            Key::End => last,
+            Key::Right => {
+                let Some(index) = current else { return };
+                let row = state.rows()[index].clone();
+                if !row.has_children {
+                    return;
+                }
+                if !state.is_expanded(&row.path) {
+                    self.set_expanded(state, model, &row.path, true);
+                    return;
+                }
+                // Moves to the first child, if the node has any after all.
+                match state.rows().get(index + 1) {
+                    Some(next) if next.path.len() > row.path.len() => index + 1,
+                    _ => return,
+                }
+            }
+            Key::Left => {
+                let Some(index) = current else { return };
+                let path = state.rows()[index].path.clone();
+                if state.is_expanded(&path) {
+                    self.set_expanded(state, model, &path, false);
+                    return;
+                }
+                let parent = &path[..path.len() - 1];
+                match state.rows().iter().position(|row| row.path == parent) {
+                    Some(parent) => parent,
+                    None => return,
+                }
+            }
+            Key::Enter => {
+                state.activated = state.selected.clone();
+                return;
+            }
+            _ => return,
+        };
+
+        self.select_row(state, target);
+    }
+}
+
+impl HandleEvent for TreeView<'_> {
+    type State<'a> = (&'a mut TreeViewState, &'a dyn TreeModel);
+
+    fn handle_event(
+        &self,
+        state: Self::State<'_>,
+        event: ComponentEvent,
+    ) -> VuiResult<ComponentEvent> {
+        let (state, model) = state;
+        if state.rows.is_none() {
+            state.refresh(model);
+        }
+
+        let event = self
+            .outer
+            .as_chain()
+            .handle_event(((), &mut state.mouse_sensor), event)?;
+        self.scrollbar(state)
+            .as_chain()
+            .handle_event(((), &mut state.scrollbar), event.clone())?;
+
+        match &event {
+            ComponentEvent::LoopStart(..) => {
+                state.selection_changed = false;
+                state.expanded_node = None;
+                state.activated = None;
+            }
+            ComponentEvent::MouseButtonDown(MouseButton::Left, pos, ..) => {
+                state.focused = pos.is_hit();
+                state.pressed_row = None;
+                if pos.is_hit() {
+                    if let Some(index) = self.row_at(state, pos) {
+                        let point = RowScroll::leading_point(&self.rows.enter_area(pos));
+                        if self.is_on_arrow(state, index, point) {
+                            self.toggle(state, model, index);
+                        } else {
+                            state.pressed_row = Some(index);
+                            self.select_row(state, index);
+                        }
+                    }
+                }
+            }
+            ComponentEvent::MouseWheel(amount, pos, ..) if pos.is_hit() => {
+                let range = *self.scrollbar(state).1.range();
+                self.rows
+                    .scroll_by_wheel(&mut state.scrollbar, &range, *amount);
+            }
+            ComponentEvent::KeyDown(key, ..) if state.focused => {
+                self.handle_key(state, model, *key);
+            }
+            _ => {}
+        }
+
+        if state
+            .mouse_sensor
+            .click_states()
+            .has_double_click_completed(MouseButton::Left)
+        {
+            if let Some(index) = state.pressed_row.take() {
+                self.toggle(state, model, index);
+                state.activated = state.selected.clone();
+            }
+        }
+
+        Ok(event)
+    }
+}
+
+impl Render for TreeView<'_> {
+    type State<'a> = (&'a TreeViewState, &'a dyn TreeModel);
+
+    fn render(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let (state, model) = state;
+        let layout = self.outer.as_chain().update_layout(((), ()), layout)?;
+        self.images
+            .background
+            .render((), layout.clone(), render_context)?;
+        self.scrollbar(state)
+            .render(((), &state.scrollbar), layout.clone(), render_context)?;
+
+        let rows_layout = self.rows.area_layout(&layout);
+        let rows = state.rows();
+        let selected = state.selected_row();
+        let first = state.first_visible_row().min(rows.len());
+        // The last row may be partially visible.
+        let end = (first + self.rows.visible_rows() + 1).min(rows.len());
+        for (offset, row) in rows[first..end].iter().enumerate() {
+            let index = first + offset;
+            let row_layout = self.rows.row_layout(&rows_layout, offset);
+            let is_selected = selected == Some(index);
+            if is_selected {
+                self.images
+                    .selection
+                    .render((), row_layout.clone(), render_context)?;
+            }
+
+            let indent = row.depth() as i32 * self.indent;
+            if row.has_children {
+                let arrow = if state.is_expanded(&row.path) {
+                    &self.images.collapse_arrow
+                } else {
+                    &self.images.expand_arrow
+                };
+                let arrow_offset =
+                    (Size::new(self.indent, self.rows.row_height()) - arrow.size()) / 2;
+                arrow.render(
+                    (),
+                    row_layout
+                        .place(Vector::new(indent, 0) + arrow_offset.as_vector())
+                        .fit(arrow.size()),
+                    render_context,
+                )?;
+            }
+
+            let label_layout =
+                row_layout.place(Vector::new(indent + self.indent, 0) + self.padding);
+            self.label
+                .render(&model.label(&row.path), label_layout, render_context)?;
+
+            if is_selected && state.focused {
+                self.images
+                    .focus_ring
+                    .render((), row_layout, render_context)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use amulet_core::mouse::CursorShape;
+use amulet_core::VuiResult;
+use sdl2::mouse::{Cursor, SystemCursor};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+fn map_cursor_shape(shape: CursorShape) -> SystemCursor {
+    match shape {
+        CursorShape::Arrow => SystemCursor::Arrow,
+        CursorShape::Text => SystemCursor::IBeam,
+        CursorShape::Hand => SystemCursor::Hand,
+        CursorShape::ResizeHorizontal => SystemCursor::SizeWE,
+        CursorShape::ResizeVertical => SystemCursor::SizeNS,
+    }
+}
+
+/// Applies [`CursorShape`]s to the mouse cursor, using the system cursors.
+///
+/// SDL only keeps a reference to the active cursor, so the cursors are kept alive here. They are
+/// created as they are first used.
+#[derive(Default)]
+pub struct Cursors {
+    cursors: HashMap<CursorShape, Cursor>,
+    current: CursorShape,
+}
+
+impl Cursors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Changes the cursor, unless it already has the requested shape.
+    pub fn set(&mut self, shape: CursorShape) -> VuiResult<()> {
+        if shape == self.current {
+            return Ok(());
+        }
+
+        let cursor = match self.cursors.entry(shape) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Cursor::from_system(map_cursor_shape(shape))?),
+        };
+        cursor.set();
+        self.current = shape;
+        Ok(())
+    }
+}
@@ -1,32 +1,203 @@
-use amulet_core::{mouse, GlobalEvent};
-use sdl2::event::Event as SdlEvent;
-use sdl2::EventPump;
+use amulet_core::geom::{Point, Vector};
+use amulet_core::keyboard::{Key, Modifiers};
+use amulet_core::time::Timestamp;
+use amulet_core::timer::Timers;
+use amulet_core::{mouse, GlobalEvent, WindowEvent};
+use sdl2::event::{Event as SdlEvent, WindowEvent as SdlWindowEvent};
+use sdl2::keyboard::{KeyboardState, Mod, Scancode};
+use sdl2::mouse::MouseWheelDirection;
+use sdl2::{EventPump, TimerSubsystem};
+use std::time::Duration;
 
+pub mod cursor;
 pub mod lossy;
 pub mod render;
 
 fn map_mouse_button(value: sdl2::mouse::MouseButton) -> Option<mouse::MouseButton> {
     use sdl2::mouse::MouseButton as MB;
     let out = match value {
-        MB::Unknown | MB::X1 | MB::X2 => return None,
+        MB::Unknown => return None,
         MB::Left => mouse::MouseButton::Left,
         MB::Middle => mouse::MouseButton::Middle,
         MB::Right => mouse::MouseButton::Right,
+        MB::X1 => mouse::MouseButton::X1,
+        MB::X2 => mouse::MouseButton::X2,
     };
     Some(out)
 }
 
-fn map_event(sdl_event: SdlEvent) -> Option<Event> {
+fn map_key(value: sdl2::keyboard::Keycode) -> Option<Key> {
+    use sdl2::keyboard::Keycode as KC;
+    let out = match value {
+        KC::Return | KC::KpEnter => Key::Enter,
+        KC::Escape => Key::Escape,
+        KC::Tab => Key::Tab,
+        KC::Backspace => Key::Backspace,
+        KC::Delete => Key::Delete,
+        KC::Insert => Key::Insert,
+        KC::Left => Key::Left,
+        KC::Right => Key::Right,
+        KC::Up => Key::Up,
+        KC::Down => Key::Down,
+        KC::Home => Key::Home,
+        KC::End => Key::End,
+        KC::PageUp => Key::PageUp,
+        KC::PageDown => Key::PageDown,
+        KC::F1 => Key::Function(1),
+        KC::F2 => Key::Function(2),
+        KC::F3 => Key::Function(3),
+        KC::F4 => Key::Function(4),
+        KC::F5 => Key::Function(5),
+        KC::F6 => Key::Function(6),
+        KC::F7 => Key::Function(7),
+        KC::F8 => Key::Function(8),
+        KC::F9 => Key::Function(9),
+        KC::F10 => Key::Function(10),
+        KC::F11 => Key::Function(11),
+        KC::F12 => Key::Function(12),
+        other => {
+            // Keycodes for printable keys are equal to their (unshifted) ASCII value.
+            let chr = char::from_u32(other as u32).filter(|c| c.is_ascii_graphic() || *c == ' ')?;
+            Key::Character(chr)
+        }
+    };
+    Some(out)
+}
+
+fn map_timestamp(value: u32) -> Timestamp {
+    Timestamp::from_millis(value.into())
+}
+
+fn map_keymod(value: Mod) -> Modifiers {
+    let mut out = Modifiers::NONE;
+    if value.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+        out.insert(Modifiers::SHIFT);
+    }
+    if value.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+        out.insert(Modifiers::CTRL);
+    }
+    if value.intersects(Mod::LALTMOD | Mod::RALTMOD) {
+        out.insert(Modifiers::ALT);
+    }
+    if value.intersects(Mod::LGUIMOD | Mod::RGUIMOD) {
+        out.insert(Modifiers::SUPER);
+    }
+    out
+}
+
+fn map_keyboard_state(value: &KeyboardState) -> Modifiers {
+    let mut out = Modifiers::NONE;
+    let pairs = [
+        (Scancode::LShift, Scancode::RShift, Modifiers::SHIFT),
+        (Scancode::LCtrl, Scancode::RCtrl, Modifiers::CTRL),
+        (Scancode::LAlt, Scancode::RAlt, Modifiers::ALT),
+        (Scancode::LGui, Scancode::RGui, Modifiers::SUPER),
+    ];
+    for (left, right, modifier) in pairs {
+        if value.is_scancode_pressed(left) || value.is_scancode_pressed(right) {
+            out.insert(modifier);
+        }
+    }
+    out
+}
+
+fn map_window_event(value: SdlWindowEvent) -> Option<WindowEvent> {
+    let out = match value {
+        // SDL sends `SizeChanged` for every size change, but `Resized` only for external ones.
+        SdlWindowEvent::SizeChanged(w, h) => WindowEvent::Resized((w, h).into()),
+        SdlWindowEvent::FocusGained => WindowEvent::FocusGained,
+        SdlWindowEvent::FocusLost => WindowEvent::FocusLost,
+        SdlWindowEvent::Minimized => WindowEvent::Minimized,
+        SdlWindowEvent::Restored => WindowEvent::Restored,
+        SdlWindowEvent::Leave => WindowEvent::PointerLeft,
+        SdlWindowEvent::Close => WindowEvent::CloseRequested,
+        _ => return None,
+    };
+    Some(out)
+}
+
+/// Maps an SDL event. Mouse events don't carry the keyboard modifiers in SDL and wheel events
+/// don't carry the pointer position, so the caller needs to provide the current `modifiers` and
+/// `pointer` position.
+fn map_event(sdl_event: SdlEvent, modifiers: Modifiers, pointer: Point) -> Option<Event> {
     let amu_event = match sdl_event {
-        SdlEvent::MouseMotion { x, y, .. } => Some(GlobalEvent::MouseMotion((x, y).into())),
+        SdlEvent::MouseMotion {
+            timestamp, x, y, ..
+        } => Some(GlobalEvent::MouseMotion(
+            (x, y).into(),
+            modifiers,
+            map_timestamp(timestamp),
+        )),
         SdlEvent::MouseButtonUp {
-            x, y, mouse_btn, ..
-        } => map_mouse_button(mouse_btn).map(|btn| GlobalEvent::MouseButtonUp(btn, (x, y).into())),
+            timestamp,
+            x,
+            y,
+            mouse_btn,
+            ..
+        } => map_mouse_button(mouse_btn).map(|btn| {
+            GlobalEvent::MouseButtonUp(btn, (x, y).into(), modifiers, map_timestamp(timestamp))
+        }),
         SdlEvent::MouseButtonDown {
-            x, y, mouse_btn, ..
+            timestamp,
+            x,
+            y,
+            mouse_btn,
+            ..
+        } => map_mouse_button(mouse_btn).map(|btn| {
+            GlobalEvent::MouseButtonDown(btn, (x, y).into(), modifiers, map_timestamp(timestamp))
+        }),
+        SdlEvent::MouseWheel {
+            timestamp,
+            x,
+            y,
+            direction,
+            ..
         } => {
-            map_mouse_button(mouse_btn).map(|btn| GlobalEvent::MouseButtonDown(btn, (x, y).into()))
+            let amount = match direction {
+                MouseWheelDirection::Flipped => Vector::new(-x, -y),
+                _ => Vector::new(x, y),
+            };
+            Some(GlobalEvent::MouseWheel(
+                amount,
+                pointer,
+                modifiers,
+                map_timestamp(timestamp),
+            ))
         }
+        SdlEvent::KeyDown {
+            timestamp,
+            keycode: Some(keycode),
+            keymod,
+            ..
+        } => match map_key(keycode) {
+            Some(key) => Some(GlobalEvent::KeyDown(
+                key,
+                map_keymod(keymod),
+                map_timestamp(timestamp),
+            )),
+            None => return Some(Event::Sdl(sdl_event)),
+        },
+        SdlEvent::KeyUp {
+            timestamp,
+            keycode: Some(keycode),
+            keymod,
+            ..
+        } => match map_key(keycode) {
+            Some(key) => Some(GlobalEvent::KeyUp(
+                key,
+                map_keymod(keymod),
+                map_timestamp(timestamp),
+            )),
+            None => return Some(Event::Sdl(sdl_event)),
+        },
+        SdlEvent::Window {
+            timestamp,
+            win_event,
+            ..
+        } => match map_window_event(win_event) {
+            Some(evt) => Some(GlobalEvent::Window(evt, map_timestamp(timestamp))),
+            None => return Some(Event::Sdl(sdl_event)),
+        },
         sdl_event => return Some(Event::Sdl(sdl_event)),
     };
 
@@ -40,25 +211,122 @@ pub enum Event {
 }
 
 struct EventIter<'a> {
-    pre_iter: std::array::IntoIter<Event, 1>,
+    pre_iter: std::vec::IntoIter<Event>,
+    pending: Option<SdlEvent>,
     event_iter: sdl2::event::EventPollIterator<'a>,
+    modifiers: Modifiers,
+    pointer: Point,
 }
 
 impl Iterator for EventIter<'_> {
     type Item = Event;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.pre_iter
-            .next()
-            .or_else(|| self.event_iter.next().and_then(map_event))
+        if let Some(event) = self.pre_iter.next() {
+            return Some(event);
+        }
+
+        // Skip the events that can't be mapped (e.g. unknown mouse buttons).
+        loop {
+            let sdl_event = self.pending.take().or_else(|| self.event_iter.next())?;
+            if let SdlEvent::KeyDown { keymod, .. } | SdlEvent::KeyUp { keymod, .. } = &sdl_event {
+                self.modifiers = map_keymod(*keymod);
+            }
+            if let SdlEvent::MouseMotion { x, y, .. } = &sdl_event {
+                self.pointer = Point::new(*x, *y);
+            }
+
+            if let Some(event) = map_event(sdl_event, self.modifiers, self.pointer) {
+                return Some(event);
+            }
+        }
     }
 }
 
-pub fn event_iterator(event_pump: &mut EventPump) -> impl Iterator<Item = Event> + '_ {
-    let pre_iter = [Event::Amulet(GlobalEvent::LoopStart)].into_iter();
-    let event_iter = event_pump.poll_iter();
-    EventIter {
-        pre_iter,
-        event_iter,
+/// Produces the events for the iterations of the event loop.
+pub struct EventSource {
+    timer: TimerSubsystem,
+    timers: Timers,
+    last_loop_start: Option<Timestamp>,
+}
+
+impl EventSource {
+    /// Creates a new instance. The `timer` provides the current time using the same clock as the
+    /// timestamps of the SDL events. The `timers` are checked on every iteration.
+    pub fn new(timer: TimerSubsystem, timers: Timers) -> Self {
+        Self {
+            timer,
+            timers,
+            last_loop_start: None,
+        }
+    }
+
+    pub fn timers(&self) -> &Timers {
+        &self.timers
+    }
+
+    /// Creates an iterator over the events for one iteration of the event loop.
+    ///
+    /// This starts with a [`GlobalEvent::LoopStart`], followed by a [`GlobalEvent::Timer`] for
+    /// every timer that went off and finally the pending SDL events.
+    pub fn poll<'a>(&mut self, event_pump: &'a mut EventPump) -> impl Iterator<Item = Event> + 'a {
+        self.iterate(event_pump, None)
+    }
+
+    /// Like [`Self::poll()`], but first blocks until there is an SDL event or a timer goes off.
+    ///
+    /// This does not block while the [`Timers`] are requested to keep the loop awake.
+    pub fn wait<'a>(&mut self, event_pump: &'a mut EventPump) -> impl Iterator<Item = Event> + 'a {
+        let now = map_timestamp(self.timer.ticks());
+        let pending = if self.timers.is_awake(now) {
+            None
+        } else {
+            match self.timers.next_deadline() {
+                Some(deadline) => {
+                    let timeout = deadline.duration_since(now).as_millis();
+                    event_pump.wait_event_timeout(u32::try_from(timeout).unwrap_or(u32::MAX))
+                }
+                None => Some(event_pump.wait_event()),
+            }
+        };
+
+        self.iterate(event_pump, pending)
+    }
+
+    fn iterate<'a>(
+        &mut self,
+        event_pump: &'a mut EventPump,
+        pending: Option<SdlEvent>,
+    ) -> EventIter<'a> {
+        let now = map_timestamp(self.timer.ticks());
+        let delta = self
+            .last_loop_start
+            .map_or(Duration::ZERO, |last| now.duration_since(last));
+        self.last_loop_start = Some(now);
+
+        let pre_iter = std::iter::once(GlobalEvent::LoopStart(now, delta))
+            .chain(
+                self.timers
+                    .fire(now)
+                    .into_iter()
+                    .map(|id| GlobalEvent::Timer(id, now)),
+            )
+            .map(Event::Amulet)
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let modifiers = map_keyboard_state(&event_pump.keyboard_state());
+        let pointer = {
+            let mouse_state = event_pump.mouse_state();
+            Point::new(mouse_state.x(), mouse_state.y())
+        };
+        let event_iter = event_pump.poll_iter();
+        EventIter {
+            pre_iter,
+            pending,
+            event_iter,
+            modifiers,
+            pointer,
+        }
     }
 }
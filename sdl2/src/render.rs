@@ -1,5 +1,6 @@
 use crate::lossy::LossyInto;
 use amulet_core::component::{Layout, UpdateLayout};
+use amulet_core::geom::Rect;
 use amulet_core::VuiResult;
 use sdl2::render::{Canvas, WindowCanvas};
 use sdl2::video::Window;
@@ -13,6 +14,24 @@ pub trait Render {
         layout: Layout,
         render_context: &mut RenderContext,
     ) -> VuiResult<()>;
+
+    /// Renders content that belongs on the overlay layer, such as tooltips.
+    ///
+    /// The overlay pass runs after the regular pass has completed for the whole component tree,
+    /// so overlay content appears above everything else. Containers must forward this call to
+    /// their children.
+    ///
+    /// Components that opened an overlay through [`amulet_core::overlay::Overlays`] render its
+    /// content here, using a layout for the rectangle of the overlay. Overlays that belong to the
+    /// same component should be rendered in the order they were opened.
+    fn render_overlay(
+        &self,
+        _state: Self::State<'_>,
+        _layout: Layout,
+        _render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        Ok(())
+    }
 }
 
 impl<A, Z> Render for (A, Z)
@@ -33,6 +52,18 @@ where
         let layout = a.update_layout(a_state, layout)?;
         z.render(z_state, layout, render_context)
     }
+
+    fn render_overlay(
+        &self,
+        state: Self::State<'_>,
+        layout: Layout,
+        render_context: &mut RenderContext,
+    ) -> VuiResult<()> {
+        let (a, z) = self;
+        let (a_state, z_state) = state;
+        let layout = a.update_layout(a_state, layout)?;
+        z.render_overlay(z_state, layout, render_context)
+    }
 }
 
 pub struct RenderContext<'a> {
@@ -52,3 +83,11 @@ impl<'a> RenderContext<'a> {
         self.canvas
     }
 }
+
+impl RenderContext<'_> {
+    /// The rectangle covered by the window. Overlay content should be kept within it.
+    pub fn window_rect(&self) -> Rect {
+        let size: (i32, i32) = self.canvas.window().size().lossy_into();
+        Rect::from_size(size.into())
+    }
+}